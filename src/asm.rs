@@ -0,0 +1,880 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::cpu::{instruction_matrix, AddressingMode, Instruction, InstructionMnemonic};
+use crate::disasm::is_documented;
+use crate::error::{Error, Result};
+use crate::loader::Image;
+use crate::variant::CpuVariant;
+
+#[cfg(test)]
+#[path="./asm_test.rs"]
+mod asm_test;
+
+// Nesting limit of includes and macro expansions, catches recursion
+const MAX_DEPTH : usize = 32;
+
+// File name of source passed as a string in error messages
+const SOURCE_NAME : &str = "<source>";
+
+// Errors below the statement level are plain messages, the location of the
+// line is added when they are turned into an Error
+type Parse<T> = std::result::Result<T, String>;
+
+// Output of the assembler
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program {
+    pub image : Image,
+    // Labels and constants, truncated to 16 bits. Local labels are listed
+    // as "global@local".
+    pub symbols : BTreeMap<String, u16>,
+}
+
+// Two pass assembler for 6502 and 65C02 source in the ca65 syntax written
+// by the source module. It supports labels, @local labels scoped to the
+// label before them, constants (name = expr), expressions, .org, .byte,
+// .word, .res, .include, .setcpu and macros (.macro name args / .endmacro).
+//
+// Each instruction gets the first documented opcode for its mnemonic and
+// operand syntax, undocumented instructions are assembled as well. An
+// operand whose value is known to fit in the zero page when it is first
+// seen uses the zero page form, so forward references are absolute. The
+// a: and z: prefixes force either size, as in ca65.
+#[derive(Debug, Clone)]
+pub struct Assembler {
+    variant : CpuVariant,
+    defines : BTreeMap<String, i64>,
+}
+
+impl Default for Assembler {
+    fn default() -> Self {
+        Self::new(CpuVariant::default())
+    }
+}
+
+impl Assembler {
+    pub fn new(variant : CpuVariant) -> Assembler {
+        Assembler{ variant, defines : BTreeMap::new() }
+    }
+
+    // Predefines a symbol, e.g. the address of an I/O register
+    pub fn define(&mut self, name : &str, value : i64){
+        self.defines.insert(name.to_string(), value);
+    }
+
+    // Assembles source text. Included files are looked up relative to the
+    // working directory.
+    pub fn assemble(&self, source : &str) -> Result<Program> {
+        let mut pre = Preprocessor::default();
+        pre.file(SOURCE_NAME.to_string(), source, Path::new(""), 0)?;
+        self.passes(&pre)
+    }
+
+    // Assembles a file. Included files are looked up relative to the file
+    // that includes them.
+    pub fn assemble_file<P : AsRef<Path>>(&self, path : P) -> Result<Program> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut pre = Preprocessor::default();
+        pre.file(path.display().to_string(), &text, path.parent().unwrap_or(Path::new("")), 0)?;
+        self.passes(&pre)
+    }
+
+    fn passes(&self, pre : &Preprocessor) -> Result<Program> {
+        let mut symbols = self.defines.clone();
+        let mut modes = HashMap::new();
+        Pass::new(self.variant, &mut symbols, &mut modes, false).run(pre)?;
+        let image = Pass::new(self.variant, &mut symbols, &mut modes, true).run(pre)?;
+        let symbols = symbols.into_iter().map(|(name, value)| (name, value as u16)).collect();
+        Ok(Program{ image, symbols })
+    }
+}
+
+// A line of source with its comment removed, after includes and macros are
+// expanded
+#[derive(Clone)]
+struct SourceLine {
+    file : usize,
+    line : usize,
+    text : String,
+}
+
+struct Macro {
+    params : Vec<String>,
+    body : Vec<SourceLine>,
+}
+
+// Expands includes and macros into a flat list of lines
+#[derive(Default)]
+struct Preprocessor {
+    files : Vec<String>,
+    lines : Vec<SourceLine>,
+    macros : HashMap<String, Macro>,
+    // Number of macro expansions so far, makes the local labels of each
+    // expansion unique
+    expansions : usize,
+}
+
+impl Preprocessor {
+    fn error(&self, line : &SourceLine, message : String) -> Error {
+        Error::Assembly { file : self.files[line.file].clone(), line : line.line, message }
+    }
+
+    fn file(&mut self, name : String, text : &str, dir : &Path, depth : usize) -> Result<()> {
+        let file = self.files.len();
+        self.files.push(name);
+
+        let mut definition : Option<(String, Macro, SourceLine)> = None;
+        for (i, raw) in text.lines().enumerate() {
+            let line = SourceLine{ file, line : i + 1, text : strip_comment(raw).trim().to_string() };
+            let (label, rest) = split_label(&line.text);
+            let (word, args) = first_word(rest);
+            let word = word.to_ascii_lowercase();
+
+            if let Some((_, mac, _)) = &mut definition {
+                match word.as_str() {
+                    ".endmacro" | ".endm" => {
+                        let (name, mac, _) = definition.take().unwrap();
+                        self.macros.insert(name, mac);
+                    }
+                    ".macro" => return Err(self.error(&line, String::from("macros cannot be defined inside a macro"))),
+                    _ => mac.body.push(line),
+                }
+                continue;
+            }
+
+            match word.as_str() {
+                ".macro" => {
+                    let (name, params) = first_word(args);
+                    if label.is_some() || !is_symbol(name) || name.starts_with('@') {
+                        return Err(self.error(&line, String::from("expected a macro name")));
+                    }
+                    let params = split_list(params).into_iter().map(String::from).collect();
+                    definition = Some((name.to_string(), Macro{ params, body : Vec::new() }, line.clone()));
+                }
+                ".endmacro" | ".endm" => return Err(self.error(&line, String::from(".endmacro without .macro"))),
+                _ => self.statement(line, dir, depth)?,
+            }
+        }
+
+        match definition {
+            Some((name, _, line)) => Err(self.error(&line, format!("macro {} has no .endmacro", name))),
+            None => Ok(()),
+        }
+    }
+
+    // Adds a line, or the lines it includes or expands to
+    fn statement(&mut self, line : SourceLine, dir : &Path, depth : usize) -> Result<()> {
+        let (label, rest) = split_label(&line.text);
+        let (word, args) = first_word(rest);
+        let include = word.eq_ignore_ascii_case(".include");
+        if !include && !self.macros.contains_key(word) {
+            self.lines.push(line);
+            return Ok(());
+        }
+
+        if depth >= MAX_DEPTH {
+            return Err(self.error(&line, String::from("includes or macros are nested too deeply")));
+        }
+        if let Some(label) = label {
+            self.lines.push(SourceLine{ text : format!("{}:", label), ..line.clone() });
+        }
+
+        if include {
+            let name = string_literal(args).ok_or_else(|| self.error(&line, String::from("expected a file name in quotes")))?;
+            let path = dir.join(name);
+            let text = fs::read_to_string(&path)
+                .map_err(|e| self.error(&line, format!("cannot read {}: {}", path.display(), e)))?;
+            return self.file(path.display().to_string(), &text, path.parent().unwrap_or(Path::new("")), depth + 1);
+        }
+
+        let mac = &self.macros[word];
+        let args = split_list(args);
+        if args.len() != mac.params.len() {
+            return Err(self.error(&line, format!("macro {} takes {} arguments", word, mac.params.len())));
+        }
+        self.expansions += 1;
+        let mut names : HashMap<&str, String> = mac.params.iter()
+            .map(String::as_str)
+            .zip(args.iter().map(|arg| arg.to_string()))
+            .collect();
+        for body_line in &mac.body {
+            if let (Some(label), _) = split_label(&body_line.text) {
+                if label.starts_with('@') {
+                    names.insert(label, format!("{}_{}", label, self.expansions));
+                }
+            }
+        }
+        let body : Vec<SourceLine> = mac.body.iter()
+            .map(|body_line| SourceLine{ text : substitute(&body_line.text, &names), ..body_line.clone() })
+            .collect();
+        for body_line in body {
+            self.statement(body_line, dir, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+// Operand syntax of an instruction, before the addressing mode is chosen
+enum Operand<'t> {
+    None,
+    Accumulator,
+    Immediate(&'t str),
+    // Address with an optional index register, forced to a size by a: or z:
+    Direct { expr : &'t str, index : Option<char>, absolute : bool, zero_page : bool },
+    Indirect(&'t str),
+    IndirectX(&'t str),
+    IndirectY(&'t str),
+    // Zero page address and branch target of BBR and BBS
+    BitBranch(&'t str, &'t str),
+}
+
+impl<'t> Operand<'t> {
+    fn parse(mnem : InstructionMnemonic, text : &'t str) -> Parse<Operand<'t>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(Operand::None);
+        }
+        if matches!(mnem, InstructionMnemonic::InstrBBR | InstructionMnemonic::InstrBBS) {
+            return match split_list(text)[..] {
+                [zp, target] => Ok(Operand::BitBranch(zp, target)),
+                _ => Err(String::from("expected a zero page address and a branch target")),
+            };
+        }
+        if text.eq_ignore_ascii_case("a") {
+            return Ok(Operand::Accumulator);
+        }
+        if let Some(expr) = text.strip_prefix('#') {
+            return Ok(Operand::Immediate(expr));
+        }
+
+        let (base, index) = match split_list(text)[..] {
+            [base] => (base, None),
+            [base, index] if index.eq_ignore_ascii_case("x") => (base, Some('X')),
+            [base, index] if index.eq_ignore_ascii_case("y") => (base, Some('Y')),
+            _ => return Err(format!("invalid operand {}", text)),
+        };
+        if let Some(inner) = enclosed(base) {
+            return match (&split_list(inner)[..], index) {
+                (&[expr], None) => Ok(Operand::Indirect(expr)),
+                (&[expr, x], None) if x.eq_ignore_ascii_case("x") => Ok(Operand::IndirectX(expr)),
+                (&[expr], Some('Y')) => Ok(Operand::IndirectY(expr)),
+                _ => Err(format!("invalid operand {}", text)),
+            };
+        }
+
+        let prefix = base.get(..2).map(str::to_ascii_lowercase);
+        let (expr, absolute, zero_page) = match prefix.as_deref() {
+            Some("a:") => (&base[2..], true, false),
+            Some("z:") => (&base[2..], false, true),
+            _ => (base, false, false),
+        };
+        Ok(Operand::Direct{ expr, index, absolute, zero_page })
+    }
+
+    // Expression of an operand that has a single one
+    fn expr(&self) -> &'t str {
+        match *self {
+            Operand::Immediate(expr) | Operand::Direct { expr, .. } | Operand::Indirect(expr)
+                | Operand::IndirectX(expr) | Operand::IndirectY(expr) | Operand::BitBranch(expr, _) => expr,
+            Operand::None | Operand::Accumulator => "",
+        }
+    }
+}
+
+// One pass over the expanded source. The first pass finds the value of
+// every label and the addressing mode of every instruction, the second one
+// uses them to produce the image.
+struct Pass<'a> {
+    matrix : &'static [Instruction ; crate::NUM_INSTR],
+    symbols : &'a mut BTreeMap<String, i64>,
+    // Addressing mode chosen in the first pass, by line index
+    modes : &'a mut HashMap<usize, AddressingMode>,
+    last : bool,
+    pc : Option<u32>,
+    // Label local labels belong to
+    scope : String,
+    defined : HashSet<String>,
+    image : Image,
+}
+
+impl<'a> Pass<'a> {
+    fn new(variant : CpuVariant, symbols : &'a mut BTreeMap<String, i64>,
+           modes : &'a mut HashMap<usize, AddressingMode>, last : bool) -> Pass<'a> {
+        Pass{
+            matrix : instruction_matrix(variant),
+            symbols,
+            modes,
+            last,
+            pc : None,
+            scope : String::new(),
+            defined : HashSet::new(),
+            image : Image::new(),
+        }
+    }
+
+    fn run(mut self, pre : &Preprocessor) -> Result<Image> {
+        for (index, line) in pre.lines.iter().enumerate() {
+            self.statement(index, &line.text).map_err(|message| pre.error(line, message))?;
+        }
+        Ok(self.image)
+    }
+
+    fn statement(&mut self, index : usize, text : &str) -> Parse<()> {
+        let (label, rest) = split_label(text);
+        if let Some(label) = label {
+            let pc = self.pc()?;
+            if !label.starts_with('@') {
+                self.scope = label.to_string();
+            }
+            self.define(label, pc as i64)?;
+        }
+        if rest.is_empty() {
+            return Ok(());
+        }
+
+        if let Some((name, expr)) = constant(rest) {
+            // undefined in the first pass if it depends on a later symbol
+            if let Some(value) = self.eval(expr)? {
+                self.define(name, value)?;
+            }
+            return Ok(());
+        }
+        let (word, args) = first_word(rest);
+        if word.starts_with('.') {
+            self.directive(&word.to_ascii_lowercase(), args)
+        } else {
+            self.instruction(index, word, args)
+        }
+    }
+
+    fn pc(&self) -> Parse<u32> {
+        self.pc.ok_or_else(|| String::from("no .org before the first label, instruction or data"))
+    }
+
+    fn qualify(&self, name : &str) -> String {
+        if name.starts_with('@') { format!("{}{}", self.scope, name) } else { name.to_string() }
+    }
+
+    fn define(&mut self, name : &str, value : i64) -> Parse<()> {
+        let name = self.qualify(name);
+        if !self.defined.insert(name.clone()) {
+            return Err(format!("{} is defined more than once", name));
+        }
+        self.symbols.insert(name, value);
+        Ok(())
+    }
+
+    fn eval(&self, text : &str) -> Parse<Option<i64>> {
+        let mut eval = Eval{ pass : self, text : text.trim().as_bytes(), pos : 0 };
+        if eval.text.is_empty() {
+            return Err(String::from("expected a value"));
+        }
+        let value = eval.expr()?;
+        eval.skip_space();
+        if eval.pos < eval.text.len() {
+            return Err(format!("unexpected {}", String::from_utf8_lossy(&eval.text[eval.pos..])));
+        }
+        Ok(value)
+    }
+
+    // Value of an expression in the range min..=max. Unknown values in the
+    // first pass are taken as min.
+    fn value(&self, text : &str, min : i64, max : i64) -> Parse<i64> {
+        match self.eval(text)? {
+            None => Ok(min),
+            Some(value) if (min..=max).contains(&value) => Ok(value),
+            Some(value) => Err(format!("value {} is out of range", value)),
+        }
+    }
+
+    // Value of an expression that has to be known in the first pass, like
+    // the argument of .org
+    fn known(&self, text : &str, max : i64) -> Parse<i64> {
+        match self.eval(text)? {
+            None => Err(format!("{} has to be defined before it is used here", text.trim())),
+            Some(value) if (0..=max).contains(&value) => Ok(value),
+            Some(value) => Err(format!("value {} is out of range", value)),
+        }
+    }
+
+    fn byte(&self, text : &str) -> Parse<u8> {
+        Ok(self.value(text, -0x80, 0xFF)? as u8)
+    }
+
+    fn word(&self, text : &str) -> Parse<[u8 ; 2]> {
+        Ok((self.value(text, -0x8000, 0xFFFF)? as u16).to_le_bytes())
+    }
+
+    fn emit(&mut self, bytes : &[u8]) -> Parse<()> {
+        let pc = self.pc()?;
+        if pc as usize + bytes.len() > crate::MAX_MEM {
+            return Err(String::from("code runs past the end of memory"));
+        }
+        if self.last {
+            self.image.append(pc, bytes).map_err(|e| e.to_string())?;
+        }
+        self.pc = Some(pc + bytes.len() as u32);
+        Ok(())
+    }
+
+    fn directive(&mut self, name : &str, args : &str) -> Parse<()> {
+        match name {
+            ".org" => {
+                self.pc = Some(self.known(args, 0xFFFF)? as u32);
+                Ok(())
+            }
+            ".byte" => {
+                let mut bytes = Vec::new();
+                for item in split_list(args) {
+                    match string_literal(item) {
+                        Some(text) => bytes.extend_from_slice(text.as_bytes()),
+                        None => bytes.push(self.byte(item)?),
+                    }
+                }
+                self.emit(&bytes)
+            }
+            ".word" => {
+                let mut bytes = Vec::new();
+                for item in split_list(args) {
+                    bytes.extend_from_slice(&self.word(item)?);
+                }
+                self.emit(&bytes)
+            }
+            ".res" => {
+                let (count, fill) = match split_list(args)[..] {
+                    [count] => (count, 0),
+                    [count, fill] => (count, self.byte(fill)?),
+                    _ => return Err(String::from("expected a count and an optional fill value")),
+                };
+                let count = self.known(count, crate::MAX_MEM as i64)?;
+                self.emit(&vec![fill ; count as usize])
+            }
+            ".setcpu" => {
+                let variant = match string_literal(args).map(str::to_ascii_uppercase).as_deref() {
+                    Some("6502" | "6502X") => CpuVariant::Nmos6502,
+                    Some("65C02") => CpuVariant::Cmos65C02,
+                    _ => return Err(format!("unsupported CPU {}", args)),
+                };
+                self.matrix = instruction_matrix(variant);
+                Ok(())
+            }
+            _ => Err(format!("unknown directive {}", name)),
+        }
+    }
+
+    // Mnemonic and bit number of the BBR, BBS, RMB and SMB instructions
+    fn mnemonic(&self, word : &str) -> Parse<(InstructionMnemonic, Option<u8>)> {
+        let upper = word.to_ascii_uppercase();
+        let (name, bit) = match upper.as_bytes() {
+            [b'B', b'B', b'R' | b'S', bit @ b'0'..=b'7'] | [b'R' | b'S', b'M', b'B', bit @ b'0'..=b'7'] =>
+                (&upper[..3], Some(bit - b'0')),
+            _ => (upper.as_str(), None),
+        };
+        let mnem = self.matrix.iter()
+            .map(|instr| instr.mnem)
+            .find(|&mnem| mnem != InstructionMnemonic::InstrUndefined && mnem.to_string() == name)
+            .ok_or_else(|| format!("unknown instruction {}", word))?;
+
+        let has_bit = matches!(mnem, InstructionMnemonic::InstrBBR | InstructionMnemonic::InstrBBS
+            | InstructionMnemonic::InstrRMB | InstructionMnemonic::InstrSMB);
+        if has_bit && bit.is_none() {
+            return Err(format!("{} needs a bit number, e.g. {}0", word, name));
+        }
+        Ok((mnem, bit))
+    }
+
+    fn has_mode(&self, mnem : InstructionMnemonic, mode : AddressingMode) -> bool {
+        self.matrix.iter().any(|instr| instr.mnem == mnem && instr.mode == mode)
+    }
+
+    fn mode(&self, mnem : InstructionMnemonic, operand : &Operand) -> Parse<AddressingMode> {
+        let candidates : &[AddressingMode] = match *operand {
+            Operand::None => &[AddressingMode::AddrModeImplied, AddressingMode::AddrModeA],
+            Operand::Accumulator => &[AddressingMode::AddrModeA],
+            Operand::Immediate(_) => &[AddressingMode::AddrModeImmed],
+            Operand::Indirect(_) => &[AddressingMode::AddrModeIndirect, AddressingMode::AddrModeZPInd],
+            Operand::IndirectX(_) => &[AddressingMode::AddrModeABSIndX, AddressingMode::AddrModeIndX],
+            Operand::IndirectY(_) => &[AddressingMode::AddrModeIndY],
+            Operand::BitBranch(..) => &[AddressingMode::AddrModeZPRelative],
+            Operand::Direct { expr, index, absolute, zero_page } => {
+                if index.is_none() && self.has_mode(mnem, AddressingMode::AddrModeRelative) {
+                    return Ok(AddressingMode::AddrModeRelative);
+                }
+                let (zp, abs) = match index {
+                    None => (AddressingMode::AddrModeZP, AddressingMode::AddrModeABS),
+                    Some('X') => (AddressingMode::AddrModeZPX, AddressingMode::AddrModeABSX),
+                    _ => (AddressingMode::AddrModeZPY, AddressingMode::AddrModeABSY),
+                };
+                let fits = zero_page || (!absolute && matches!(self.eval(expr)?, Some(0..=0xFF)));
+                let only_zp = !absolute && !self.has_mode(mnem, abs);
+                if self.has_mode(mnem, zp) && (fits || only_zp) {
+                    return Ok(zp);
+                }
+                &[abs]
+            }
+        };
+        candidates.iter()
+            .copied()
+            .find(|&mode| self.has_mode(mnem, mode))
+            .ok_or_else(|| format!("{} does not support this addressing mode", mnem))
+    }
+
+    // Opcode of an instruction, documented ones first
+    fn opcode(&self, mnem : InstructionMnemonic, mode : AddressingMode, bit : Option<u8>) -> Option<u8> {
+        (0..crate::NUM_INSTR)
+            .filter(|&op| {
+                let instr = self.matrix[op];
+                instr.mnem == mnem && instr.mode == mode && bit.is_none_or(|bit| (op >> 4) & 0x07 == bit as usize)
+            })
+            .min_by_key(|&op| !is_documented(op as u8, self.matrix[op]))
+            .map(|op| op as u8)
+    }
+
+    // Offset of a relative branch from next, the address after it
+    fn branch(&self, target : &str, next : u32) -> Parse<u8> {
+        let Some(target) = self.eval(target)? else {
+            return Ok(0);
+        };
+        let offset = target - next as i64;
+        if !(-0x80..=0x7F).contains(&offset) {
+            return Err(format!("branch target is {} bytes away", offset));
+        }
+        Ok(offset as u8)
+    }
+
+    fn instruction(&mut self, index : usize, word : &str, args : &str) -> Parse<()> {
+        let (mnem, bit) = self.mnemonic(word)?;
+        let pc = self.pc()?;
+        let operand = Operand::parse(mnem, args)?;
+        let mode = match self.modes.get(&index) {
+            Some(&mode) if self.last => mode,
+            _ => self.mode(mnem, &operand)?,
+        };
+        self.modes.insert(index, mode);
+        let opcode = self.opcode(mnem, mode, bit)
+            .ok_or_else(|| format!("{} does not support this addressing mode", mnem))?;
+
+        let expr = operand.expr();
+        let mut bytes = vec![opcode];
+        match mode {
+            AddressingMode::AddrModeUndefined | AddressingMode::AddrModeImplied | AddressingMode::AddrModeA => {}
+            AddressingMode::AddrModeImmed => bytes.push(self.byte(expr)?),
+            AddressingMode::AddrModeZP | AddressingMode::AddrModeZPX | AddressingMode::AddrModeZPY
+                | AddressingMode::AddrModeIndX | AddressingMode::AddrModeIndY
+                | AddressingMode::AddrModeZPInd => bytes.push(self.value(expr, 0, 0xFF)? as u8),
+            AddressingMode::AddrModeABS | AddressingMode::AddrModeABSX | AddressingMode::AddrModeABSY
+                | AddressingMode::AddrModeIndirect | AddressingMode::AddrModeABSIndX =>
+                bytes.extend_from_slice(&(self.value(expr, 0, 0xFFFF)? as u16).to_le_bytes()),
+            AddressingMode::AddrModeRelative => bytes.push(self.branch(expr, pc + 2)?),
+            AddressingMode::AddrModeZPRelative => {
+                let Operand::BitBranch(zp, target) = operand else {
+                    unreachable!();
+                };
+                bytes.push(self.value(zp, 0, 0xFF)? as u8);
+                bytes.push(self.branch(target, pc + 3)?);
+            }
+            // only .setcpu "6502" and "65C02" are supported, mode() never
+            // picks a 65C816 mode
+            AddressingMode::AddrModeABSLong | AddressingMode::AddrModeABSLongX | AddressingMode::AddrModeABSIndLong
+                | AddressingMode::AddrModeBlockMove | AddressingMode::AddrModeZPIndLong
+                | AddressingMode::AddrModeZPIndLongY | AddressingMode::AddrModeImmedM | AddressingMode::AddrModeImmedX
+                | AddressingMode::AddrModeRelativeLong | AddressingMode::AddrModeStackRel
+                | AddressingMode::AddrModeStackRelIndY => unreachable!(),
+        }
+        self.emit(&bytes)
+    }
+}
+
+// Expression evaluator. From lowest to highest precedence: |, ^, &, << and
+// >>, + and -, * and /, then the unary -, ~, +, < (low byte), > (high byte)
+// and ^ (bank byte). As in ca65, #<label+1 is (<label)+1.
+// Values depending on a symbol that is not defined yet are None in the
+// first pass.
+struct Eval<'p, 'a> {
+    pass : &'p Pass<'a>,
+    text : &'p [u8],
+    pos : usize,
+}
+
+// Binary operators by precedence, lowest first
+const OPERATORS : [&[&str] ; 6] = [&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/"]];
+
+impl Eval<'_, '_> {
+    fn skip_space(&mut self){
+        while self.text.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_space();
+        self.text.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, token : &str) -> bool {
+        self.skip_space();
+        let found = self.text[self.pos..].starts_with(token.as_bytes());
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expr(&mut self) -> Parse<Option<i64>> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level : usize) -> Parse<Option<i64>> {
+        if level == OPERATORS.len() {
+            return self.unary();
+        }
+        let mut value = self.binary(level + 1)?;
+        'next : loop {
+            for op in OPERATORS[level] {
+                if self.eat(op) {
+                    let rhs = self.binary(level + 1)?;
+                    value = match (value, rhs) {
+                        (Some(lhs), Some(rhs)) => Some(apply(op, lhs, rhs)?),
+                        _ => None,
+                    };
+                    continue 'next;
+                }
+            }
+            return Ok(value);
+        }
+    }
+
+    fn unary(&mut self) -> Parse<Option<i64>> {
+        if self.eat("-") {
+            return Ok(self.unary()?.map(i64::wrapping_neg));
+        }
+        if self.eat("~") {
+            return Ok(self.unary()?.map(|value| !value));
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("<") {
+            return Ok(self.unary()?.map(|value| value & 0xFF));
+        }
+        if self.eat(">") {
+            return Ok(self.unary()?.map(|value| (value >> 8) & 0xFF));
+        }
+        if self.eat("^") {
+            return Ok(self.unary()?.map(|value| (value >> 16) & 0xFF));
+        }
+        if self.eat("(") {
+            let value = self.expr()?;
+            if !self.eat(")") {
+                return Err(String::from("missing )"));
+            }
+            return Ok(value);
+        }
+        self.primary()
+    }
+
+    fn digits(&mut self, radix : u32) -> Parse<Option<i64>> {
+        let start = self.pos;
+        while self.text.get(self.pos).is_some_and(|c| (*c as char).is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.text[start..self.pos]).unwrap();
+        i64::from_str_radix(text, radix).map(Some).map_err(|_| format!("invalid number {}", text))
+    }
+
+    fn primary(&mut self) -> Parse<Option<i64>> {
+        match self.peek() {
+            Some(b'$') => {
+                self.pos += 1;
+                self.digits(16)
+            }
+            Some(b'%') => {
+                self.pos += 1;
+                self.digits(2)
+            }
+            Some(b'0'..=b'9') => self.digits(10),
+            Some(b'\'') => match self.text.get(self.pos..self.pos + 3) {
+                Some([_, c, b'\'']) => {
+                    self.pos += 3;
+                    Ok(Some(*c as i64))
+                }
+                _ => Err(String::from("invalid character constant")),
+            },
+            Some(b'*') => {
+                self.pos += 1;
+                self.pass.pc().map(|pc| Some(pc as i64))
+            }
+            Some(c) if c == b'@' || c == b'_' || c.is_ascii_alphabetic() => {
+                let start = self.pos;
+                self.pos += 1;
+                while self.text.get(self.pos).is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_') {
+                    self.pos += 1;
+                }
+                let name = std::str::from_utf8(&self.text[start..self.pos]).unwrap();
+                match self.pass.symbols.get(&self.pass.qualify(name)) {
+                    Some(&value) => Ok(Some(value)),
+                    None if self.pass.last => Err(format!("undefined symbol {}", name)),
+                    None => Ok(None),
+                }
+            }
+            Some(c) => Err(format!("unexpected {}", c as char)),
+            None => Err(String::from("expected a value")),
+        }
+    }
+}
+
+fn apply(op : &str, lhs : i64, rhs : i64) -> Parse<i64> {
+    Ok(match op {
+        "|" => lhs | rhs,
+        "^" => lhs ^ rhs,
+        "&" => lhs & rhs,
+        "<<" => lhs.checked_shl(rhs.try_into().map_err(|_| "negative shift")?).unwrap_or(0),
+        ">>" => lhs.checked_shr(rhs.try_into().map_err(|_| "negative shift")?).unwrap_or(0),
+        "+" => lhs.wrapping_add(rhs),
+        "-" => lhs.wrapping_sub(rhs),
+        "*" => lhs.wrapping_mul(rhs),
+        _ => lhs.checked_div(rhs).ok_or("division by zero")?,
+    })
+}
+
+fn is_symbol(name : &str) -> bool {
+    let name = name.strip_prefix('@').unwrap_or(name);
+    name.starts_with(|c : char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Splits "label: rest" into the label and the rest
+fn split_label(text : &str) -> (Option<&str>, &str) {
+    let end = text.find(|c : char| !(c.is_ascii_alphanumeric() || c == '_' || c == '@')).unwrap_or(text.len());
+    let name = &text[..end];
+    match text[end..].strip_prefix(':') {
+        Some(rest) if is_symbol(name) => (Some(name), rest.trim()),
+        _ => (None, text),
+    }
+}
+
+// Splits "name = expr" into the name and the expression
+fn constant(text : &str) -> Option<(&str, &str)> {
+    let (name, expr) = text.split_once('=')?;
+    let name = name.trim();
+    is_symbol(name).then_some((name, expr))
+}
+
+fn first_word(text : &str) -> (&str, &str) {
+    match text.split_once(|c : char| c.is_ascii_whitespace()) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+// Contents of a "string"
+fn string_literal(text : &str) -> Option<&str> {
+    let text = text.trim();
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    (!inner.contains('"')).then_some(inner)
+}
+
+// Calls f with the index, character and parenthesis depth of each character
+// of text outside of string and character constants, until it returns true
+fn scan(text : &str, mut f : impl FnMut(usize, char, usize) -> bool){
+    let mut quote = None;
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => {
+                match c {
+                    '"' | '\'' => quote = Some(c),
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                if f(i, c, depth) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn strip_comment(text : &str) -> &str {
+    let mut end = text.len();
+    scan(text, |i, c, _| {
+        if c == ';' {
+            end = i;
+        }
+        c == ';'
+    });
+    &text[..end]
+}
+
+// Splits a comma separated list, leaving commas in parentheses and strings
+fn split_list(text : &str) -> Vec<&str> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    let mut items = Vec::new();
+    let mut start = 0;
+    scan(text, |i, c, depth| {
+        if c == ',' && depth == 0 {
+            items.push(text[start..i].trim());
+            start = i + 1;
+        }
+        false
+    });
+    items.push(text[start..].trim());
+    items
+}
+
+// Inside of text if all of it is in one pair of parentheses
+fn enclosed(text : &str) -> Option<&str> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    let mut close = None;
+    scan(text, |i, c, depth| {
+        if c == ')' && depth == 0 {
+            close = Some(i);
+        }
+        close.is_some()
+    });
+    (close? == text.len() - 1).then(|| &text[1..text.len() - 1])
+}
+
+// Replaces the symbols in text that are keys of names
+fn substitute(text : &str, names : &HashMap<&str, String>) -> String {
+    let mut result = String::new();
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if let Some(q) = quote {
+            quote = (c != q).then_some(q);
+            result.push(c);
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            quote = Some(c);
+            result.push(c);
+            continue;
+        }
+        if !(c.is_ascii_alphanumeric() || c == '_' || c == '@' || c == '.' || c == '$' || c == '%') {
+            result.push(c);
+            continue;
+        }
+        // a whole word, so numbers and directives are never replaced
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if !(next.is_ascii_alphanumeric() || next == '_') {
+                break;
+            }
+            end = j + next.len_utf8();
+            chars.next();
+        }
+        let word = &text[i..end];
+        result.push_str(names.get(word).map_or(word, String::as_str));
+    }
+    result
+}
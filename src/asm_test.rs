@@ -0,0 +1,179 @@
+use std::fs;
+
+use super::*;
+use crate::source::Analysis;
+
+fn assemble(variant : CpuVariant, source : &str) -> Result<Program> {
+    Assembler::new(variant).assemble(source)
+}
+
+fn bytes(program : &Program) -> Vec<u8> {
+    program.image.segments.iter().flat_map(|segment| segment.data.iter().copied()).collect()
+}
+
+#[test]
+fn test_addressing_modes(){
+    let program = assemble(CpuVariant::Nmos6502, "
+        .org $0200
+zp = $10
+start:  LDA #$42
+        lda zp          ; zero page
+        LDA a:zp        ; forced absolute
+        LDA zp,X
+        LDX zp,y
+        LDA zp,Y        ; no zero page,Y form
+        LDA ($20,X)
+        LDA ( $20 ),Y
+        LDA later       ; forward reference
+        JMP ($FFFC)
+        ASL
+        ASL A
+        BNE start
+later:  NOP
+        SBC #1
+        LAX $30
+").unwrap();
+    assert_eq!(program.image.segments.len(), 1);
+    assert_eq!(program.image.segments[0].address, 0x0200);
+    assert_eq!(bytes(&program), [
+        0xA9, 0x42, 0xA5, 0x10, 0xAD, 0x10, 0x00, 0xB5, 0x10, 0xB6, 0x10, 0xB9, 0x10, 0x00,
+        0xA1, 0x20, 0xB1, 0x20, 0xAD, 0x1C, 0x02, 0x6C, 0xFC, 0xFF, 0x0A, 0x0A, 0xD0, 0xE4,
+        0xEA, 0xE9, 0x01, 0xA7, 0x30,
+    ]);
+    assert_eq!(program.symbols["later"], 0x021C);
+}
+
+#[test]
+fn test_65c02(){
+    let source = "
+        .setcpu \"65C02\"
+        .org $0200
+top:    LDA ($20)
+        JMP ($8000,X)
+        BBS1 $12, top
+        SMB7 $12
+        STZ $2000
+        BRA top
+";
+    let program = assemble(CpuVariant::Nmos6502, source).unwrap();
+    assert_eq!(bytes(&program), [
+        0xB2, 0x20, 0x7C, 0x00, 0x80, 0x9F, 0x12, 0xF8, 0xF7, 0x12, 0x9C, 0x00, 0x20, 0x80, 0xF1,
+    ]);
+
+    let err = assemble(CpuVariant::Nmos6502, ".org 0\n  BRA *\n").unwrap_err();
+    assert_eq!(err.to_string(), "<source>:2: unknown instruction BRA");
+}
+
+#[test]
+fn test_labels_and_expressions(){
+    let program = assemble(CpuVariant::Nmos6502, "
+ptr = $FB
+        .org $C000
+reset:  LDA #<message
+        STA ptr
+        LDA #>message
+        STA ptr+1
+@loop:  LDA (ptr),Y
+        BEQ @done
+        INY
+        BNE @loop
+@done:  RTS
+print:
+@loop:  DEX
+        BNE @loop
+        RTS
+message: .byte \"HI\", 0, 'A' | $80, -1
+        .word reset, message + 2, *
+        .res 3, $EA
+        .res 2
+size = * - message
+").unwrap();
+    assert_eq!(bytes(&program), [
+        0xA9, 0x14, 0x85, 0xFB, 0xA9, 0xC0, 0x85, 0xFC, 0xB1, 0xFB, 0xF0, 0x03, 0xC8, 0xD0, 0xF9,
+        0x60, 0xCA, 0xD0, 0xFD, 0x60,
+        0x48, 0x49, 0x00, 0xC1, 0xFF, 0x00, 0xC0, 0x16, 0xC0, 0x19, 0xC0, 0xEA, 0xEA, 0xEA, 0x00, 0x00,
+    ]);
+    assert_eq!(program.symbols["reset@loop"], 0xC008);
+    assert_eq!(program.symbols["print@loop"], 0xC010);
+    assert_eq!(program.symbols["size"], 0x10);
+    assert_eq!(program.symbols["ptr"], 0xFB);
+}
+
+#[test]
+fn test_byte_operator_precedence(){
+    // < and > bind tighter than the binary operators, like in ca65
+    let program = assemble(CpuVariant::Nmos6502, "
+table = $10FE
+        .org $0200
+        LDA #<(table+1)
+        LDA #>table+1
+        LDA #<(table+2)
+        .byte ^$123456, <table | 1
+").unwrap();
+    assert_eq!(bytes(&program), [0xA9, 0xFF, 0xA9, 0x11, 0xA9, 0x00, 0x12, 0xFF]);
+
+    let err = assemble(CpuVariant::Nmos6502, "table = $10FF\n.org $0200\n  LDA #<table+1").unwrap_err();
+    assert_eq!(err.to_string(), "<source>:3: value 256 is out of range");
+}
+
+#[test]
+fn test_macros_and_include(){
+    let dir = std::env::temp_dir().join(format!("rs6502-asm-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("macros.inc"), "
+; copies a byte, waiting for it to change first
+.macro copy from, to
+@wait:  LDA from
+        BEQ @wait
+        STA to
+.endmacro
+").unwrap();
+    fs::write(dir.join("main.s"), "
+        .include \"macros.inc\"
+        .org $0300
+first:  copy $10, $0400
+        copy io, $11
+").unwrap();
+
+    let mut asm = Assembler::default();
+    asm.define("io", 0xD012);
+    let program = asm.assemble_file(dir.join("main.s")).unwrap();
+    assert_eq!(bytes(&program), [
+        0xA5, 0x10, 0xF0, 0xFC, 0x8D, 0x00, 0x04,
+        0xAD, 0x12, 0xD0, 0xF0, 0xFB, 0x85, 0x11,
+    ]);
+    assert_eq!(program.symbols["first@wait_1"], 0x0300);
+    assert_eq!(program.symbols["first@wait_2"], 0x0307);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_errors(){
+    let message = |source : &str| assemble(CpuVariant::Nmos6502, source).unwrap_err().to_string();
+    assert_eq!(message("  LDA #1"), "<source>:1: no .org before the first label, instruction or data");
+    assert_eq!(message(".org $200\n  JMP nowhere"), "<source>:2: undefined symbol nowhere");
+    assert_eq!(message(".org $200\nx: NOP\nx: NOP"), "<source>:3: x is defined more than once");
+    assert_eq!(message(".org $200\n  BNE far\n  .res 200\nfar: RTS"), "<source>:2: branch target is 200 bytes away");
+    assert_eq!(message(".org $200\n  LDA #256"), "<source>:2: value 256 is out of range");
+    assert_eq!(message(".org $200\n  STX $1234,Y"), "<source>:2: value 4660 is out of range");
+    assert_eq!(message(".org $FFFF\n  .word 0"), "<source>:2: code runs past the end of memory");
+    assert_eq!(message(".org $200\n  .res later\nlater:"), "<source>:2: later has to be defined before it is used here");
+    assert_eq!(message(".macro m\n m\n.endmacro\n.org 0\n m"), "<source>:2: includes or macros are nested too deeply");
+}
+
+#[test]
+fn test_reassemble_source(){
+    // the output of the recursive descent disassembler assembles back to
+    // the bytes it came from
+    let rom = [
+        0xA2, 0xFF, 0x9A, 0x20, 0xEE, 0xFF, 0xAD, 0xEC, 0xFF, 0x4C, 0xE3, 0xFF, 0x12, 0x34, 0x8D, 0x11,
+        0x00, 0xEE, 0xE7, 0xFF, 0xF0, 0x02, 0x02, 0xFF, 0x60, 0xEA, 0xF8, 0xFF, 0xE0, 0xFF, 0xF8, 0xFF,
+    ];
+    let source = Analysis::new(&rom, 0xFFE0, CpuVariant::Nmos6502, &[]).unwrap().to_source();
+    let program = assemble(CpuVariant::Nmos6502, &source).unwrap();
+    assert_eq!(program.image, Image::from_binary(&rom, 0xFFE0).unwrap());
+
+    let code = [0xB2, 0x20, 0x1F, 0x20, 0xFB, 0x80, 0xF9, 0x44, 0x00];
+    let source = Analysis::new(&code, 0x0200, CpuVariant::Cmos65C02, &[0x0200]).unwrap().to_source();
+    assert_eq!(bytes(&assemble(CpuVariant::Nmos6502, &source).unwrap()), code);
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+#[path="./bus_test.rs"]
+mod bus_test;
+
+// Level of the R/W pin during a bus cycle
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Read,
+    Write,
+}
+
+// A single cycle of bus activity. SYNC is high while an opcode is fetched.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BusCycle {
+    pub direction : Direction,
+    pub address : u16,
+    pub data : u8,
+    pub sync : bool,
+}
+
+// Memory map seen by the CPU. Every access the CPU makes goes through read()
+// and write(), so implementations are free to model ROM, mirrors or
+// memory-mapped hardware behind any address.
+pub trait Bus {
+    // Read performed by the CPU, may have side effects (e.g. clear on read)
+    fn read(&mut self, address : u16) -> u8;
+
+    // Write performed by the CPU
+    fn write(&mut self, address : u16, data : u8);
+
+    // Side-effect free read used by debuggers and memory dumps
+    fn peek(&self, address : u16) -> u8;
+
+    // Write used when loading images; unlike write() it should also
+    // reach read-only regions such as ROM
+    fn poke(&mut self, address : u16, data : u8){
+        self.write(address, data);
+    }
+
+    // Whether anything responds at the given address
+    fn is_mapped(&self, _address : u16) -> bool {
+        true
+    }
+
+    // Called after every bus cycle when the CPU runs in cycle accurate mode
+    fn cycle(&mut self, _cycle : BusCycle){}
+
+    // Interrupt lines driven by devices on the bus, asserted while true.
+    // They are sampled once per cycle.
+    fn irq(&self) -> bool {
+        false
+    }
+
+    fn nmi(&self) -> bool {
+        false
+    }
+
+    // Called with the new pin levels whenever a 6510 changes its I/O port,
+    // e.g. to switch the ROM banks of a C64. Also called on reset.
+    fn io_port(&mut self, _pins : u8){}
+}
+
+// Plain 64 KiB of RAM, the default memory map of the CPU
+pub struct FlatRam {
+    mem : Box<[u8 ; crate::MAX_MEM]>,
+}
+
+impl FlatRam {
+    pub fn new() -> FlatRam {
+        FlatRam{ mem : Box::new([0 ; crate::MAX_MEM]) }
+    }
+}
+
+impl Default for FlatRam {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus for FlatRam {
+    fn read(&mut self, address : u16) -> u8 {
+        self.mem[address as usize]
+    }
+
+    fn write(&mut self, address : u16, data : u8){
+        self.mem[address as usize] = data;
+    }
+
+    fn peek(&self, address : u16) -> u8 {
+        self.mem[address as usize]
+    }
+}
+
+// Memory map of the 24 bit address space of the 65C816. Addresses passed in
+// are always below $1000000.
+pub trait Bus24 {
+    fn read(&mut self, address : u32) -> u8;
+
+    fn write(&mut self, address : u32, data : u8);
+
+    fn peek(&self, address : u32) -> u8;
+
+    fn poke(&mut self, address : u32, data : u8){
+        self.write(address, data);
+    }
+
+    fn is_mapped(&self, _address : u32) -> bool {
+        true
+    }
+
+    fn irq(&self) -> bool {
+        false
+    }
+
+    fn nmi(&self) -> bool {
+        false
+    }
+}
+
+// Plain 16 MiB of RAM, the default memory map of the 65C816
+pub struct FlatRam24 {
+    mem : Vec<u8>,
+}
+
+impl FlatRam24 {
+    pub fn new() -> FlatRam24 {
+        FlatRam24{ mem : vec![0 ; crate::MAX_MEM_24] }
+    }
+}
+
+impl Default for FlatRam24 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus24 for FlatRam24 {
+    fn read(&mut self, address : u32) -> u8 {
+        self.mem[address as usize]
+    }
+
+    fn write(&mut self, address : u32, data : u8){
+        self.mem[address as usize] = data;
+    }
+
+    fn peek(&self, address : u32) -> u8 {
+        self.mem[address as usize]
+    }
+}
@@ -0,0 +1,140 @@
+use super::*;
+
+// 16 KiB of RAM mirrored four times with a read-only page at $F000
+struct MirroredRam {
+    ram : [u8 ; 0x4000],
+    rom : [u8 ; 0x1000],
+}
+
+impl Bus for MirroredRam {
+    fn read(&mut self, address : u16) -> u8 {
+        self.peek(address)
+    }
+
+    fn write(&mut self, address : u16, data : u8){
+        if address < 0xF000 {
+            self.ram[(address & 0x3FFF) as usize] = data;
+        }
+    }
+
+    fn peek(&self, address : u16) -> u8 {
+        match address {
+            0xF000..=0xFFFF => self.rom[(address & 0x0FFF) as usize],
+            _ => self.ram[(address & 0x3FFF) as usize],
+        }
+    }
+
+    fn poke(&mut self, address : u16, data : u8){
+        match address {
+            0xF000..=0xFFFF => self.rom[(address & 0x0FFF) as usize] = data,
+            _ => self.write(address, data),
+        }
+    }
+}
+
+#[test]
+fn test_flat_ram(){
+    let mut ram = FlatRam::new();
+    ram.write(0xFFFF, 0x42);
+    assert_eq!(ram.read(0xFFFF), 0x42);
+    assert_eq!(ram.peek(0x0000), 0x00);
+}
+
+#[test]
+fn test_cpu_on_custom_bus(){
+    let bus = MirroredRam{ ram : [0 ; 0x4000], rom : [0 ; 0x1000] };
+    let mut cpu = crate::CPU::with_bus(bus);
+    // LDA #$55; STA $4010; STA $F100
+    cpu.mount_mem(0xF000, &[0xA9, 0x55, 0x8D, 0x10, 0x40, 0x8D, 0x00, 0xF1]).unwrap();
+    cpu.set_pc(0xF000);
+    cpu.run(10);
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x55);
+    assert_eq!(cpu.read_mem(0xC010).unwrap(), 0x55);
+    assert_eq!(cpu.read_mem(0xF100).unwrap(), 0x00);
+}
+
+// Only the lower 32 KiB respond
+struct HalfBus(FlatRam);
+
+impl Bus for HalfBus {
+    fn read(&mut self, address : u16) -> u8 { self.0.read(address) }
+    fn write(&mut self, address : u16, data : u8){ self.0.write(address, data) }
+    fn peek(&self, address : u16) -> u8 { self.0.peek(address) }
+    fn is_mapped(&self, address : u16) -> bool { address < 0x8000 }
+}
+
+#[test]
+fn test_unmapped_addresses(){
+    let mut cpu = crate::CPU::with_bus(HalfBus(FlatRam::new()));
+    assert!(matches!(cpu.read_mem(0x8000), Err(crate::Error::UnmappedAddress(0x8000))));
+    assert!(matches!(cpu.mount_mem(0x7FFF, &[1, 2]), Err(crate::Error::UnmappedAddress(0x8000))));
+    assert_eq!(cpu.read_mem(0x7FFF).unwrap(), 0);
+    assert!(cpu.write_mem(0x7FFF, 1).is_ok());
+}
+
+// Records every bus cycle reported by the CPU
+struct TraceBus {
+    ram : FlatRam,
+    trace : Vec<BusCycle>,
+}
+
+impl Bus for TraceBus {
+    fn read(&mut self, address : u16) -> u8 { self.ram.read(address) }
+    fn write(&mut self, address : u16, data : u8){ self.ram.write(address, data) }
+    fn peek(&self, address : u16) -> u8 { self.ram.peek(address) }
+    fn cycle(&mut self, cycle : BusCycle){ self.trace.push(cycle); }
+}
+
+fn r(address : u16, data : u8) -> BusCycle {
+    BusCycle{ direction : Direction::Read, address, data, sync : false }
+}
+
+fn w(address : u16, data : u8) -> BusCycle {
+    BusCycle{ direction : Direction::Write, address, data, sync : false }
+}
+
+fn sync(address : u16, data : u8) -> BusCycle {
+    BusCycle{ direction : Direction::Read, address, data, sync : true }
+}
+
+fn trace(code : &[u8], x : u8) -> Vec<BusCycle> {
+    let mut cpu = crate::CPU::with_bus(TraceBus{ ram : FlatRam::new(), trace : Vec::new() });
+    cpu.mount_mem(0x0600, code).unwrap();
+    cpu.mount_mem(0x1310, &[0x41]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_x(x);
+    cpu.set_sp(0xFF);
+    cpu.set_cycle_accurate(true);
+    let cycles = cpu.step();
+    assert_eq!(cycles as usize, cpu.bus().trace.len());
+    std::mem::take(&mut cpu.bus_mut().trace)
+}
+
+#[test]
+fn test_rmw_absolute_indexed_cycles(){
+    // INC $12F0,X
+    assert_eq!(trace(&[0xFE, 0xF0, 0x12], 0x20), vec![
+        sync(0x0600, 0xFE), r(0x0601, 0xF0), r(0x0602, 0x12),
+        r(0x1210, 0x00), r(0x1310, 0x41), w(0x1310, 0x41), w(0x1310, 0x42),
+    ]);
+}
+
+#[test]
+fn test_indexed_read_cycles(){
+    // LDA $12F0,X without and with a page crossing
+    assert_eq!(trace(&[0xBD, 0xF0, 0x12], 0x0F), vec![
+        sync(0x0600, 0xBD), r(0x0601, 0xF0), r(0x0602, 0x12), r(0x12FF, 0x00),
+    ]);
+    assert_eq!(trace(&[0xBD, 0xF0, 0x12], 0x20), vec![
+        sync(0x0600, 0xBD), r(0x0601, 0xF0), r(0x0602, 0x12), r(0x1210, 0x00), r(0x1310, 0x41),
+    ]);
+}
+
+#[test]
+fn test_jsr_cycles(){
+    // JSR $1234
+    assert_eq!(trace(&[0x20, 0x34, 0x12], 0), vec![
+        sync(0x0600, 0x20), r(0x0601, 0x34), r(0x01FF, 0x00),
+        w(0x01FF, 0x06), w(0x01FE, 0x02), r(0x0602, 0x12),
+    ]);
+}
@@ -1,12 +1,18 @@
 use std::fmt;
 
+use crate::bus::{Bus, BusCycle, Direction, FlatRam};
+use crate::error::{Error, Result};
+use crate::ioport::IoPort;
+use crate::status::StatusFlags;
+use crate::variant::CpuVariant;
+use crate::{IRQ_VEC, NMI_VEC};
+
 #[cfg(test)]
 #[path="./cpu_test.rs"]
 mod cpu_test;
 
-#[allow(dead_code)]
-#[derive(Copy, Clone)]
-enum AddressingMode {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AddressingMode {
     // from https://csh.rit.edu/~moffitt/docs/6502.html
     AddrModeUndefined,      
     AddrModeABS,        // Absolute
@@ -22,11 +28,29 @@ enum AddressingMode {
     AddrModeZP,         // Zero-Page
     AddrModeZPX,        // Zero-Page Indexed with X
     AddrModeZPY,        // Zero-Page Indexed with Y
+
+    // 65C02 only
+    AddrModeZPInd,      // Zero-Page Indirect
+    AddrModeZPRelative, // Zero-Page and Relative (BBR/BBS)
+    AddrModeABSIndX,    // Absolute Indexed Indirect with X (JMP)
+
+    // 65C816 only. The zero page modes address the direct page there and
+    // Indirect is Absolute Indirect.
+    AddrModeABSLong,    // Absolute Long
+    AddrModeABSLongX,   // Absolute Long Indexed with X
+    AddrModeABSIndLong, // Absolute Indirect Long (JML)
+    AddrModeBlockMove,  // Block Move (MVN/MVP)
+    AddrModeZPIndLong,  // Direct Page Indirect Long
+    AddrModeZPIndLongY, // Direct Page Indirect Long Indexed with Y
+    AddrModeImmedM,     // Immediate, 16 bit when M is clear
+    AddrModeImmedX,     // Immediate, 16 bit when X is clear
+    AddrModeRelativeLong, // Relative Long (BRL/PER)
+    AddrModeStackRel,   // Stack Relative
+    AddrModeStackRelIndY, // Stack Relative Indirect Indexed with Y
 }
 
-#[allow(dead_code)]
-#[derive(Copy, Clone)]
-enum InstructionMnemonic {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InstructionMnemonic {
     // from https://csh.rit.edu/~moffitt/docs/6502.html
     InstrUndefined,
     InstrADC,       // Add Memory to A with Carry
@@ -85,24 +109,224 @@ enum InstructionMnemonic {
     InstrTXA,       // Transfer X to A
     InstrTXS,       // Transfer X to SP
     InstrTYA,       // Transfer Y to A
+
+    // Undocumented NMOS instructions, named as in "No More Secrets"
+    InstrALR,       // AND A with Memory then LSR A
+    InstrANC,       // AND A with Memory, copy N to C
+    InstrANE,       // (A | magic) & X & Memory to A (unstable)
+    InstrARR,       // AND A with Memory then ROR A with odd flags
+    InstrDCP,       // Decrement Memory then CMP
+    InstrISC,       // Increase Memory then SBC
+    InstrJAM,       // Halt the CPU
+    InstrLAS,       // Memory & SP to A, X and SP
+    InstrLAX,       // Load A and X with Memory
+    InstrLXA,       // (A | magic) & Memory to A and X (unstable)
+    InstrRLA,       // ROL Memory then AND
+    InstrRRA,       // ROR Memory then ADC
+    InstrSAX,       // Store A & X in Memory
+    InstrSBX,       // (A & X) - Memory to X
+    InstrSHA,       // Store A & X & (H + 1) in Memory (unstable)
+    InstrSHX,       // Store X & (H + 1) in Memory (unstable)
+    InstrSHY,       // Store Y & (H + 1) in Memory (unstable)
+    InstrSLO,       // ASL Memory then ORA
+    InstrSRE,       // LSR Memory then EOR
+    InstrTAS,       // A & X to SP, store SP & (H + 1) in Memory (unstable)
+
+    // 65C02 instructions. The bit operated on by BBR/BBS/RMB/SMB is encoded
+    // in bits 4-6 of the opcode.
+    InstrBBR,       // Branch if Bit of Memory is CLEAR
+    InstrBBS,       // Branch if Bit of Memory is SET
+    InstrBRA,       // Branch Always
+    InstrPHX,       // Push X onto Stack
+    InstrPHY,       // Push Y onto Stack
+    InstrPLX,       // Pull from Stack to X
+    InstrPLY,       // Pull from Stack to Y
+    InstrRMB,       // Reset Bit of Memory
+    InstrSMB,       // Set Bit of Memory
+    InstrSTP,       // Stop the CPU until reset
+    InstrSTZ,       // Store Zero in Memory
+    InstrTRB,       // Test and Reset Bits of Memory with A
+    InstrTSB,       // Test and Set Bits of Memory with A
+    InstrWAI,       // Wait for Interrupt
+
+    // 65C816 instructions
+    InstrBRL,       // Branch Always Long
+    InstrCOP,       // Co-Processor Software Interrupt
+    InstrJML,       // Jump Long
+    InstrJSL,       // Jump to Subroutine Long
+    InstrMVN,       // Block Move Next (incrementing)
+    InstrMVP,       // Block Move Previous (decrementing)
+    InstrPEA,       // Push Effective Absolute Address
+    InstrPEI,       // Push Effective Indirect Address
+    InstrPER,       // Push Effective PC Relative Address
+    InstrPHB,       // Push Data Bank onto Stack
+    InstrPHD,       // Push Direct Page onto Stack
+    InstrPHK,       // Push Program Bank onto Stack
+    InstrPLB,       // Pull from Stack to Data Bank
+    InstrPLD,       // Pull from Stack to Direct Page
+    InstrREP,       // Reset Status Bits
+    InstrRTL,       // Return from Subroutine Long
+    InstrSEP,       // Set Status Bits
+    InstrTCD,       // Transfer 16 bit A to Direct Page
+    InstrTCS,       // Transfer 16 bit A to SP
+    InstrTDC,       // Transfer Direct Page to 16 bit A
+    InstrTSC,       // Transfer SP to 16 bit A
+    InstrTXY,       // Transfer X to Y
+    InstrTYX,       // Transfer Y to X
+    InstrWDM,       // Reserved, two byte No Operation
+    InstrXBA,       // Exchange the Bytes of A
+    InstrXCE,       // Exchange Carry and Emulation Flags
 }
 
-#[allow(dead_code)]
+// An entry of the decode tables
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub mnem : InstructionMnemonic, // Mnemonic of Instruction
+    pub length : u8,                // Length of Instruction
+    pub cycles : u8,                // Number of Cycles used by Instruction
+    pub mode : AddressingMode,      // Addressing Mode of Instruction
+}
+
+// Assembler name of the mnemonic, e.g. "LDA"
+impl fmt::Display for InstructionMnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("{:?}", self);
+        f.pad(name.trim_start_matches("Instr"))
+    }
+}
+
+// Name of the addressing mode as used in data sheets
+impl fmt::Display for AddressingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            AddressingMode::AddrModeUndefined => "Undefined",
+            AddressingMode::AddrModeABS => "Absolute",
+            AddressingMode::AddrModeABSX => "Absolute Indexed with X",
+            AddressingMode::AddrModeABSY => "Absolute Indexed with Y",
+            AddressingMode::AddrModeA => "Accumulator",
+            AddressingMode::AddrModeImmed => "Immediate",
+            AddressingMode::AddrModeImplied => "Implied",
+            AddressingMode::AddrModeIndirect => "Indirect",
+            AddressingMode::AddrModeIndX => "Indexed Indirect with X",
+            AddressingMode::AddrModeIndY => "Indexed Indirect with Y",
+            AddressingMode::AddrModeRelative => "Relative",
+            AddressingMode::AddrModeZP => "Zero-Page",
+            AddressingMode::AddrModeZPX => "Zero-Page Indexed with X",
+            AddressingMode::AddrModeZPY => "Zero-Page Indexed with Y",
+            AddressingMode::AddrModeZPInd => "Zero-Page Indirect",
+            AddressingMode::AddrModeZPRelative => "Zero-Page and Relative",
+            AddressingMode::AddrModeABSIndX => "Absolute Indexed Indirect with X",
+            AddressingMode::AddrModeABSLong => "Absolute Long",
+            AddressingMode::AddrModeABSLongX => "Absolute Long Indexed with X",
+            AddressingMode::AddrModeABSIndLong => "Absolute Indirect Long",
+            AddressingMode::AddrModeBlockMove => "Block Move",
+            AddressingMode::AddrModeZPIndLong => "Direct Page Indirect Long",
+            AddressingMode::AddrModeZPIndLongY => "Direct Page Indirect Long Indexed with Y",
+            AddressingMode::AddrModeImmedM | AddressingMode::AddrModeImmedX => "Immediate",
+            AddressingMode::AddrModeRelativeLong => "Relative Long",
+            AddressingMode::AddrModeStackRel => "Stack Relative",
+            AddressingMode::AddrModeStackRelIndY => "Stack Relative Indirect Indexed with Y",
+        })
+    }
+}
+
+// Decode table used by the given variant
+pub fn instruction_matrix(variant : CpuVariant) -> &'static [Instruction ; crate::NUM_INSTR] {
+    match variant {
+        CpuVariant::Wdc65C816 => &INSTRUCTION_MATRIX_65816,
+        _ if variant.has_illegal_opcodes() => &INSTRUCTION_MATRIX,
+        _ => &INSTRUCTION_MATRIX_65C02,
+    }
+}
+
+// How an instruction accesses its operand in memory. Indexed addressing
+// only skips the dummy read before the page fixup for plain reads.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+    Modify,
+    Other,
+}
+
+impl InstructionMnemonic {
+    // Whether this is one of the undocumented NMOS instructions
+    pub fn is_undocumented(&self) -> bool {
+        matches!(self, InstructionMnemonic::InstrALR | InstructionMnemonic::InstrANC | InstructionMnemonic::InstrANE
+            | InstructionMnemonic::InstrARR | InstructionMnemonic::InstrDCP | InstructionMnemonic::InstrISC
+            | InstructionMnemonic::InstrJAM | InstructionMnemonic::InstrLAS | InstructionMnemonic::InstrLAX
+            | InstructionMnemonic::InstrLXA | InstructionMnemonic::InstrRLA | InstructionMnemonic::InstrRRA
+            | InstructionMnemonic::InstrSAX | InstructionMnemonic::InstrSBX | InstructionMnemonic::InstrSHA
+            | InstructionMnemonic::InstrSHX | InstructionMnemonic::InstrSHY | InstructionMnemonic::InstrSLO
+            | InstructionMnemonic::InstrSRE | InstructionMnemonic::InstrTAS)
+    }
+
+    fn access(&self) -> Access {
+        match self {
+            InstructionMnemonic::InstrLDA | InstructionMnemonic::InstrLDX | InstructionMnemonic::InstrLDY
+                | InstructionMnemonic::InstrAND | InstructionMnemonic::InstrEOR | InstructionMnemonic::InstrORA
+                | InstructionMnemonic::InstrADC | InstructionMnemonic::InstrSBC | InstructionMnemonic::InstrCMP
+                | InstructionMnemonic::InstrCPX | InstructionMnemonic::InstrCPY | InstructionMnemonic::InstrBIT
+                | InstructionMnemonic::InstrNOP | InstructionMnemonic::InstrLAX | InstructionMnemonic::InstrLAS => Access::Read,
+            InstructionMnemonic::InstrSTA | InstructionMnemonic::InstrSTX | InstructionMnemonic::InstrSTY
+                | InstructionMnemonic::InstrSAX | InstructionMnemonic::InstrSHA | InstructionMnemonic::InstrSHX
+                | InstructionMnemonic::InstrSHY | InstructionMnemonic::InstrTAS | InstructionMnemonic::InstrSTZ => Access::Write,
+            InstructionMnemonic::InstrASL | InstructionMnemonic::InstrLSR | InstructionMnemonic::InstrROL
+                | InstructionMnemonic::InstrROR | InstructionMnemonic::InstrINC | InstructionMnemonic::InstrDEC
+                | InstructionMnemonic::InstrSLO | InstructionMnemonic::InstrRLA | InstructionMnemonic::InstrSRE
+                | InstructionMnemonic::InstrRRA | InstructionMnemonic::InstrDCP | InstructionMnemonic::InstrISC
+                | InstructionMnemonic::InstrTRB | InstructionMnemonic::InstrTSB | InstructionMnemonic::InstrRMB
+                | InstructionMnemonic::InstrSMB => Access::Modify,
+            _ => Access::Other,
+        }
+    }
+}
+
+const STACK_PAGE : u16 = 0x0100;
+
+// Bits of A that leak into the result of the unstable ANE and LXA. The value
+// differs between chips, $EE is the most commonly observed one.
+const UNSTABLE_MAGIC : u8 = 0xEE;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Interrupt {
+    Nmi,
+    Irq,
+}
+
+// Interrupt state as sampled at the end of a cycle
+#[derive(Copy, Clone, Default)]
+struct InterruptPoll {
+    nmi : bool,
+    irq : bool,
+}
+
+// Suspicious program behaviour reported in diagnostics mode, tagged with the
+// address of the offending instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    StackOverflow { pc : u16 },     // push wrapped SP from $00 to $FF
+    StackUnderflow { pc : u16 },    // pull wrapped SP from $FF to $00
+    JmpIndirectPageWrap { pc : u16, pointer : u16 },   // JMP ($xxFF)
+}
+
+// Operand resolved by fetch() from the addressing mode of the instruction
 #[derive(Copy, Clone)]
-struct Instruction {
-    mnem : InstructionMnemonic,     // Mnemonic of Instruction
-    length : u8,                    // Length of Instruction
-    cycles : u8,                    // Number of Cycles used by Instruction
-    mode : AddressingMode,          // Addressing Mode of Instruction
+enum Operand {
+    Implied,
+    Accumulator,
+    Immediate(u8),
+    Address(u16),
+    ZeroPageRelative(u8, u16),      // address to test and branch target
 }
 
-#[allow(dead_code)]
-pub struct CPU {
+#[allow(clippy::upper_case_acronyms)]
+pub struct CPU<B : Bus = FlatRam> {
     // Program Counter
     pc : u16,
 
     // Stack Pointer
-    sp : u16,
+    sp : u8,
 
     // Accumulator Register
     a : u8,
@@ -111,20 +335,65 @@ pub struct CPU {
     x : u8,
     y : u8,
 
-    // Flags
-    n : u8, // Sign Flag
-    v : u8, // Overflow Flag
-    b : u8, // Break Flag
-    d : u8, // Binary Encoded Decimal Flag
-    i : u8, // Interrupt Disable Flag
-    z : u8, // Zero Flag
-    c : u8, // Carry Flag
-
-    // Onboard Memory
-    mem: [u8 ; rs6502::MAX_MEM],
+    // Processor Status Flags
+    p : StatusFlags,
+
+    // Memory map the CPU is attached to
+    bus : B,
+
+    // Emulated chip and the opcode table it decodes with
+    variant : CpuVariant,
+    matrix : &'static [Instruction; crate::NUM_INSTR],
+
+    // Instruction decoded by the last fetch(), its opcode and address
+    opcode : u8,
+    instr : Instruction,
+    operand : Operand,
+    instr_pc : u16,
+    index_base : u16,
+
+    // Cycles taken by the current instruction and in total. Every cycle of
+    // the 6502 is a bus cycle, so both count bus accesses.
+    instr_cycles : u8,
+    cycles : u64,
+
+    // Set by the JAM instructions and STP, only a reset recovers the CPU
+    halted : bool,
+
+    // Set by WAI until an interrupt is signalled
+    waiting : bool,
+
+    // Whether dummy accesses are performed on the bus and every cycle is
+    // reported through Bus::cycle()
+    cycle_accurate : bool,
+
+    // Interrupt inputs driven through set_irq()/set_nmi(), combined with the
+    // lines of the bus. NMI is edge triggered and latched in nmi_pending.
+    irq_line : bool,
+    nmi_line : bool,
+    nmi_level : bool,
+    nmi_pending : bool,
+
+    // Interrupt polls of the last three cycles, newest first, and the
+    // interrupt to service before the next instruction
+    polls : [InterruptPoll ; 3],
+    interrupt : Option<Interrupt>,
+
+    // Diagnostics collected while diagnostics_enabled is set
+    diagnostics_enabled : bool,
+    diagnostics : Vec<Diagnostic>,
+
+    // Whether the D flag selects BCD arithmetic in ADC/SBC
+    bcd_enabled : bool,
+
+    // Whether JMP ($xxFF) fetches the high byte from $xx00 as on NMOS parts
+    jmp_indirect_bug : bool,
+
+    // On-chip port of the 6510 at $0000/$0001
+    io_port : IoPort,
 }
 
-impl fmt::Debug for CPU {
+impl<B : Bus> fmt::Debug for CPU<B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CPU")
             .field("Program Counter", &format_args!("0x{0:X}", &self.pc))
@@ -132,325 +401,1946 @@ impl fmt::Debug for CPU {
             .field("Register A", &format_args!("0x{0:X}", &self.a))
             .field("Register X", &format_args!("0x{0:X}", &self.x))
             .field("Register Y", &format_args!("0x{0:X}", &self.y))
-            .field("Negative", &self.n)
-            .field("Overflow", &self.v)
-            .field("Break", &self.b)
-            .field("Decimal", &self.d)
-            .field("Interrupt", &self.i)
-            .field("Zero Flag", &self.z)
-            .field("Carry Flag", &self.c)
+            .field("Status", &self.p)
             .finish()
     }
 }
 
-pub fn new() -> CPU {
-    CPU{
-        pc : 0, sp : 0, a : 0, x : 0, y : 0,
-        n : 0, v : 0, b : 0, d : 0, i : 0, z : 0, c : 0,
-        mem: [0 ; rs6502::MAX_MEM],
+impl Default for CPU {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl CPU {
-    pub fn fetch(&self){
-        println!("Fetch");
+    pub fn new() -> CPU {
+        CPU::with_bus(FlatRam::new())
+    }
+
+    pub fn builder() -> CPUBuilder {
+        CPUBuilder::new()
+    }
+}
+
+// Configures a CPU before it is created. Without further settings the
+// builder produces the same NMOS 6502 on 64K of RAM as CPU::new().
+#[allow(clippy::upper_case_acronyms)]
+pub struct CPUBuilder<B : Bus = FlatRam> {
+    bus : B,
+    variant : CpuVariant,
+    cycle_accurate : bool,
+    diagnostics : bool,
+}
+
+impl Default for CPUBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CPUBuilder {
+    pub fn new() -> CPUBuilder {
+        CPUBuilder{ bus : FlatRam::new(), variant : CpuVariant::default(), cycle_accurate : false, diagnostics : false }
+    }
+}
+
+impl<B : Bus> CPUBuilder<B> {
+    pub fn bus<C : Bus>(self, bus : C) -> CPUBuilder<C> {
+        CPUBuilder{ bus, variant : self.variant, cycle_accurate : self.cycle_accurate, diagnostics : self.diagnostics }
+    }
+
+    pub fn variant(mut self, variant : CpuVariant) -> Self {
+        self.variant = variant;
+        self
     }
 
-    pub fn execute(&self){
-        println!("Execution");
+    pub fn cycle_accurate(mut self, enabled : bool) -> Self {
+        self.cycle_accurate = enabled;
+        self
     }
 
-    pub fn reset(&mut self){
+    pub fn diagnostics(mut self, enabled : bool) -> Self {
+        self.diagnostics = enabled;
+        self
+    }
+
+    pub fn build(self) -> CPU<B> {
+        let mut cpu = CPU::with_bus(self.bus);
+        cpu.set_variant(self.variant);
+        cpu.set_cycle_accurate(self.cycle_accurate);
+        cpu.set_diagnostics(self.diagnostics);
+        cpu
+    }
+}
+
+impl<B : Bus> CPU<B> {
+    pub fn with_bus(bus : B) -> CPU<B> {
+        CPU{
+            pc : 0, sp : 0, a : 0, x : 0, y : 0,
+            p : StatusFlags::default(),
+            bus,
+            variant : CpuVariant::Nmos6502, matrix : &INSTRUCTION_MATRIX,
+            opcode : 0, instr : INSTRUCTION_UNDEFINED, operand : Operand::Implied, instr_pc : 0, index_base : 0,
+            instr_cycles : 0, cycles : 0, halted : false, waiting : false, cycle_accurate : false,
+            irq_line : false, nmi_line : false, nmi_level : false, nmi_pending : false,
+            polls : [InterruptPoll::default() ; 3], interrupt : None,
+            diagnostics_enabled : false, diagnostics : Vec::new(),
+            bcd_enabled : true, jmp_indirect_bug : true,
+            io_port : IoPort::default(),
+        }
+    }
+
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    // Fetches and executes a single instruction, returning the cycles it
+    // took. If an interrupt was recognized during the previous instruction
+    // the interrupt sequence is run instead. A halted CPU takes no cycles,
+    // one waiting after WAI idles for a cycle per step.
+    pub fn step(&mut self) -> u8 {
+        if self.halted {
+            return 0;
+        }
+        if let Some(interrupt) = self.interrupt.take() {
+            return self.service_interrupt(interrupt);
+        }
+        if self.waiting {
+            return self.wait();
+        }
+        self.fetch();
+        self.execute()
+    }
+
+    // Level of the IRQ input, asserted while true
+    pub fn set_irq(&mut self, asserted : bool){
+        self.irq_line = asserted;
+    }
+
+    // Level of the NMI input. Only the transition to asserted triggers an
+    // interrupt.
+    pub fn set_nmi(&mut self, asserted : bool){
+        self.nmi_line = asserted;
+    }
+
+    // Executes instructions until at least the given number of cycles have
+    // elapsed or the CPU halts and returns the number of cycles actually spent
+    pub fn run(&mut self, cycles : u64) -> u64 {
+        let mut elapsed : u64 = 0;
+        while elapsed < cycles && !self.halted {
+            elapsed += self.step() as u64;
+        }
+        elapsed
+    }
+
+    // Reads the opcode at PC, decodes it through the table of the variant and
+    // resolves its operand, running the addressing cycles of the instruction.
+    // PC is left pointing at the next instruction.
+    pub fn fetch(&mut self){
+        self.instr_pc = self.pc;
+        self.instr_cycles = 0;
+        self.opcode = self.fetch_opcode();
+        self.instr = self.matrix[self.opcode as usize];
+
+        let access = self.instr.mnem.access();
+        self.operand = match self.instr.mode {
+            // the 65C02 has single cycle NOPs
+            AddressingMode::AddrModeImplied if self.instr.cycles == 1 => Operand::Implied,
+            AddressingMode::AddrModeUndefined | AddressingMode::AddrModeImplied => {
+                self.dummy_read(self.pc);
+                Operand::Implied
+            }
+            // only in the 65C816 table, which CPU816 executes
+            AddressingMode::AddrModeABSLong | AddressingMode::AddrModeABSLongX | AddressingMode::AddrModeABSIndLong
+                | AddressingMode::AddrModeBlockMove | AddressingMode::AddrModeZPIndLong
+                | AddressingMode::AddrModeZPIndLongY | AddressingMode::AddrModeImmedM | AddressingMode::AddrModeImmedX
+                | AddressingMode::AddrModeRelativeLong | AddressingMode::AddrModeStackRel
+                | AddressingMode::AddrModeStackRelIndY => Operand::Implied,
+            AddressingMode::AddrModeA => {
+                self.dummy_read(self.pc);
+                Operand::Accumulator
+            }
+            AddressingMode::AddrModeImmed => Operand::Immediate(self.fetch_byte()),
+            AddressingMode::AddrModeABS => match self.instr.mnem {
+                // JSR fetches the high byte of the target after pushing PC
+                InstructionMnemonic::InstrJSR => Operand::Immediate(self.fetch_byte()),
+                _ => Operand::Address(self.fetch_word()),
+            },
+            AddressingMode::AddrModeABSX => {
+                let base = self.fetch_word();
+                Operand::Address(self.index(base, self.x, access))
+            }
+            AddressingMode::AddrModeABSY => {
+                let base = self.fetch_word();
+                Operand::Address(self.index(base, self.y, access))
+            }
+            AddressingMode::AddrModeIndirect => {
+                let ptr = self.fetch_word();
+                Operand::Address(self.read_indirect(ptr))
+            }
+            AddressingMode::AddrModeIndX => {
+                let zp = self.fetch_byte();
+                self.dummy_read(zp as u16);
+                Operand::Address(self.read_word_zp(zp.wrapping_add(self.x)))
+            }
+            AddressingMode::AddrModeIndY => {
+                let zp = self.fetch_byte();
+                let base = self.read_word_zp(zp);
+                Operand::Address(self.index(base, self.y, access))
+            }
+            AddressingMode::AddrModeRelative => {
+                let offset = self.fetch_byte() as i8;
+                Operand::Address(self.pc.wrapping_add(offset as u16))
+            }
+            AddressingMode::AddrModeZP => Operand::Address(self.fetch_byte() as u16),
+            AddressingMode::AddrModeZPX => {
+                let zp = self.fetch_byte();
+                self.dummy_read(zp as u16);
+                Operand::Address(zp.wrapping_add(self.x) as u16)
+            }
+            AddressingMode::AddrModeZPY => {
+                let zp = self.fetch_byte();
+                self.dummy_read(zp as u16);
+                Operand::Address(zp.wrapping_add(self.y) as u16)
+            }
+            AddressingMode::AddrModeZPInd => {
+                let zp = self.fetch_byte();
+                Operand::Address(self.read_word_zp(zp))
+            }
+            AddressingMode::AddrModeZPRelative => {
+                let zp = self.fetch_byte();
+                let offset = self.fetch_byte() as i8;
+                Operand::ZeroPageRelative(zp, self.pc.wrapping_add(offset as u16))
+            }
+            AddressingMode::AddrModeABSIndX => {
+                let base = self.fetch_word();
+                self.dummy_read(self.pc.wrapping_sub(1));
+                Operand::Address(self.read_word(base.wrapping_add(self.x as u16)))
+            }
+        };
+    }
+
+    // Executes the instruction decoded by the last fetch() and returns the
+    // number of cycles fetch() and execute() took together, including page
+    // crossing and branch penalties.
+    pub fn execute(&mut self) -> u8 {
+        match self.instr.mnem {
+            InstructionMnemonic::InstrUndefined => {}
+
+            // Load / Store
+            InstructionMnemonic::InstrLDA => { self.a = self.load(); self.set_nz(self.a); }
+            InstructionMnemonic::InstrLDX => { self.x = self.load(); self.set_nz(self.x); }
+            InstructionMnemonic::InstrLDY => { self.y = self.load(); self.set_nz(self.y); }
+            InstructionMnemonic::InstrSTA => self.store(self.a),
+            InstructionMnemonic::InstrSTX => self.store(self.x),
+            InstructionMnemonic::InstrSTY => self.store(self.y),
+
+            // Register Transfers
+            InstructionMnemonic::InstrTAX => { self.x = self.a; self.set_nz(self.x); }
+            InstructionMnemonic::InstrTAY => { self.y = self.a; self.set_nz(self.y); }
+            InstructionMnemonic::InstrTXA => { self.a = self.x; self.set_nz(self.a); }
+            InstructionMnemonic::InstrTYA => { self.a = self.y; self.set_nz(self.a); }
+            InstructionMnemonic::InstrTSX => { self.x = self.sp; self.set_nz(self.x); }
+            InstructionMnemonic::InstrTXS => self.sp = self.x,
+
+            // Stack
+            InstructionMnemonic::InstrPHA => self.push(self.a),
+            InstructionMnemonic::InstrPHP => self.push(self.p.pushed(true)),
+            InstructionMnemonic::InstrPLA => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.a = self.pull();
+                self.set_nz(self.a);
+            }
+            InstructionMnemonic::InstrPLP => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.p = StatusFlags::from_bits(self.pull());
+            }
+
+            // Logical
+            InstructionMnemonic::InstrAND => { self.a &= self.load(); self.set_nz(self.a); }
+            InstructionMnemonic::InstrEOR => { self.a ^= self.load(); self.set_nz(self.a); }
+            InstructionMnemonic::InstrORA => { self.a |= self.load(); self.set_nz(self.a); }
+            InstructionMnemonic::InstrBIT => {
+                let m = self.load();
+                self.p.set_zero(self.a & m == 0);
+                // BIT #imm of the 65C02 only affects Z
+                if !matches!(self.operand, Operand::Immediate(_)) {
+                    self.p.set_negative(m & 0x80 != 0);
+                    self.p.set_overflow(m & 0x40 != 0);
+                }
+            }
+
+            // Arithmetic
+            InstructionMnemonic::InstrADC => { let m = self.load(); self.adc(m); }
+            InstructionMnemonic::InstrSBC => { let m = self.load(); self.sbc(m); }
+            InstructionMnemonic::InstrCMP => { let m = self.load(); self.compare(self.a, m); }
+            InstructionMnemonic::InstrCPX => { let m = self.load(); self.compare(self.x, m); }
+            InstructionMnemonic::InstrCPY => { let m = self.load(); self.compare(self.y, m); }
+
+            // Increments / Decrements
+            InstructionMnemonic::InstrINC => { self.modify(Self::inc); }
+            InstructionMnemonic::InstrDEC => { self.modify(Self::dec); }
+            InstructionMnemonic::InstrINX => { self.x = self.x.wrapping_add(1); self.set_nz(self.x); }
+            InstructionMnemonic::InstrINY => { self.y = self.y.wrapping_add(1); self.set_nz(self.y); }
+            InstructionMnemonic::InstrDEX => { self.x = self.x.wrapping_sub(1); self.set_nz(self.x); }
+            InstructionMnemonic::InstrDEY => { self.y = self.y.wrapping_sub(1); self.set_nz(self.y); }
+
+            // Shifts
+            InstructionMnemonic::InstrASL => { self.modify(Self::asl); }
+            InstructionMnemonic::InstrLSR => { self.modify(Self::lsr); }
+            InstructionMnemonic::InstrROL => { self.modify(Self::rol); }
+            InstructionMnemonic::InstrROR => { self.modify(Self::ror); }
+
+            // Jumps / Calls
+            InstructionMnemonic::InstrJMP => self.pc = self.address(),
+            InstructionMnemonic::InstrJSR => {
+                // PC still points at the high byte of the target here
+                let lo = self.load() as u16;
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.push_word(self.pc);
+                let hi = self.read(self.pc) as u16;
+                self.pc = (hi << 8) | lo;
+            }
+            InstructionMnemonic::InstrRTS => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.pc = self.pull_word();
+                self.dummy_read(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+            }
+
+            // Branches
+            InstructionMnemonic::InstrBCC => self.branch(!self.p.carry()),
+            InstructionMnemonic::InstrBCS => self.branch(self.p.carry()),
+            InstructionMnemonic::InstrBEQ => self.branch(self.p.zero()),
+            InstructionMnemonic::InstrBMI => self.branch(self.p.negative()),
+            InstructionMnemonic::InstrBNE => self.branch(!self.p.zero()),
+            InstructionMnemonic::InstrBPL => self.branch(!self.p.negative()),
+            InstructionMnemonic::InstrBVC => self.branch(!self.p.overflow()),
+            InstructionMnemonic::InstrBVS => self.branch(self.p.overflow()),
+
+            // Status Flag Changes
+            InstructionMnemonic::InstrCLC => self.p.set_carry(false),
+            InstructionMnemonic::InstrCLD => self.p.set_decimal(false),
+            InstructionMnemonic::InstrCLI => self.p.set_interrupt_disable(false),
+            InstructionMnemonic::InstrCLV => self.p.set_overflow(false),
+            InstructionMnemonic::InstrSEC => self.p.set_carry(true),
+            InstructionMnemonic::InstrSED => self.p.set_decimal(true),
+            InstructionMnemonic::InstrSEI => self.p.set_interrupt_disable(true),
+
+            // System Functions
+            InstructionMnemonic::InstrBRK => {
+                // BRK skips the padding byte following the opcode
+                self.pc = self.pc.wrapping_add(1);
+                self.interrupt_sequence(Interrupt::Irq, true);
+                return self.instr_cycles;
+            }
+            InstructionMnemonic::InstrNOP => {
+                self.load();
+                // $5C of the 65C02 idles for another four cycles
+                while self.instr_cycles < self.instr.cycles {
+                    self.dummy_read(self.pc);
+                }
+            }
+            InstructionMnemonic::InstrRTI => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.p = StatusFlags::from_bits(self.pull());
+                self.pc = self.pull_word();
+            }
+
+            // Undocumented Combined Read-Modify-Write
+            InstructionMnemonic::InstrSLO => { let m = self.modify(Self::asl); self.a |= m; self.set_nz(self.a); }
+            InstructionMnemonic::InstrRLA => { let m = self.modify(Self::rol); self.a &= m; self.set_nz(self.a); }
+            InstructionMnemonic::InstrSRE => { let m = self.modify(Self::lsr); self.a ^= m; self.set_nz(self.a); }
+            InstructionMnemonic::InstrRRA => { let m = self.modify(Self::ror); self.adc(m); }
+            InstructionMnemonic::InstrDCP => { let m = self.modify(Self::dec); self.compare(self.a, m); }
+            InstructionMnemonic::InstrISC => { let m = self.modify(Self::inc); self.sbc(m); }
+
+            // Undocumented Load / Store
+            InstructionMnemonic::InstrLAX => { self.a = self.load(); self.x = self.a; self.set_nz(self.a); }
+            InstructionMnemonic::InstrSAX => self.store(self.a & self.x),
+            InstructionMnemonic::InstrLAS => {
+                self.sp &= self.load();
+                self.a = self.sp;
+                self.x = self.sp;
+                self.set_nz(self.sp);
+            }
+
+            // Undocumented Immediate
+            InstructionMnemonic::InstrANC => {
+                self.a &= self.load();
+                self.set_nz(self.a);
+                self.p.set_carry(self.p.negative());
+            }
+            InstructionMnemonic::InstrALR => { self.a &= self.load(); self.a = self.lsr(self.a); }
+            InstructionMnemonic::InstrARR => { let m = self.load(); self.arr(m); }
+            InstructionMnemonic::InstrSBX => {
+                let m = self.load();
+                let ax = self.a & self.x;
+                self.p.set_carry(ax >= m);
+                self.x = ax.wrapping_sub(m);
+                self.set_nz(self.x);
+            }
+            InstructionMnemonic::InstrANE => {
+                self.a = (self.a | UNSTABLE_MAGIC) & self.x & self.load();
+                self.set_nz(self.a);
+            }
+            InstructionMnemonic::InstrLXA => {
+                self.a = (self.a | UNSTABLE_MAGIC) & self.load();
+                self.x = self.a;
+                self.set_nz(self.a);
+            }
+
+            // Undocumented Stores ANDed with the high byte of the address + 1
+            InstructionMnemonic::InstrSHA => self.store_high(self.a & self.x),
+            InstructionMnemonic::InstrSHX => self.store_high(self.x),
+            InstructionMnemonic::InstrSHY => self.store_high(self.y),
+            InstructionMnemonic::InstrTAS => {
+                self.sp = self.a & self.x;
+                self.store_high(self.sp);
+            }
+
+            // The CPU locks up until it is reset
+            InstructionMnemonic::InstrJAM => self.halted = true,
+
+            // 65C02 Stack
+            InstructionMnemonic::InstrPHX => self.push(self.x),
+            InstructionMnemonic::InstrPHY => self.push(self.y),
+            InstructionMnemonic::InstrPLX => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.x = self.pull();
+                self.set_nz(self.x);
+            }
+            InstructionMnemonic::InstrPLY => {
+                self.dummy_read(STACK_PAGE | self.sp as u16);
+                self.y = self.pull();
+                self.set_nz(self.y);
+            }
+
+            // 65C02 Memory
+            InstructionMnemonic::InstrSTZ => self.store(0),
+            InstructionMnemonic::InstrTRB => { self.modify(Self::trb); }
+            InstructionMnemonic::InstrTSB => { self.modify(Self::tsb); }
+            InstructionMnemonic::InstrRMB => { self.modify(Self::rmb); }
+            InstructionMnemonic::InstrSMB => { self.modify(Self::smb); }
+
+            // 65C02 Branches
+            InstructionMnemonic::InstrBRA => self.branch(true),
+            InstructionMnemonic::InstrBBR | InstructionMnemonic::InstrBBS => {
+                let m = self.load();
+                if let Operand::ZeroPageRelative(zp, _) = self.operand {
+                    self.dummy_read(zp as u16);
+                }
+                let set = m & self.opcode_bit() != 0;
+                self.branch(set == matches!(self.instr.mnem, InstructionMnemonic::InstrBBS));
+            }
+
+            // 65C02 System Functions
+            InstructionMnemonic::InstrWAI => {
+                self.dummy_read(self.pc);
+                self.waiting = true;
+            }
+            InstructionMnemonic::InstrSTP => {
+                self.dummy_read(self.pc);
+                self.halted = true;
+            }
+
+            // Only in the 65C816 table, which CPU816 executes
+            InstructionMnemonic::InstrBRL | InstructionMnemonic::InstrCOP | InstructionMnemonic::InstrJML
+                | InstructionMnemonic::InstrJSL | InstructionMnemonic::InstrMVN | InstructionMnemonic::InstrMVP
+                | InstructionMnemonic::InstrPEA | InstructionMnemonic::InstrPEI | InstructionMnemonic::InstrPER
+                | InstructionMnemonic::InstrPHB | InstructionMnemonic::InstrPHD | InstructionMnemonic::InstrPHK
+                | InstructionMnemonic::InstrPLB | InstructionMnemonic::InstrPLD | InstructionMnemonic::InstrREP
+                | InstructionMnemonic::InstrRTL | InstructionMnemonic::InstrSEP | InstructionMnemonic::InstrTCD
+                | InstructionMnemonic::InstrTCS | InstructionMnemonic::InstrTDC | InstructionMnemonic::InstrTSC
+                | InstructionMnemonic::InstrTXY | InstructionMnemonic::InstrTYX | InstructionMnemonic::InstrWDM
+                | InstructionMnemonic::InstrXBA | InstructionMnemonic::InstrXCE => {}
+        }
+
+        // The decision to take an interrupt is made from the state polled
+        // at the end of the second to last cycle. A taken branch that stays
+        // on its page only polls before its operand fetch, so an interrupt
+        // arriving later waits for one more instruction.
+        let branch = matches!(self.instr.mode, AddressingMode::AddrModeRelative);
+        let poll = if branch && self.instr_cycles == 3 { self.polls[2] } else { self.polls[1] };
+        self.interrupt = self.recognized(poll);
+        self.instr_cycles
+    }
+
+    // Runs the 7 cycle reset sequence and returns the cycles it took. Reset
+    // goes through the motions of an interrupt with writes suppressed, so
+    // the three stack "pushes" only read the stack and move SP down by 3
+    // (from the power-on value of 0 this leaves SP at $FD). NMOS parts leave
+    // the decimal flag untouched, the 65C02 clears it.
+    pub fn reset(&mut self) -> u8 {
+        self.instr_cycles = 0;
+        self.halted = false;
+        self.waiting = false;
+        self.interrupt = None;
+        if self.variant.has_io_port() {
+            self.io_port.reset();
+            self.bus.io_port(self.io_port.pins());
+        }
+        self.dummy_read(self.pc);
+        self.dummy_read(self.pc);
+        for _ in 0..3 {
+            self.dummy_read(STACK_PAGE | self.sp as u16);
+            self.sp = self.sp.wrapping_sub(1);
+        }
+
         // set Interrupt disable flag
-        self.i = 1;
+        self.p.set_interrupt_disable(true);
+        if self.variant.is_cmos() {
+            self.p.set_decimal(false);
+        }
+
+        // load PC from the RESET vector
+        self.pc = self.read_word(crate::RESET_VEC);
 
-        // set RESET vector address into PC
-        self.pc = rs6502::RESET_VEC;
+        // an NMI edge latched before or during the reset is lost
+        self.nmi_pending = false;
+        self.polls = [InterruptPoll::default() ; 3];
+
+        self.instr_cycles
     }
 
-    pub fn mount_mem(&mut self, address : u16, data : &[u8]){
+    // Copies data into memory starting at address. Nothing is written unless
+    // the whole image fits and every target address is mapped.
+    pub fn mount_mem(&mut self, address : u16, data : &[u8]) -> Result<()> {
+        if (address as usize) + data.len() > crate::MAX_MEM {
+            return Err(Error::DataDoesNotFit { addr : address as u32, len : data.len() });
+        }
+        if let Some(unmapped) = (0..data.len())
+            .map(|i| address.wrapping_add(i as u16))
+            .find(|&addr| !self.bus.is_mapped(addr)) {
+            return Err(Error::UnmappedAddress(unmapped as u32));
+        }
 
-        if (address as usize) + data.len() > self.mem.len() {
-            println!("[-] Data does not fit in the memory for the given address");
-            return;
+        for (i, byte) in data.iter().enumerate() {
+            self.bus.poke(address.wrapping_add(i as u16), *byte);
         }
+        Ok(())
+    }
 
-        for i in 0..data.len(){
-            self.mem[(address as usize) + i] = data[i].clone();
+    pub fn read_mem(&self, address : u16) -> Result<u8> {
+        if !self.bus.is_mapped(address) {
+            return Err(Error::UnmappedAddress(address as u32));
+        }
+        if address < 2 && self.variant.has_io_port() {
+            return Ok(self.io_port.read(address, self.cycles));
         }
+        Ok(self.bus.peek(address))
+    }
+
+    pub fn write_mem(&mut self, address : u16, data : u8) -> Result<()> {
+        self.mount_mem(address, &[data])
+    }
+
+    // Switches the emulated chip. This selects the opcode table and resets
+    // the BCD and JMP indirect settings to the behaviour of the variant.
+    // The 65C816 runs as a 65C02, CPU816 emulates the whole chip.
+    pub fn set_variant(&mut self, variant : CpuVariant){
+        self.variant = variant;
+        self.matrix = match variant {
+            CpuVariant::Wdc65C816 => &INSTRUCTION_MATRIX_65C02,
+            _ => instruction_matrix(variant),
+        };
+        self.bcd_enabled = variant.has_decimal_mode();
+        self.jmp_indirect_bug = variant.has_jmp_indirect_bug();
+    }
+
+    pub fn variant(&self) -> CpuVariant {
+        self.variant
+    }
+
+    // Enables recording of suspicious program behaviour such as stack
+    // wraparound, see take_diagnostics()
+    pub fn set_diagnostics(&mut self, enabled : bool){
+        self.diagnostics_enabled = enabled;
+    }
+
+    // Returns and clears the diagnostics recorded so far
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    // Enables or disables BCD arithmetic. Cores such as the Ricoh 2A03 have
+    // the D flag but ADC/SBC always operate in binary.
+    pub fn set_bcd_enabled(&mut self, enabled : bool){
+        self.bcd_enabled = enabled;
+    }
+
+    pub fn bcd_enabled(&self) -> bool {
+        self.bcd_enabled
+    }
+
+    // NMOS parts do not carry into the high byte of the pointer of JMP ($xxFF)
+    // and fetch the target from $xxFF/$xx00. CMOS parts fix this at the cost
+    // of an extra cycle.
+    pub fn set_jmp_indirect_bug(&mut self, enabled : bool){
+        self.jmp_indirect_bug = enabled;
     }
 
-    // TODO: rewrite this so it returns Option<&u8> to make error handling possible
-    pub fn read_mem(&self, address : u16) -> &u8 {
-        if (address as usize) >= rs6502::MAX_MEM {
-            println!("[-] Address out of bounds");
-            return &0;
+    pub fn jmp_indirect_bug(&self) -> bool {
+        self.jmp_indirect_bug
+    }
+
+    // In cycle accurate mode every instruction performs all of its bus
+    // cycles, including the dummy reads and writes the 6502 does while it
+    // computes addresses, and reports each of them through Bus::cycle().
+    // Otherwise dummy accesses are only counted, not performed.
+    pub fn set_cycle_accurate(&mut self, enabled : bool){
+        self.cycle_accurate = enabled;
+    }
+
+    pub fn cycle_accurate(&self) -> bool {
+        self.cycle_accurate
+    }
+
+    // I/O port of the 6510, only mapped at $0000/$0001 for that variant
+    pub fn io_port(&self) -> &IoPort {
+        &self.io_port
+    }
+
+    pub fn io_port_mut(&mut self) -> &mut IoPort {
+        &mut self.io_port
+    }
+
+    // Whether the CPU has been stopped by a JAM or STP instruction
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    // Whether the CPU is waiting for an interrupt after WAI
+    pub fn waiting(&self) -> bool {
+        self.waiting
+    }
+
+    // Total number of cycles executed since the CPU was created
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // Register accessors
+    pub fn pc(&self) -> u16 { self.pc }
+    pub fn sp(&self) -> u8 { self.sp }
+    pub fn a(&self) -> u8 { self.a }
+    pub fn x(&self) -> u8 { self.x }
+    pub fn y(&self) -> u8 { self.y }
+
+    pub fn set_pc(&mut self, pc : u16){ self.pc = pc; }
+    pub fn set_sp(&mut self, sp : u8){ self.sp = sp; }
+    pub fn set_a(&mut self, a : u8){ self.a = a; }
+    pub fn set_x(&mut self, x : u8){ self.x = x; }
+    pub fn set_y(&mut self, y : u8){ self.y = y; }
+
+    // Flag accessors
+    pub fn negative(&self) -> bool { self.p.negative() }
+    pub fn overflow(&self) -> bool { self.p.overflow() }
+    pub fn decimal(&self) -> bool { self.p.decimal() }
+    pub fn interrupt_disable(&self) -> bool { self.p.interrupt_disable() }
+    pub fn zero(&self) -> bool { self.p.zero() }
+    pub fn carry(&self) -> bool { self.p.carry() }
+
+    pub fn set_negative(&mut self, flag : bool){ self.p.set_negative(flag); }
+    pub fn set_overflow(&mut self, flag : bool){ self.p.set_overflow(flag); }
+    pub fn set_decimal(&mut self, flag : bool){ self.p.set_decimal(flag); }
+    pub fn set_interrupt_disable(&mut self, flag : bool){ self.p.set_interrupt_disable(flag); }
+    pub fn set_zero(&mut self, flag : bool){ self.p.set_zero(flag); }
+    pub fn set_carry(&mut self, flag : bool){ self.p.set_carry(flag); }
+
+    // Processor status register
+    pub fn status(&self) -> StatusFlags { self.p }
+    pub fn set_status(&mut self, p : StatusFlags){ self.p = p; }
+
+    // Packed processor status byte, bit 5 reads as 1 and B as 0
+    pub fn p(&self) -> u8 { self.p.bits() }
+    pub fn set_p(&mut self, p : u8){ self.p = StatusFlags::from_bits(p); }
+
+    // Runs the 7 cycle IRQ/NMI sequence. The opcode fetch happens but is
+    // discarded, and PC is not advanced.
+    fn service_interrupt(&mut self, interrupt : Interrupt) -> u8 {
+        self.instr_pc = self.pc;
+        self.instr_cycles = 0;
+        self.bus_read(self.pc, true);
+        self.dummy_read(self.pc);
+        self.interrupt_sequence(interrupt, false);
+        self.instr_cycles
+    }
+
+    // Pushes PC and P and jumps through the vector. The vector is picked
+    // after PC has been pushed, so an NMI arriving during the first four
+    // cycles of BRK or IRQ hijacks the sequence (B stays set for BRK). The
+    // 65C02 completes BRK and takes the NMI right after it instead, and
+    // clears the decimal flag on every interrupt.
+    fn interrupt_sequence(&mut self, interrupt : Interrupt, brk : bool){
+        let cmos = self.variant.is_cmos();
+        self.waiting = false;
+        self.push_word(self.pc);
+        let hijack = self.nmi_pending && !(brk && cmos);
+        let vector = if interrupt == Interrupt::Nmi || hijack {
+            self.nmi_pending = false;
+            NMI_VEC
+        } else {
+            IRQ_VEC
+        };
+        self.push(self.p.pushed(brk));
+        self.p.set_interrupt_disable(true);
+        if cmos {
+            self.p.set_decimal(false);
+        }
+        self.pc = self.read_word(vector);
+
+        // the first instruction of the handler always runs, except after a
+        // 65C02 BRK with an NMI pending
+        self.interrupt = if brk && cmos && self.nmi_pending { Some(Interrupt::Nmi) } else { None };
+    }
+
+    // Samples the interrupt inputs at the end of a cycle
+    fn tick(&mut self){
+        self.instr_cycles += 1;
+        self.cycles += 1;
+
+        let nmi = self.nmi_line || self.bus.nmi();
+        if nmi && !self.nmi_level {
+            self.nmi_pending = true;
+        }
+        self.nmi_level = nmi;
+
+        let irq = (self.irq_line || self.bus.irq()) && !self.p.interrupt_disable();
+        self.polls = [InterruptPoll{ nmi : self.nmi_pending, irq }, self.polls[0], self.polls[1]];
+    }
+
+    // Idles for one cycle after WAI. Any asserted interrupt line ends the
+    // wait, a masked IRQ just resumes execution after WAI.
+    fn wait(&mut self) -> u8 {
+        self.instr_cycles = 0;
+        self.tick();
+        if self.nmi_pending || self.irq_line || self.bus.irq() {
+            self.waiting = false;
+            self.interrupt = self.recognized(self.polls[0]);
+        }
+        self.instr_cycles
+    }
+
+    fn recognized(&self, poll : InterruptPoll) -> Option<Interrupt> {
+        if poll.nmi {
+            Some(Interrupt::Nmi)
+        } else if poll.irq {
+            Some(Interrupt::Irq)
+        } else {
+            None
+        }
+    }
+
+    // Every access below is one bus cycle
+    fn bus_read(&mut self, address : u16, sync : bool) -> u8 {
+        let mut data = self.bus.read(address);
+        if address < 2 && self.variant.has_io_port() {
+            data = self.io_port.read(address, self.cycles);
+        }
+        self.tick();
+        if self.cycle_accurate {
+            self.bus.cycle(BusCycle{ direction : Direction::Read, address, data, sync });
+        }
+        data
+    }
+
+    fn read(&mut self, address : u16) -> u8 {
+        self.bus_read(address, false)
+    }
+
+    // Writes to the 6510 port also reach the RAM underneath
+    fn write(&mut self, address : u16, data : u8){
+        self.bus.write(address, data);
+        if address < 2 && self.variant.has_io_port() {
+            let pins = self.io_port.pins();
+            self.io_port.write(address, data, self.cycles);
+            if self.io_port.pins() != pins {
+                self.bus.io_port(self.io_port.pins());
+            }
+        }
+        self.tick();
+        if self.cycle_accurate {
+            self.bus.cycle(BusCycle{ direction : Direction::Write, address, data, sync : false });
+        }
+    }
+
+    // Cycles whose data the CPU ignores only reach the bus in cycle
+    // accurate mode
+    fn dummy_read(&mut self, address : u16){
+        if self.cycle_accurate {
+            self.read(address);
+        } else {
+            self.tick();
+        }
+    }
+
+    fn dummy_write(&mut self, address : u16, data : u8){
+        if self.cycle_accurate {
+            self.write(address, data);
+        } else {
+            self.tick();
+        }
+    }
+
+    fn fetch_opcode(&mut self) -> u8 {
+        let opcode = self.bus_read(self.pc, true);
+        self.pc = self.pc.wrapping_add(1);
+        opcode
+    }
+
+    fn fetch_byte(&mut self) -> u8 {
+        let data = self.read(self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        data
+    }
+
+    fn fetch_word(&mut self) -> u16 {
+        let lo = self.fetch_byte() as u16;
+        let hi = self.fetch_byte() as u16;
+        (hi << 8) | lo
+    }
+
+    fn read_word(&mut self, address : u16) -> u16 {
+        let lo = self.read(address) as u16;
+        let hi = self.read(address.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    fn read_indirect(&mut self, ptr : u16) -> u16 {
+        if ptr & 0x00FF == 0x00FF {
+            self.report(Diagnostic::JmpIndirectPageWrap { pc : self.instr_pc, pointer : ptr });
+        }
+        if !self.jmp_indirect_bug {
+            self.dummy_read(self.pc.wrapping_sub(1));
+            return self.read_word(ptr);
+        }
+        let lo = self.read(ptr) as u16;
+        let hi = self.read((ptr & 0xFF00) | (ptr.wrapping_add(1) & 0x00FF)) as u16;
+        (hi << 8) | lo
+    }
+
+    // Pointers stored in the zero-page wrap around within the page
+    fn read_word_zp(&mut self, address : u8) -> u16 {
+        let lo = self.read(address as u16) as u16;
+        let hi = self.read(address.wrapping_add(1) as u16) as u16;
+        (hi << 8) | lo
+    }
+
+    // The stack lives in page $01 and SP wraps around within it
+    fn push(&mut self, data : u8){
+        self.write(STACK_PAGE | self.sp as u16, data);
+        if self.sp == 0x00 {
+            self.report(Diagnostic::StackOverflow { pc : self.instr_pc });
+        }
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull(&mut self) -> u8 {
+        if self.sp == 0xFF {
+            self.report(Diagnostic::StackUnderflow { pc : self.instr_pc });
+        }
+        self.sp = self.sp.wrapping_add(1);
+        self.read(STACK_PAGE | self.sp as u16)
+    }
+
+    fn push_word(&mut self, data : u16){
+        self.push((data >> 8) as u8);
+        self.push(data as u8);
+    }
+
+    fn pull_word(&mut self) -> u16 {
+        let lo = self.pull() as u16;
+        let hi = self.pull() as u16;
+        (hi << 8) | lo
+    }
+
+    fn report(&mut self, diagnostic : Diagnostic){
+        if self.diagnostics_enabled {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn address(&self) -> u16 {
+        match self.operand {
+            Operand::Address(address) | Operand::ZeroPageRelative(_, address) => address,
+            _ => 0,
+        }
+    }
+
+    fn load(&mut self) -> u8 {
+        match self.operand {
+            Operand::Implied => 0,
+            Operand::Accumulator => self.a,
+            Operand::Immediate(data) => data,
+            Operand::Address(address) => self.read(address),
+            Operand::ZeroPageRelative(zp, _) => self.read(zp as u16),
+        }
+    }
+
+    fn store(&mut self, data : u8){
+        if let Operand::Address(address) = self.operand {
+            self.write(address, data);
+        }
+    }
+
+    // Read-modify-write on either the accumulator or memory, returning the
+    // result. NMOS parts write memory twice, first with the unmodified value,
+    // the 65C02 reads it a second time instead.
+    fn modify(&mut self, op : fn(&mut CPU<B>, u8) -> u8) -> u8 {
+        let data = self.load();
+        let result = op(self, data);
+        match self.operand {
+            Operand::Accumulator => self.a = result,
+            Operand::Address(address) => {
+                if self.variant.is_cmos() {
+                    self.dummy_read(address);
+                } else {
+                    self.dummy_write(address, data);
+                }
+                self.write(address, result);
+            }
+            _ => {}
+        }
+        result
+    }
+
+    fn inc(&mut self, data : u8) -> u8 {
+        let result = data.wrapping_add(1);
+        self.set_nz(result);
+        result
+    }
+
+    fn dec(&mut self, data : u8) -> u8 {
+        let result = data.wrapping_sub(1);
+        self.set_nz(result);
+        result
+    }
+
+    fn asl(&mut self, data : u8) -> u8 {
+        self.p.set_carry(data & 0x80 != 0);
+        let result = data << 1;
+        self.set_nz(result);
+        result
+    }
+
+    fn lsr(&mut self, data : u8) -> u8 {
+        self.p.set_carry(data & 0x01 != 0);
+        let result = data >> 1;
+        self.set_nz(result);
+        result
+    }
+
+    fn rol(&mut self, data : u8) -> u8 {
+        let result = (data << 1) | self.p.carry() as u8;
+        self.p.set_carry(data & 0x80 != 0);
+        self.set_nz(result);
+        result
+    }
+
+    fn ror(&mut self, data : u8) -> u8 {
+        let result = (data >> 1) | ((self.p.carry() as u8) << 7);
+        self.p.set_carry(data & 0x01 != 0);
+        self.set_nz(result);
+        result
+    }
+
+    // AND followed by ROR A. The flags come from the adder rather than the
+    // shifter, and in decimal mode the result gets a BCD style fixup.
+    fn arr(&mut self, data : u8){
+        let and = self.a & data;
+        let mut result = (and >> 1) | ((self.p.carry() as u8) << 7);
+        if self.p.decimal() && self.bcd_enabled {
+            self.p.set_negative(self.p.carry());
+            self.p.set_zero(result == 0);
+            self.p.set_overflow((and ^ result) & 0x40 != 0);
+            if (and & 0x0F) + (and & 0x01) > 0x05 {
+                result = (result & 0xF0) | (result.wrapping_add(0x06) & 0x0F);
+            }
+            let carry = (and as u16 & 0xF0) + (and as u16 & 0x10) > 0x50;
+            if carry {
+                result = result.wrapping_add(0x60);
+            }
+            self.p.set_carry(carry);
+        } else {
+            self.set_nz(result);
+            self.p.set_carry(result & 0x40 != 0);
+            self.p.set_overflow(((result >> 6) ^ (result >> 5)) & 0x01 != 0);
+        }
+        self.a = result;
+    }
+
+    fn trb(&mut self, data : u8) -> u8 {
+        self.p.set_zero(self.a & data == 0);
+        data & !self.a
+    }
+
+    fn tsb(&mut self, data : u8) -> u8 {
+        self.p.set_zero(self.a & data == 0);
+        data | self.a
+    }
+
+    fn rmb(&mut self, data : u8) -> u8 {
+        data & !self.opcode_bit()
+    }
+
+    fn smb(&mut self, data : u8) -> u8 {
+        data | self.opcode_bit()
+    }
+
+    // Mask of the bit selected by the opcode of BBR/BBS/RMB/SMB
+    fn opcode_bit(&self) -> u8 {
+        1 << ((self.opcode >> 4) & 0x07)
+    }
+
+    // SHA/SHX/SHY/TAS store the value ANDed with the high byte of the base
+    // address + 1. When indexing crosses a page the value also replaces the
+    // high byte of the target address.
+    fn store_high(&mut self, data : u8){
+        let address = self.address();
+        let value = data & (self.index_base >> 8).wrapping_add(1) as u8;
+        if (self.index_base ^ address) & 0xFF00 != 0 {
+            self.write(((value as u16) << 8) | (address & 0x00FF), value);
+        } else {
+            self.write(address, value);
+        }
+    }
+
+    // Taken branches cost one extra cycle, two if the target is on another
+    // page than the next instruction
+    fn branch(&mut self, condition : bool){
+        if condition {
+            let target = self.address();
+            self.dummy_read(self.pc);
+            if (target ^ self.pc) & 0xFF00 != 0 {
+                self.dummy_read((self.pc & 0xFF00) | (target & 0x00FF));
+            }
+            self.pc = target;
+        }
+    }
+
+    // Indexing first adds to the low byte only. The CPU reads from that
+    // address while it fixes up the high byte, which plain reads can skip
+    // when no page was crossed. The 65C02 rereads the last operand byte
+    // instead and also lets shifts and rotates skip the fixup.
+    fn index(&mut self, base : u16, index : u8, access : Access) -> u16 {
+        self.index_base = base;
+        let address = base.wrapping_add(index as u16);
+        let crossed = (base ^ address) & 0xFF00 != 0;
+        if !self.variant.is_cmos() {
+            if crossed || access != Access::Read {
+                self.dummy_read((base & 0xFF00) | (address & 0x00FF));
+            }
+        } else if crossed || access == Access::Write
+            || matches!(self.instr.mnem, InstructionMnemonic::InstrINC | InstructionMnemonic::InstrDEC) {
+            self.dummy_read(self.pc.wrapping_sub(1));
+        }
+        address
+    }
+
+    // In decimal mode the 65C02 takes an extra cycle to set N and Z from
+    // the adjusted result
+    fn adc(&mut self, data : u8){
+        if !self.p.decimal() || !self.bcd_enabled {
+            self.adc_binary(data);
+        } else if self.variant.is_cmos() {
+            self.adc_decimal(data);
+            self.set_nz(self.a);
+            self.dummy_read(self.pc);
+        } else {
+            self.adc_decimal(data);
         }
-        return &self.mem[address as usize];
+    }
+
+    fn sbc(&mut self, data : u8){
+        if !self.p.decimal() || !self.bcd_enabled {
+            self.adc_binary(!data);
+        } else if self.variant.is_cmos() {
+            self.sbc_decimal_cmos(data);
+            self.dummy_read(self.pc);
+        } else {
+            self.sbc_decimal(data);
+        }
+    }
+
+    fn adc_binary(&mut self, data : u8){
+        let sum = self.a as u16 + data as u16 + self.p.carry() as u16;
+        let result = sum as u8;
+        self.p.set_carry(sum > 0xFF);
+        self.p.set_overflow((!(self.a ^ data) & (self.a ^ result)) & 0x80 != 0);
+        self.a = result;
+        self.set_nz(result);
+    }
+
+    // NMOS decimal addition. Z comes from the binary sum, N and V from the
+    // intermediate result after the low nibble has been adjusted, so they
+    // are only meaningful for valid BCD inputs but still match the chip for
+    // invalid ones.
+    fn adc_decimal(&mut self, data : u8){
+        let a = self.a as u16;
+        let m = data as u16;
+        let c = self.p.carry() as u16;
+
+        let mut lo = (a & 0x0F) + (m & 0x0F) + c;
+        if lo > 0x09 {
+            lo += 0x06;
+        }
+        let mut sum = (a & 0xF0) + (m & 0xF0) + (lo & 0x0F);
+        if lo > 0x0F {
+            sum += 0x10;
+        }
+
+        self.p.set_zero((a + m + c) & 0xFF == 0);
+        self.p.set_negative(sum & 0x80 != 0);
+        self.p.set_overflow((a ^ sum) & 0x80 != 0 && (a ^ m) & 0x80 == 0);
+
+        if sum & 0x1F0 > 0x90 {
+            sum += 0x60;
+        }
+        self.p.set_carry(sum & 0xFF0 > 0xF0);
+        self.a = sum as u8;
+    }
+
+    // NMOS decimal subtraction. All flags are set as for a binary SBC,
+    // only the accumulator is decimal adjusted.
+    fn sbc_decimal(&mut self, data : u8){
+        let a = self.a as u16;
+        let m = data as u16;
+        let borrow = !self.p.carry() as u16;
+
+        let mut lo = (a & 0x0F).wrapping_sub(m & 0x0F).wrapping_sub(borrow);
+        let mut diff = if lo & 0x10 != 0 {
+            lo = lo.wrapping_sub(0x06);
+            (lo & 0x0F) | (a & 0xF0).wrapping_sub(m & 0xF0).wrapping_sub(0x10)
+        } else {
+            (lo & 0x0F) | (a & 0xF0).wrapping_sub(m & 0xF0)
+        };
+        if diff & 0x100 != 0 {
+            diff = diff.wrapping_sub(0x60);
+        }
+
+        self.adc_binary(!data);
+        self.a = diff as u8;
+    }
+
+    // 65C02 decimal subtraction. C and V are set as for a binary SBC, N and
+    // Z from the adjusted result. The adjustment differs from NMOS parts
+    // for invalid BCD inputs.
+    fn sbc_decimal_cmos(&mut self, data : u8){
+        let a = self.a as i16;
+        let m = data as i16;
+        let c = self.p.carry() as i16;
+
+        let lo = (a & 0x0F) - (m & 0x0F) + c - 1;
+        let mut diff = a - m + c - 1;
+        if diff < 0 {
+            diff -= 0x60;
+        }
+        if lo < 0 {
+            diff -= 0x06;
+        }
+
+        self.adc_binary(!data);
+        self.a = diff as u8;
+        self.set_nz(self.a);
+    }
+
+    fn compare(&mut self, register : u8, data : u8){
+        self.p.set_carry(register >= data);
+        self.set_nz(register.wrapping_sub(data));
+    }
+
+    fn set_nz(&mut self, data : u8){
+        self.p.set_negative(data & 0x80 != 0);
+        self.p.set_zero(data == 0);
     }
 }
 
 #[allow(dead_code)]
 const INSTRUCTION_UNDEFINED : Instruction = Instruction{mnem : InstructionMnemonic::InstrUndefined, length : 0, cycles : 0 , mode : AddressingMode::AddrModeUndefined};
 
-#[allow(dead_code)]
-const INSTRUCTION_MATRIX : [Instruction; rs6502::NUM_INSTR] = [
+// NMOS decode table, including the undocumented opcodes
+pub const INSTRUCTION_MATRIX : [Instruction; crate::NUM_INSTR] = [
+    /* 00 */Instruction{ mnem : InstructionMnemonic::InstrBRK , length : 1, cycles : 7, mode : AddressingMode::AddrModeImplied},
+    /* 01 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 02 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 03 */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* 04 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 05 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 06 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 07 */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 08 */Instruction{ mnem : InstructionMnemonic::InstrPHP , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 09 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 0A */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 0B */Instruction{ mnem : InstructionMnemonic::InstrANC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 0C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 0D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 0E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 0F */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 10 */Instruction{ mnem : InstructionMnemonic::InstrBPL , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 11 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 12 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 13 */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* 14 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 15 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 16 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 17 */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 18 */Instruction{ mnem : InstructionMnemonic::InstrCLC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 19 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 1A */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 1B */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* 1C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 1D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 1E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 1F */Instruction{ mnem : InstructionMnemonic::InstrSLO , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 20 */Instruction{ mnem : InstructionMnemonic::InstrJSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 21 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 22 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 23 */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* 24 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 25 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 26 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 27 */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 28 */Instruction{ mnem : InstructionMnemonic::InstrPLP , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 29 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 2A */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 2B */Instruction{ mnem : InstructionMnemonic::InstrANC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 2C */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 2D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 2E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 2F */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 30 */Instruction{ mnem : InstructionMnemonic::InstrBMI , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 31 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 32 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 33 */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* 34 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 35 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 36 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 37 */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 38 */Instruction{ mnem : InstructionMnemonic::InstrSEC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 39 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 3A */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 3B */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* 3C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 3D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 3E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 3F */Instruction{ mnem : InstructionMnemonic::InstrRLA , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 40 */Instruction{ mnem : InstructionMnemonic::InstrRTI , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
+    /* 41 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 42 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 43 */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* 44 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 45 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 46 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 47 */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 48 */Instruction{ mnem : InstructionMnemonic::InstrPHA , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 49 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 4A */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 4B */Instruction{ mnem : InstructionMnemonic::InstrALR , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 4C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 3, mode : AddressingMode::AddrModeABS},
+    /* 4D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 4E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 4F */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 50 */Instruction{ mnem : InstructionMnemonic::InstrBVC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 51 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 52 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 53 */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* 54 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 55 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 56 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 57 */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 58 */Instruction{ mnem : InstructionMnemonic::InstrCLI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 59 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 5A */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 5B */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* 5C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 5D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 5E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 5F */Instruction{ mnem : InstructionMnemonic::InstrSRE , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 60 */Instruction{ mnem : InstructionMnemonic::InstrRTS , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
+    /* 61 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 62 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 63 */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* 64 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 65 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 66 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 67 */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 68 */Instruction{ mnem : InstructionMnemonic::InstrPLA , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 69 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 6A */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 6B */Instruction{ mnem : InstructionMnemonic::InstrARR , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 6C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 5, mode : AddressingMode::AddrModeIndirect},
+    /* 6D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 6E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 6F */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 70 */Instruction{ mnem : InstructionMnemonic::InstrBVS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 71 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 72 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 73 */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* 74 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 75 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 76 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 77 */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 78 */Instruction{ mnem : InstructionMnemonic::InstrSEI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 79 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 7A */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 7B */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* 7C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 7D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 7E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 7F */Instruction{ mnem : InstructionMnemonic::InstrRRA , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 80 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 81 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 82 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 83 */Instruction{ mnem : InstructionMnemonic::InstrSAX , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 84 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 85 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 86 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 87 */Instruction{ mnem : InstructionMnemonic::InstrSAX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 88 */Instruction{ mnem : InstructionMnemonic::InstrDEY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 89 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 8A */Instruction{ mnem : InstructionMnemonic::InstrTXA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 8B */Instruction{ mnem : InstructionMnemonic::InstrANE , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 8C */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8E */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8F */Instruction{ mnem : InstructionMnemonic::InstrSAX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 90 */Instruction{ mnem : InstructionMnemonic::InstrBCC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 91 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndY},
+    /* 92 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 93 */Instruction{ mnem : InstructionMnemonic::InstrSHA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndY},
+    /* 94 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 95 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 96 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* 97 */Instruction{ mnem : InstructionMnemonic::InstrSAX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* 98 */Instruction{ mnem : InstructionMnemonic::InstrTYA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 99 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
+    /* 9A */Instruction{ mnem : InstructionMnemonic::InstrTXS , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 9B */Instruction{ mnem : InstructionMnemonic::InstrTAS , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
+    /* 9C */Instruction{ mnem : InstructionMnemonic::InstrSHY , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
+    /* 9D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
+    /* 9E */Instruction{ mnem : InstructionMnemonic::InstrSHX , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
+    /* 9F */Instruction{ mnem : InstructionMnemonic::InstrSHA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
+    /* A0 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* A1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* A2 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* A3 */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* A4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A7 */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A8 */Instruction{ mnem : InstructionMnemonic::InstrTAY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* A9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* AA */Instruction{ mnem : InstructionMnemonic::InstrTAX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* AB */Instruction{ mnem : InstructionMnemonic::InstrLXA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* AC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AF */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* B0 */Instruction{ mnem : InstructionMnemonic::InstrBCS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* B1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* B2 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* B3 */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* B4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* B5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* B6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* B7 */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* B8 */Instruction{ mnem : InstructionMnemonic::InstrCLV , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* B9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BA */Instruction{ mnem : InstructionMnemonic::InstrTSX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* BB */Instruction{ mnem : InstructionMnemonic::InstrLAS , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* BD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* BE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BF */Instruction{ mnem : InstructionMnemonic::InstrLAX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* C0 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* C1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* C2 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* C3 */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* C4 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* C5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* C6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* C7 */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* C8 */Instruction{ mnem : InstructionMnemonic::InstrINY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* C9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* CA */Instruction{ mnem : InstructionMnemonic::InstrDEX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* CB */Instruction{ mnem : InstructionMnemonic::InstrSBX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* CC */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* CD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* CE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* CF */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* D0 */Instruction{ mnem : InstructionMnemonic::InstrBNE , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* D1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* D2 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* D3 */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* D4 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* D5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* D6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* D7 */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* D8 */Instruction{ mnem : InstructionMnemonic::InstrCLD , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* D9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* DA */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* DB */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* DC */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* DD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* DE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* DF */Instruction{ mnem : InstructionMnemonic::InstrDCP , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* E0 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* E1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* E2 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* E3 */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndX},
+    /* E4 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* E5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* E6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* E7 */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* E8 */Instruction{ mnem : InstructionMnemonic::InstrINX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* E9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* EA */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* EB */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* EC */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* ED */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* EE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* EF */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* F0 */Instruction{ mnem : InstructionMnemonic::InstrBEQ , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* F1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* F2 */Instruction{ mnem : InstructionMnemonic::InstrJAM , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* F3 */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 2, cycles : 8, mode : AddressingMode::AddrModeIndY},
+    /* F4 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* F5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* F6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* F7 */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* F8 */Instruction{ mnem : InstructionMnemonic::InstrSED , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* F9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* FA */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* FB */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSY},
+    /* FC */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* FD */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* FE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* FF */Instruction{ mnem : InstructionMnemonic::InstrISC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+];
+
+// 65C02 decode table as implemented by the W65C02S, including the Rockwell
+// bit instructions. Opcodes without an instruction are NOPs.
+pub const INSTRUCTION_MATRIX_65C02 : [Instruction; crate::NUM_INSTR] = [
     /* 00 */Instruction{ mnem : InstructionMnemonic::InstrBRK , length : 1, cycles : 7, mode : AddressingMode::AddrModeImplied},
     /* 01 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* 02 */INSTRUCTION_UNDEFINED,
-    /* 03 */INSTRUCTION_UNDEFINED,
-    /* 04 */INSTRUCTION_UNDEFINED,
+    /* 02 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 03 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 04 */Instruction{ mnem : InstructionMnemonic::InstrTSB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 05 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 06 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* 07 */INSTRUCTION_UNDEFINED,
+    /* 07 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 08 */Instruction{ mnem : InstructionMnemonic::InstrPHP , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
     /* 09 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* 0A */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
-    /* 0B */INSTRUCTION_UNDEFINED,
-    /* 0C */INSTRUCTION_UNDEFINED,
+    /* 0B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 0C */Instruction{ mnem : InstructionMnemonic::InstrTSB , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
     /* 0D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
-    /* 0E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 6, mode : AddressingMode::AddrModeImmed},
-    /* 0F */INSTRUCTION_UNDEFINED,
+    /* 0E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 0F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 10 */Instruction{ mnem : InstructionMnemonic::InstrBPL , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* 11 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* 12 */INSTRUCTION_UNDEFINED,
-    /* 13 */INSTRUCTION_UNDEFINED,
-    /* 14 */INSTRUCTION_UNDEFINED,
+    /* 12 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 13 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 14 */Instruction{ mnem : InstructionMnemonic::InstrTRB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 15 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 16 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* 17 */INSTRUCTION_UNDEFINED,
+    /* 17 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 18 */Instruction{ mnem : InstructionMnemonic::InstrCLC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* 19 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* 1A */INSTRUCTION_UNDEFINED,
-    /* 1B */INSTRUCTION_UNDEFINED,
-    /* 1C */INSTRUCTION_UNDEFINED,
+    /* 1A */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 1B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 1C */Instruction{ mnem : InstructionMnemonic::InstrTRB , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
     /* 1D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
-    /* 1E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* 1F */INSTRUCTION_UNDEFINED,
+    /* 1E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSX},
+    /* 1F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 20 */Instruction{ mnem : InstructionMnemonic::InstrJSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
     /* 21 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* 22 */INSTRUCTION_UNDEFINED,
-    /* 23 */INSTRUCTION_UNDEFINED,
+    /* 22 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 23 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 24 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
-    /* 25 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 2, mode : AddressingMode::AddrModeZP},
+    /* 25 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 26 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* 27 */INSTRUCTION_UNDEFINED,
+    /* 27 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 28 */Instruction{ mnem : InstructionMnemonic::InstrPLP , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
     /* 29 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* 2A */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
-    /* 2B */INSTRUCTION_UNDEFINED,
+    /* 2B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 2C */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 2D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 2E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
-    /* 2F */INSTRUCTION_UNDEFINED,
+    /* 2F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 30 */Instruction{ mnem : InstructionMnemonic::InstrBMI , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* 31 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* 32 */INSTRUCTION_UNDEFINED,
-    /* 33 */INSTRUCTION_UNDEFINED,
-    /* 34 */INSTRUCTION_UNDEFINED,
-    /* 35 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 3, mode : AddressingMode::AddrModeZPX},
+    /* 32 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 33 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 34 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 35 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 36 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* 37 */INSTRUCTION_UNDEFINED,
+    /* 37 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 38 */Instruction{ mnem : InstructionMnemonic::InstrSEC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* 39 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* 3A */INSTRUCTION_UNDEFINED,
-    /* 3B */INSTRUCTION_UNDEFINED,
-    /* 3C */INSTRUCTION_UNDEFINED,
+    /* 3A */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 3B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 3C */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
     /* 3D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
-    /* 3E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* 3F */INSTRUCTION_UNDEFINED,
+    /* 3E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSX},
+    /* 3F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 40 */Instruction{ mnem : InstructionMnemonic::InstrRTI , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
     /* 41 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* 42 */INSTRUCTION_UNDEFINED,
-    /* 43 */INSTRUCTION_UNDEFINED,
-    /* 44 */INSTRUCTION_UNDEFINED,
+    /* 42 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 43 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 44 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 45 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 46 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* 47 */INSTRUCTION_UNDEFINED,
+    /* 47 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 48 */Instruction{ mnem : InstructionMnemonic::InstrPHA , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
     /* 49 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* 4A */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
-    /* 4B */INSTRUCTION_UNDEFINED,
+    /* 4B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 4C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 3, mode : AddressingMode::AddrModeABS},
     /* 4D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 4E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
-    /* 4F */INSTRUCTION_UNDEFINED,
+    /* 4F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 50 */Instruction{ mnem : InstructionMnemonic::InstrBVC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* 51 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* 52 */INSTRUCTION_UNDEFINED,
-    /* 53 */INSTRUCTION_UNDEFINED,
-    /* 54 */INSTRUCTION_UNDEFINED,
+    /* 52 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 53 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 54 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 55 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 56 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* 57 */INSTRUCTION_UNDEFINED,
+    /* 57 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 58 */Instruction{ mnem : InstructionMnemonic::InstrCLI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* 59 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* 5A */INSTRUCTION_UNDEFINED,
-    /* 5B */INSTRUCTION_UNDEFINED,
-    /* 5C */INSTRUCTION_UNDEFINED,
+    /* 5A */Instruction{ mnem : InstructionMnemonic::InstrPHY , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 5B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 5C */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 8, mode : AddressingMode::AddrModeABS},
     /* 5D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
-    /* 5E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* 5F */INSTRUCTION_UNDEFINED,
+    /* 5E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSX},
+    /* 5F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 60 */Instruction{ mnem : InstructionMnemonic::InstrRTS , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
     /* 61 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* 62 */INSTRUCTION_UNDEFINED,
-    /* 63 */INSTRUCTION_UNDEFINED,
-    /* 64 */INSTRUCTION_UNDEFINED,
+    /* 62 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 63 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 64 */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 65 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 66 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* 67 */INSTRUCTION_UNDEFINED,
+    /* 67 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 68 */Instruction{ mnem : InstructionMnemonic::InstrPLA , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
     /* 69 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* 6A */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
-    /* 6B */INSTRUCTION_UNDEFINED,
-    /* 6C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 5, mode : AddressingMode::AddrModeIndirect},
+    /* 6B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 6C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 6, mode : AddressingMode::AddrModeIndirect},
     /* 6D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 6E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
-    /* 6F */INSTRUCTION_UNDEFINED,
+    /* 6F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 70 */Instruction{ mnem : InstructionMnemonic::InstrBVS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* 71 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* 72 */INSTRUCTION_UNDEFINED,
-    /* 73 */INSTRUCTION_UNDEFINED,
-    /* 74 */INSTRUCTION_UNDEFINED,
+    /* 72 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 73 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 74 */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 75 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 76 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* 77 */INSTRUCTION_UNDEFINED,
+    /* 77 */Instruction{ mnem : InstructionMnemonic::InstrRMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 78 */Instruction{ mnem : InstructionMnemonic::InstrSEI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* 79 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* 7A */INSTRUCTION_UNDEFINED,
-    /* 7B */INSTRUCTION_UNDEFINED,
-    /* 7C */INSTRUCTION_UNDEFINED,
+    /* 7A */Instruction{ mnem : InstructionMnemonic::InstrPLY , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 7B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 7C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSIndX},
     /* 7D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
-    /* 7E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* 7F */INSTRUCTION_UNDEFINED,
-    /* 80 */INSTRUCTION_UNDEFINED,
+    /* 7E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSX},
+    /* 7F */Instruction{ mnem : InstructionMnemonic::InstrBBR , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
+    /* 80 */Instruction{ mnem : InstructionMnemonic::InstrBRA , length : 2, cycles : 3, mode : AddressingMode::AddrModeRelative},
     /* 81 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* 82 */INSTRUCTION_UNDEFINED,
-    /* 83 */INSTRUCTION_UNDEFINED,
+    /* 82 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 83 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 84 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 85 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* 86 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
-    /* 87 */INSTRUCTION_UNDEFINED,
+    /* 87 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 88 */Instruction{ mnem : InstructionMnemonic::InstrDEY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* 89 */INSTRUCTION_UNDEFINED,
+    /* 89 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* 8A */Instruction{ mnem : InstructionMnemonic::InstrTXA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* 8B */INSTRUCTION_UNDEFINED,
+    /* 8B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 8C */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 8D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 8E */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
-    /* 8F */INSTRUCTION_UNDEFINED,
+    /* 8F */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* 90 */Instruction{ mnem : InstructionMnemonic::InstrBCC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* 91 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndY},
-    /* 92 */INSTRUCTION_UNDEFINED,
-    /* 93 */INSTRUCTION_UNDEFINED,
+    /* 92 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 93 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* 94 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 95 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* 96 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
-    /* 97 */INSTRUCTION_UNDEFINED,
+    /* 97 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* 98 */Instruction{ mnem : InstructionMnemonic::InstrTYA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* 99 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
     /* 9A */Instruction{ mnem : InstructionMnemonic::InstrTXS , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* 9B */INSTRUCTION_UNDEFINED,
-    /* 9C */INSTRUCTION_UNDEFINED,
+    /* 9B */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* 9C */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* 9D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
-    /* 9E */INSTRUCTION_UNDEFINED,
-    /* 9F */INSTRUCTION_UNDEFINED,
+    /* 9E */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
+    /* 9F */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* A0 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* A1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
     /* A2 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
-    /* A3 */INSTRUCTION_UNDEFINED,
+    /* A3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* A4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* A5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* A6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
-    /* A7 */INSTRUCTION_UNDEFINED,
+    /* A7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* A8 */Instruction{ mnem : InstructionMnemonic::InstrTAY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* A9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* AA */Instruction{ mnem : InstructionMnemonic::InstrTAX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* AB */INSTRUCTION_UNDEFINED,
-    /* AC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AB */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* AC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* AD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
-    /* AE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 4, mode : AddressingMode::AddrModeABS},
-    /* AF */INSTRUCTION_UNDEFINED,
+    /* AE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* B0 */Instruction{ mnem : InstructionMnemonic::InstrBCS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* B1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* B2 */INSTRUCTION_UNDEFINED,
-    /* B3 */INSTRUCTION_UNDEFINED,
+    /* B2 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* B3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* B4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* B5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* B6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
-    /* B7 */INSTRUCTION_UNDEFINED,
+    /* B7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* B8 */Instruction{ mnem : InstructionMnemonic::InstrCLV , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* B9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
     /* BA */Instruction{ mnem : InstructionMnemonic::InstrTSX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* BB */INSTRUCTION_UNDEFINED,
-    /* BC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* BB */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* BC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
     /* BD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
-    /* BE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* BF */INSTRUCTION_UNDEFINED,
+    /* BE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* C0 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* C1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* C2 */INSTRUCTION_UNDEFINED,
-    /* C3 */INSTRUCTION_UNDEFINED,
+    /* C2 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* C3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* C4 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* C5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* C6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* C7 */INSTRUCTION_UNDEFINED,
+    /* C7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* C8 */Instruction{ mnem : InstructionMnemonic::InstrINY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* C9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* CA */Instruction{ mnem : InstructionMnemonic::InstrDEX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* CB */INSTRUCTION_UNDEFINED,
+    /* CB */Instruction{ mnem : InstructionMnemonic::InstrWAI , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
     /* CC */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* CD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* CE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
-    /* CF */INSTRUCTION_UNDEFINED,
+    /* CF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* D0 */Instruction{ mnem : InstructionMnemonic::InstrBNE , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* D1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* D2 */INSTRUCTION_UNDEFINED,
-    /* D3 */INSTRUCTION_UNDEFINED,
-    /* D4 */INSTRUCTION_UNDEFINED,
+    /* D2 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* D3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* D4 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* D5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* D6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* D7 */INSTRUCTION_UNDEFINED,
+    /* D7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* D8 */Instruction{ mnem : InstructionMnemonic::InstrCLD , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* D9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* DA */INSTRUCTION_UNDEFINED,
-    /* DB */INSTRUCTION_UNDEFINED,
-    /* DC */INSTRUCTION_UNDEFINED,
+    /* DA */Instruction{ mnem : InstructionMnemonic::InstrPHX , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* DB */Instruction{ mnem : InstructionMnemonic::InstrSTP , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* DC */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* DD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
     /* DE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* DF */INSTRUCTION_UNDEFINED,
+    /* DF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* E0 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* E1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
-    /* E2 */INSTRUCTION_UNDEFINED,
-    /* E3 */INSTRUCTION_UNDEFINED,
+    /* E2 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* E3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* E4 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* E5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
     /* E6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
-    /* E7 */INSTRUCTION_UNDEFINED,
+    /* E7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* E8 */Instruction{ mnem : InstructionMnemonic::InstrINX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* E9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
     /* EA */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
-    /* EB */INSTRUCTION_UNDEFINED,
+    /* EB */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
     /* EC */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* ED */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* EE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
-    /* EF */INSTRUCTION_UNDEFINED,
+    /* EF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
     /* F0 */Instruction{ mnem : InstructionMnemonic::InstrBEQ , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
     /* F1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
-    /* F2 */INSTRUCTION_UNDEFINED,
-    /* F3 */INSTRUCTION_UNDEFINED,
-    /* F4 */INSTRUCTION_UNDEFINED,
+    /* F2 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* F3 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* F4 */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* F5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
     /* F6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
-    /* F7 */INSTRUCTION_UNDEFINED,
+    /* F7 */Instruction{ mnem : InstructionMnemonic::InstrSMB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
     /* F8 */Instruction{ mnem : InstructionMnemonic::InstrSED , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
     /* F9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
-    /* FA */INSTRUCTION_UNDEFINED,
-    /* FB */INSTRUCTION_UNDEFINED,
-    /* FC */INSTRUCTION_UNDEFINED,
+    /* FA */Instruction{ mnem : InstructionMnemonic::InstrPLX , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* FB */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 1, mode : AddressingMode::AddrModeImplied},
+    /* FC */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
     /* FD */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
     /* FE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
-    /* FF */INSTRUCTION_UNDEFINED,
+    /* FF */Instruction{ mnem : InstructionMnemonic::InstrBBS , length : 3, cycles : 5, mode : AddressingMode::AddrModeZPRelative},
 ];
 
-
+// 65C816 decode table. Lengths and cycles are those with 8 bit registers in
+// emulation mode, CPU816 adds the cost of wide registers.
+pub const INSTRUCTION_MATRIX_65816 : [Instruction; crate::NUM_INSTR] = [
+    /* 00 */Instruction{ mnem : InstructionMnemonic::InstrBRK , length : 2, cycles : 7, mode : AddressingMode::AddrModeImmed},
+    /* 01 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 02 */Instruction{ mnem : InstructionMnemonic::InstrCOP , length : 2, cycles : 7, mode : AddressingMode::AddrModeImmed},
+    /* 03 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* 04 */Instruction{ mnem : InstructionMnemonic::InstrTSB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 05 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 06 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 07 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* 08 */Instruction{ mnem : InstructionMnemonic::InstrPHP , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 09 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* 0A */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 0B */Instruction{ mnem : InstructionMnemonic::InstrPHD , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 0C */Instruction{ mnem : InstructionMnemonic::InstrTSB , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 0D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 0E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 0F */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* 10 */Instruction{ mnem : InstructionMnemonic::InstrBPL , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 11 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 12 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 13 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* 14 */Instruction{ mnem : InstructionMnemonic::InstrTRB , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 15 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 16 */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 17 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* 18 */Instruction{ mnem : InstructionMnemonic::InstrCLC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 19 */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 1A */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 1B */Instruction{ mnem : InstructionMnemonic::InstrTCS , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 1C */Instruction{ mnem : InstructionMnemonic::InstrTRB , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 1D */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 1E */Instruction{ mnem : InstructionMnemonic::InstrASL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 1F */Instruction{ mnem : InstructionMnemonic::InstrORA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* 20 */Instruction{ mnem : InstructionMnemonic::InstrJSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 21 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 22 */Instruction{ mnem : InstructionMnemonic::InstrJSL , length : 4, cycles : 8, mode : AddressingMode::AddrModeABSLong},
+    /* 23 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* 24 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 25 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 26 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 27 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* 28 */Instruction{ mnem : InstructionMnemonic::InstrPLP , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 29 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* 2A */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 2B */Instruction{ mnem : InstructionMnemonic::InstrPLD , length : 1, cycles : 5, mode : AddressingMode::AddrModeImplied},
+    /* 2C */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 2D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 2E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 2F */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* 30 */Instruction{ mnem : InstructionMnemonic::InstrBMI , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 31 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 32 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 33 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* 34 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 35 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 36 */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 37 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* 38 */Instruction{ mnem : InstructionMnemonic::InstrSEC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 39 */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 3A */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 3B */Instruction{ mnem : InstructionMnemonic::InstrTSC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 3C */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 3D */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 3E */Instruction{ mnem : InstructionMnemonic::InstrROL , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 3F */Instruction{ mnem : InstructionMnemonic::InstrAND , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* 40 */Instruction{ mnem : InstructionMnemonic::InstrRTI , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
+    /* 41 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 42 */Instruction{ mnem : InstructionMnemonic::InstrWDM , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmed},
+    /* 43 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* 44 */Instruction{ mnem : InstructionMnemonic::InstrMVP , length : 3, cycles : 7, mode : AddressingMode::AddrModeBlockMove},
+    /* 45 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 46 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 47 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* 48 */Instruction{ mnem : InstructionMnemonic::InstrPHA , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 49 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* 4A */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 4B */Instruction{ mnem : InstructionMnemonic::InstrPHK , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 4C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 3, mode : AddressingMode::AddrModeABS},
+    /* 4D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 4E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 4F */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* 50 */Instruction{ mnem : InstructionMnemonic::InstrBVC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 51 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 52 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 53 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* 54 */Instruction{ mnem : InstructionMnemonic::InstrMVN , length : 3, cycles : 7, mode : AddressingMode::AddrModeBlockMove},
+    /* 55 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 56 */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 57 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* 58 */Instruction{ mnem : InstructionMnemonic::InstrCLI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 59 */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 5A */Instruction{ mnem : InstructionMnemonic::InstrPHY , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 5B */Instruction{ mnem : InstructionMnemonic::InstrTCD , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 5C */Instruction{ mnem : InstructionMnemonic::InstrJML , length : 4, cycles : 4, mode : AddressingMode::AddrModeABSLong},
+    /* 5D */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 5E */Instruction{ mnem : InstructionMnemonic::InstrLSR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 5F */Instruction{ mnem : InstructionMnemonic::InstrEOR , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* 60 */Instruction{ mnem : InstructionMnemonic::InstrRTS , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
+    /* 61 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 62 */Instruction{ mnem : InstructionMnemonic::InstrPER , length : 3, cycles : 6, mode : AddressingMode::AddrModeRelativeLong},
+    /* 63 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* 64 */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 65 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 66 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* 67 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* 68 */Instruction{ mnem : InstructionMnemonic::InstrPLA , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 69 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* 6A */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 1, cycles : 2, mode : AddressingMode::AddrModeA},
+    /* 6B */Instruction{ mnem : InstructionMnemonic::InstrRTL , length : 1, cycles : 6, mode : AddressingMode::AddrModeImplied},
+    /* 6C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 5, mode : AddressingMode::AddrModeIndirect},
+    /* 6D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 6E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* 6F */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* 70 */Instruction{ mnem : InstructionMnemonic::InstrBVS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 71 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* 72 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 73 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* 74 */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 75 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 76 */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* 77 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* 78 */Instruction{ mnem : InstructionMnemonic::InstrSEI , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 79 */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* 7A */Instruction{ mnem : InstructionMnemonic::InstrPLY , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* 7B */Instruction{ mnem : InstructionMnemonic::InstrTDC , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 7C */Instruction{ mnem : InstructionMnemonic::InstrJMP , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSIndX},
+    /* 7D */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* 7E */Instruction{ mnem : InstructionMnemonic::InstrROR , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* 7F */Instruction{ mnem : InstructionMnemonic::InstrADC , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* 80 */Instruction{ mnem : InstructionMnemonic::InstrBRA , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 81 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* 82 */Instruction{ mnem : InstructionMnemonic::InstrBRL , length : 3, cycles : 4, mode : AddressingMode::AddrModeRelativeLong},
+    /* 83 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* 84 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 85 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 86 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* 87 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* 88 */Instruction{ mnem : InstructionMnemonic::InstrDEY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 89 */Instruction{ mnem : InstructionMnemonic::InstrBIT , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* 8A */Instruction{ mnem : InstructionMnemonic::InstrTXA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 8B */Instruction{ mnem : InstructionMnemonic::InstrPHB , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* 8C */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8E */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 8F */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* 90 */Instruction{ mnem : InstructionMnemonic::InstrBCC , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* 91 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndY},
+    /* 92 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* 93 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* 94 */Instruction{ mnem : InstructionMnemonic::InstrSTY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 95 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* 96 */Instruction{ mnem : InstructionMnemonic::InstrSTX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* 97 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* 98 */Instruction{ mnem : InstructionMnemonic::InstrTYA , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 99 */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSY},
+    /* 9A */Instruction{ mnem : InstructionMnemonic::InstrTXS , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 9B */Instruction{ mnem : InstructionMnemonic::InstrTXY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* 9C */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* 9D */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
+    /* 9E */Instruction{ mnem : InstructionMnemonic::InstrSTZ , length : 3, cycles : 5, mode : AddressingMode::AddrModeABSX},
+    /* 9F */Instruction{ mnem : InstructionMnemonic::InstrSTA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* A0 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedX},
+    /* A1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* A2 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedX},
+    /* A3 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* A4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* A7 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* A8 */Instruction{ mnem : InstructionMnemonic::InstrTAY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* A9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* AA */Instruction{ mnem : InstructionMnemonic::InstrTAX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* AB */Instruction{ mnem : InstructionMnemonic::InstrPLB , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* AC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* AF */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* B0 */Instruction{ mnem : InstructionMnemonic::InstrBCS , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* B1 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* B2 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* B3 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* B4 */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* B5 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* B6 */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPY},
+    /* B7 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* B8 */Instruction{ mnem : InstructionMnemonic::InstrCLV , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* B9 */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BA */Instruction{ mnem : InstructionMnemonic::InstrTSX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* BB */Instruction{ mnem : InstructionMnemonic::InstrTYX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* BC */Instruction{ mnem : InstructionMnemonic::InstrLDY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* BD */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* BE */Instruction{ mnem : InstructionMnemonic::InstrLDX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* BF */Instruction{ mnem : InstructionMnemonic::InstrLDA , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* C0 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedX},
+    /* C1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* C2 */Instruction{ mnem : InstructionMnemonic::InstrREP , length : 2, cycles : 3, mode : AddressingMode::AddrModeImmed},
+    /* C3 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* C4 */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* C5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* C6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* C7 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* C8 */Instruction{ mnem : InstructionMnemonic::InstrINY , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* C9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* CA */Instruction{ mnem : InstructionMnemonic::InstrDEX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* CB */Instruction{ mnem : InstructionMnemonic::InstrWAI , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* CC */Instruction{ mnem : InstructionMnemonic::InstrCPY , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* CD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* CE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* CF */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* D0 */Instruction{ mnem : InstructionMnemonic::InstrBNE , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* D1 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* D2 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* D3 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* D4 */Instruction{ mnem : InstructionMnemonic::InstrPEI , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPInd},
+    /* D5 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* D6 */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* D7 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* D8 */Instruction{ mnem : InstructionMnemonic::InstrCLD , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* D9 */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* DA */Instruction{ mnem : InstructionMnemonic::InstrPHX , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* DB */Instruction{ mnem : InstructionMnemonic::InstrSTP , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* DC */Instruction{ mnem : InstructionMnemonic::InstrJML , length : 3, cycles : 6, mode : AddressingMode::AddrModeABSIndLong},
+    /* DD */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* DE */Instruction{ mnem : InstructionMnemonic::InstrDEC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* DF */Instruction{ mnem : InstructionMnemonic::InstrCMP , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+    /* E0 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedX},
+    /* E1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 6, mode : AddressingMode::AddrModeIndX},
+    /* E2 */Instruction{ mnem : InstructionMnemonic::InstrSEP , length : 2, cycles : 3, mode : AddressingMode::AddrModeImmed},
+    /* E3 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 4, mode : AddressingMode::AddrModeStackRel},
+    /* E4 */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* E5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 3, mode : AddressingMode::AddrModeZP},
+    /* E6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZP},
+    /* E7 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLong},
+    /* E8 */Instruction{ mnem : InstructionMnemonic::InstrINX , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* E9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 2, mode : AddressingMode::AddrModeImmedM},
+    /* EA */Instruction{ mnem : InstructionMnemonic::InstrNOP , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* EB */Instruction{ mnem : InstructionMnemonic::InstrXBA , length : 1, cycles : 3, mode : AddressingMode::AddrModeImplied},
+    /* EC */Instruction{ mnem : InstructionMnemonic::InstrCPX , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* ED */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABS},
+    /* EE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 6, mode : AddressingMode::AddrModeABS},
+    /* EF */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLong},
+    /* F0 */Instruction{ mnem : InstructionMnemonic::InstrBEQ , length : 2, cycles : 2, mode : AddressingMode::AddrModeRelative},
+    /* F1 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 5, mode : AddressingMode::AddrModeIndY},
+    /* F2 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 5, mode : AddressingMode::AddrModeZPInd},
+    /* F3 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 7, mode : AddressingMode::AddrModeStackRelIndY},
+    /* F4 */Instruction{ mnem : InstructionMnemonic::InstrPEA , length : 3, cycles : 5, mode : AddressingMode::AddrModeABS},
+    /* F5 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 4, mode : AddressingMode::AddrModeZPX},
+    /* F6 */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPX},
+    /* F7 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 2, cycles : 6, mode : AddressingMode::AddrModeZPIndLongY},
+    /* F8 */Instruction{ mnem : InstructionMnemonic::InstrSED , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* F9 */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSY},
+    /* FA */Instruction{ mnem : InstructionMnemonic::InstrPLX , length : 1, cycles : 4, mode : AddressingMode::AddrModeImplied},
+    /* FB */Instruction{ mnem : InstructionMnemonic::InstrXCE , length : 1, cycles : 2, mode : AddressingMode::AddrModeImplied},
+    /* FC */Instruction{ mnem : InstructionMnemonic::InstrJSR , length : 3, cycles : 8, mode : AddressingMode::AddrModeABSIndX},
+    /* FD */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 3, cycles : 4, mode : AddressingMode::AddrModeABSX},
+    /* FE */Instruction{ mnem : InstructionMnemonic::InstrINC , length : 3, cycles : 7, mode : AddressingMode::AddrModeABSX},
+    /* FF */Instruction{ mnem : InstructionMnemonic::InstrSBC , length : 4, cycles : 5, mode : AddressingMode::AddrModeABSLongX},
+];
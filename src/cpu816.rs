@@ -0,0 +1,1072 @@
+use std::fmt;
+
+use crate::bus::{Bus24, FlatRam24};
+use crate::cpu::{AddressingMode, Instruction, InstructionMnemonic, INSTRUCTION_MATRIX_65816};
+use crate::error::{Error, Result};
+use crate::status::StatusFlags;
+
+#[cfg(test)]
+#[path="./cpu816_test.rs"]
+mod cpu816_test;
+
+// Size of the data an instruction works on
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Width {
+    Memory,         // A and memory, 16 bit when M is clear
+    Index,          // X, Y and memory, 16 bit when X is clear
+    Other,
+}
+
+impl InstructionMnemonic {
+    fn width(&self) -> Width {
+        match self {
+            InstructionMnemonic::InstrADC | InstructionMnemonic::InstrAND | InstructionMnemonic::InstrASL
+                | InstructionMnemonic::InstrBIT | InstructionMnemonic::InstrCMP | InstructionMnemonic::InstrDEC
+                | InstructionMnemonic::InstrEOR | InstructionMnemonic::InstrINC | InstructionMnemonic::InstrLDA
+                | InstructionMnemonic::InstrLSR | InstructionMnemonic::InstrORA | InstructionMnemonic::InstrPHA
+                | InstructionMnemonic::InstrPLA | InstructionMnemonic::InstrROL | InstructionMnemonic::InstrROR
+                | InstructionMnemonic::InstrSBC | InstructionMnemonic::InstrSTA | InstructionMnemonic::InstrSTZ
+                | InstructionMnemonic::InstrTRB | InstructionMnemonic::InstrTSB => Width::Memory,
+            InstructionMnemonic::InstrCPX | InstructionMnemonic::InstrCPY | InstructionMnemonic::InstrLDX
+                | InstructionMnemonic::InstrLDY | InstructionMnemonic::InstrPHX | InstructionMnemonic::InstrPHY
+                | InstructionMnemonic::InstrPLX | InstructionMnemonic::InstrPLY | InstructionMnemonic::InstrSTX
+                | InstructionMnemonic::InstrSTY => Width::Index,
+            _ => Width::Other,
+        }
+    }
+
+    // Instructions that only read their operand pay for indexing across a
+    // page, stores and read-modify-write always take the fixed cost
+    fn reads(&self) -> bool {
+        matches!(self,
+            InstructionMnemonic::InstrADC | InstructionMnemonic::InstrAND | InstructionMnemonic::InstrBIT
+            | InstructionMnemonic::InstrCMP | InstructionMnemonic::InstrCPX | InstructionMnemonic::InstrCPY
+            | InstructionMnemonic::InstrEOR | InstructionMnemonic::InstrLDA | InstructionMnemonic::InstrLDX
+            | InstructionMnemonic::InstrLDY | InstructionMnemonic::InstrORA | InstructionMnemonic::InstrSBC)
+    }
+
+    fn modifies(&self) -> bool {
+        matches!(self,
+            InstructionMnemonic::InstrASL | InstructionMnemonic::InstrDEC | InstructionMnemonic::InstrINC
+            | InstructionMnemonic::InstrLSR | InstructionMnemonic::InstrROL | InstructionMnemonic::InstrROR
+            | InstructionMnemonic::InstrTRB | InstructionMnemonic::InstrTSB)
+    }
+}
+
+const STACK_PAGE : u16 = 0x0100;
+
+// Native mode vectors, emulation mode uses the 6502 vectors and the COP
+// vector at $FFF4
+const COP_VEC : u16 = 0xFFE4;
+const BRK_VEC : u16 = 0xFFE6;
+const NMI_VEC : u16 = 0xFFEA;
+const IRQ_VEC : u16 = 0xFFEE;
+const EMU_COP_VEC : u16 = 0xFFF4;
+const EMU_NMI_VEC : u16 = 0xFFFA;
+const EMU_IRQ_VEC : u16 = 0xFFFE;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Interrupt {
+    Cop,
+    Brk,
+    Nmi,
+    Irq,
+}
+
+// Operand resolved by fetch() from the addressing mode of the instruction.
+// Addresses are 24 bit.
+#[derive(Copy, Clone)]
+enum Operand {
+    Implied,
+    Accumulator,
+    Immediate(u16),
+    Address(u32),
+    BlockMove(u8, u8),      // source and destination bank
+}
+
+// 65C816 core. It executes whole instructions and counts cycles per
+// instruction rather than per bus access.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CPU816<B : Bus24 = FlatRam24> {
+    // Program Counter and Program Bank
+    pc : u16,
+    pbr : u8,
+
+    // Stack Pointer, confined to page 1 in emulation mode
+    sp : u16,
+
+    // Accumulator, the high byte is B while A is 8 bit
+    c : u16,
+
+    // Index Register, the high bytes are 0 while X is set
+    x : u16,
+    y : u16,
+
+    // Direct Page and Data Bank
+    dp : u16,
+    dbr : u8,
+
+    // Processor Status Flags and the hidden emulation flag. In emulation
+    // mode M and X read as 1 and bit 4 is the B flag of pushed copies of P.
+    p : StatusFlags,
+    e : bool,
+
+    // Memory map the CPU is attached to
+    bus : B,
+
+    // Instruction decoded by the last fetch()
+    instr : Instruction,
+    operand : Operand,
+
+    // Cycles taken by the current instruction and in total
+    instr_cycles : u8,
+    cycles : u64,
+
+    // Set by STP until reset and by WAI until an interrupt is signalled
+    halted : bool,
+    waiting : bool,
+
+    // Interrupt inputs, combined with the lines of the bus. NMI is edge
+    // triggered and latched in nmi_pending.
+    irq_line : bool,
+    nmi_line : bool,
+    nmi_level : bool,
+    nmi_pending : bool,
+}
+
+impl<B : Bus24> fmt::Debug for CPU816<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CPU816")
+            .field("Program Counter", &format_args!("0x{0:02X}:{1:04X}", &self.pbr, &self.pc))
+            .field("Stack Pointer", &format_args!("0x{0:X}", &self.sp))
+            .field("Register C", &format_args!("0x{0:X}", &self.c))
+            .field("Register X", &format_args!("0x{0:X}", &self.x))
+            .field("Register Y", &format_args!("0x{0:X}", &self.y))
+            .field("Direct Page", &format_args!("0x{0:X}", &self.dp))
+            .field("Data Bank", &format_args!("0x{0:X}", &self.dbr))
+            .field("Status", &format_args!("0x{0:02X}", &self.p.bits()))
+            .field("Emulation", &self.e)
+            .finish()
+    }
+}
+
+impl Default for CPU816 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CPU816 {
+    pub fn new() -> CPU816 {
+        CPU816::with_bus(FlatRam24::new())
+    }
+}
+
+impl<B : Bus24> CPU816<B> {
+    // The CPU powers up in emulation mode, call reset() to load the vector
+    pub fn with_bus(bus : B) -> CPU816<B> {
+        CPU816{
+            pc : 0, pbr : 0, sp : STACK_PAGE, c : 0, x : 0, y : 0, dp : 0, dbr : 0,
+            p : StatusFlags::from_bits_65816(StatusFlags::MEMORY | StatusFlags::INDEX), e : true,
+            bus,
+            instr : INSTRUCTION_MATRIX_65816[0xEA], operand : Operand::Implied,
+            instr_cycles : 0, cycles : 0, halted : false, waiting : false,
+            irq_line : false, nmi_line : false, nmi_level : false, nmi_pending : false,
+        }
+    }
+
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    // Executes a single instruction or takes a pending interrupt and returns
+    // the cycles it took. A stopped CPU takes no cycles, one waiting after
+    // WAI idles for a cycle per step.
+    pub fn step(&mut self) -> u8 {
+        if self.halted {
+            return 0;
+        }
+
+        let nmi = self.nmi_line || self.bus.nmi();
+        if nmi && !self.nmi_level {
+            self.nmi_pending = true;
+        }
+        self.nmi_level = nmi;
+        let irq = self.irq_line || self.bus.irq();
+
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            return self.service_interrupt(Interrupt::Nmi);
+        }
+        if irq && !self.p.interrupt_disable() {
+            return self.service_interrupt(Interrupt::Irq);
+        }
+        if self.waiting {
+            // a masked IRQ ends the wait without being taken
+            if !irq {
+                self.cycles += 1;
+                return 1;
+            }
+            self.waiting = false;
+        }
+
+        self.fetch();
+        self.execute()
+    }
+
+    // Level of the IRQ input, asserted while true
+    pub fn set_irq(&mut self, asserted : bool){
+        self.irq_line = asserted;
+    }
+
+    // Level of the NMI input. Only the transition to asserted triggers an
+    // interrupt.
+    pub fn set_nmi(&mut self, asserted : bool){
+        self.nmi_line = asserted;
+    }
+
+    // Executes instructions until at least the given number of cycles have
+    // elapsed or the CPU stops and returns the number of cycles actually spent
+    pub fn run(&mut self, cycles : u64) -> u64 {
+        let mut elapsed : u64 = 0;
+        while elapsed < cycles && !self.halted {
+            elapsed += self.step() as u64;
+        }
+        elapsed
+    }
+
+    // Switches to emulation mode and loads PC from the reset vector in bank
+    // 0. Takes 7 cycles.
+    pub fn reset(&mut self) -> u8 {
+        self.halted = false;
+        self.waiting = false;
+        self.e = true;
+        self.pbr = 0;
+        self.dbr = 0;
+        self.dp = 0;
+        self.sp = STACK_PAGE | (self.sp & 0x00FF);
+        self.p.set(StatusFlags::MEMORY | StatusFlags::INDEX | StatusFlags::INTERRUPT, true);
+        self.p.set_decimal(false);
+        self.x &= 0x00FF;
+        self.y &= 0x00FF;
+        self.pc = self.read_word(crate::RESET_VEC as u32);
+        self.cycles += 7;
+        7
+    }
+
+    // Copies data into memory starting at a 24 bit address. Nothing is
+    // written unless the whole image fits and every target address is mapped.
+    pub fn mount_mem(&mut self, address : u32, data : &[u8]) -> Result<()> {
+        if (address as usize) + data.len() > crate::MAX_MEM_24 {
+            return Err(Error::DataDoesNotFit { addr : address, len : data.len() });
+        }
+        if let Some(unmapped) = (0..data.len() as u32)
+            .map(|i| address + i)
+            .find(|&addr| !self.bus.is_mapped(addr)) {
+            return Err(Error::UnmappedAddress(unmapped));
+        }
+
+        for (i, byte) in data.iter().enumerate() {
+            self.bus.poke(address + i as u32, *byte);
+        }
+        Ok(())
+    }
+
+    pub fn read_mem(&self, address : u32) -> Result<u8> {
+        if address as usize >= crate::MAX_MEM_24 || !self.bus.is_mapped(address) {
+            return Err(Error::UnmappedAddress(address));
+        }
+        Ok(self.bus.peek(address))
+    }
+
+    pub fn write_mem(&mut self, address : u32, data : u8) -> Result<()> {
+        self.mount_mem(address, &[data])
+    }
+
+    // Whether the CPU has been stopped by STP
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    // Whether the CPU is waiting for an interrupt after WAI
+    pub fn waiting(&self) -> bool {
+        self.waiting
+    }
+
+    // Total number of cycles executed since the CPU was created
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    // Register accessors. a() is the full 16 bit accumulator C.
+    pub fn pc(&self) -> u16 { self.pc }
+    pub fn pbr(&self) -> u8 { self.pbr }
+    pub fn sp(&self) -> u16 { self.sp }
+    pub fn a(&self) -> u16 { self.c }
+    pub fn x(&self) -> u16 { self.x }
+    pub fn y(&self) -> u16 { self.y }
+    pub fn dp(&self) -> u16 { self.dp }
+    pub fn dbr(&self) -> u8 { self.dbr }
+    pub fn status(&self) -> StatusFlags { self.p }
+    pub fn p(&self) -> u8 { self.p.bits() }
+    pub fn emulation(&self) -> bool { self.e }
+
+    pub fn set_pc(&mut self, pc : u16){ self.pc = pc; }
+    pub fn set_pbr(&mut self, pbr : u8){ self.pbr = pbr; }
+    pub fn set_a(&mut self, a : u16){ self.c = a; }
+    pub fn set_dp(&mut self, dp : u16){ self.dp = dp; }
+    pub fn set_dbr(&mut self, dbr : u8){ self.dbr = dbr; }
+
+    pub fn set_sp(&mut self, sp : u16){
+        self.sp = if self.e { STACK_PAGE | (sp & 0x00FF) } else { sp };
+    }
+
+    pub fn set_x(&mut self, x : u16){
+        self.x = if self.p.get(StatusFlags::INDEX) { x & 0x00FF } else { x };
+    }
+
+    pub fn set_y(&mut self, y : u16){
+        self.y = if self.p.get(StatusFlags::INDEX) { y & 0x00FF } else { y };
+    }
+
+    // Setting X truncates the index registers, emulation mode keeps M and X
+    // set
+    pub fn set_p(&mut self, p : u8){
+        let p = if self.e { p | StatusFlags::MEMORY | StatusFlags::INDEX } else { p };
+        self.p = StatusFlags::from_bits_65816(p);
+        if self.p.get(StatusFlags::INDEX) {
+            self.x &= 0x00FF;
+            self.y &= 0x00FF;
+        }
+    }
+
+    // Switches between emulation and native mode like XCE would
+    pub fn set_emulation(&mut self, emulation : bool){
+        self.e = emulation;
+        if emulation {
+            self.set_p(self.p.bits());
+            self.sp = STACK_PAGE | (self.sp & 0x00FF);
+        }
+    }
+
+    // Reads the opcode at PBR:PC and resolves its operand. PC is left
+    // pointing at the next instruction.
+    fn fetch(&mut self){
+        let opcode = self.fetch_byte();
+        self.instr = INSTRUCTION_MATRIX_65816[opcode as usize];
+        self.instr_cycles = self.instr.cycles;
+
+        let data_bank = (self.dbr as u32) << 16;
+        let program_bank = (self.pbr as u32) << 16;
+        self.operand = match self.instr.mode {
+            // the 65C816 table has neither undefined opcodes nor the 65C02
+            // bit branches
+            AddressingMode::AddrModeUndefined | AddressingMode::AddrModeImplied
+                | AddressingMode::AddrModeZPRelative => Operand::Implied,
+            AddressingMode::AddrModeA => Operand::Accumulator,
+            AddressingMode::AddrModeImmed => Operand::Immediate(self.fetch_byte() as u16),
+            AddressingMode::AddrModeImmedM => {
+                let wide = !self.p.get(StatusFlags::MEMORY);
+                Operand::Immediate(self.fetch_immediate(wide))
+            }
+            AddressingMode::AddrModeImmedX => {
+                let wide = !self.p.get(StatusFlags::INDEX);
+                Operand::Immediate(self.fetch_immediate(wide))
+            }
+            AddressingMode::AddrModeABS => {
+                let address = self.fetch_word() as u32;
+                match self.instr.mnem {
+                    InstructionMnemonic::InstrJMP | InstructionMnemonic::InstrJSR => Operand::Address(program_bank | address),
+                    _ => Operand::Address(data_bank | address),
+                }
+            }
+            AddressingMode::AddrModeABSX => {
+                let base = data_bank | self.fetch_word() as u32;
+                Operand::Address(self.index(base, self.x))
+            }
+            AddressingMode::AddrModeABSY => {
+                let base = data_bank | self.fetch_word() as u32;
+                Operand::Address(self.index(base, self.y))
+            }
+            AddressingMode::AddrModeABSLong => Operand::Address(self.fetch_long()),
+            AddressingMode::AddrModeABSLongX => {
+                let base = self.fetch_long();
+                Operand::Address(base.wrapping_add(self.x as u32) & 0xFFFFFF)
+            }
+            AddressingMode::AddrModeIndirect => {
+                let ptr = self.fetch_word();
+                Operand::Address(program_bank | self.read_word_bank0(ptr) as u32)
+            }
+            AddressingMode::AddrModeABSIndX => {
+                let ptr = self.fetch_word().wrapping_add(self.x);
+                let lo = self.read(program_bank | ptr as u32) as u32;
+                let hi = self.read(program_bank | ptr.wrapping_add(1) as u32) as u32;
+                Operand::Address(program_bank | (hi << 8) | lo)
+            }
+            AddressingMode::AddrModeABSIndLong => {
+                let ptr = self.fetch_word();
+                Operand::Address(self.read_long_bank0(ptr))
+            }
+            AddressingMode::AddrModeZP => {
+                let offset = self.fetch_byte();
+                Operand::Address(self.direct(offset, 0) as u32)
+            }
+            AddressingMode::AddrModeZPX => {
+                let offset = self.fetch_byte();
+                Operand::Address(self.direct(offset, self.x) as u32)
+            }
+            AddressingMode::AddrModeZPY => {
+                let offset = self.fetch_byte();
+                Operand::Address(self.direct(offset, self.y) as u32)
+            }
+            AddressingMode::AddrModeZPInd => {
+                let offset = self.fetch_byte();
+                let ptr = self.direct(offset, 0);
+                Operand::Address(data_bank | self.read_word_bank0(ptr) as u32)
+            }
+            AddressingMode::AddrModeIndX => {
+                let offset = self.fetch_byte();
+                let ptr = self.direct(offset, self.x);
+                Operand::Address(data_bank | self.read_word_bank0(ptr) as u32)
+            }
+            AddressingMode::AddrModeIndY => {
+                let offset = self.fetch_byte();
+                let ptr = self.direct(offset, 0);
+                let base = data_bank | self.read_word_bank0(ptr) as u32;
+                Operand::Address(self.index(base, self.y))
+            }
+            AddressingMode::AddrModeZPIndLong => {
+                let offset = self.fetch_byte();
+                let ptr = self.direct(offset, 0);
+                Operand::Address(self.read_long_bank0(ptr))
+            }
+            AddressingMode::AddrModeZPIndLongY => {
+                let offset = self.fetch_byte();
+                let ptr = self.direct(offset, 0);
+                let base = self.read_long_bank0(ptr);
+                Operand::Address(base.wrapping_add(self.y as u32) & 0xFFFFFF)
+            }
+            AddressingMode::AddrModeRelative => {
+                let offset = self.fetch_byte() as i8;
+                Operand::Address(program_bank | self.pc.wrapping_add(offset as u16) as u32)
+            }
+            AddressingMode::AddrModeRelativeLong => {
+                let offset = self.fetch_word();
+                Operand::Address(program_bank | self.pc.wrapping_add(offset) as u32)
+            }
+            AddressingMode::AddrModeStackRel => {
+                let offset = self.fetch_byte();
+                Operand::Address(self.sp.wrapping_add(offset as u16) as u32)
+            }
+            AddressingMode::AddrModeStackRelIndY => {
+                let offset = self.fetch_byte();
+                let ptr = self.sp.wrapping_add(offset as u16);
+                let base = data_bank | self.read_word_bank0(ptr) as u32;
+                Operand::Address(base.wrapping_add(self.y as u32) & 0xFFFFFF)
+            }
+            AddressingMode::AddrModeBlockMove => {
+                let destination = self.fetch_byte();
+                let source = self.fetch_byte();
+                Operand::BlockMove(source, destination)
+            }
+        };
+
+        // 16 bit data takes one more cycle to transfer, two for
+        // read-modify-write on memory
+        let wide = match self.instr.mnem.width() {
+            Width::Memory => !self.p.get(StatusFlags::MEMORY),
+            Width::Index => !self.p.get(StatusFlags::INDEX),
+            Width::Other => false,
+        };
+        if wide {
+            self.instr_cycles += match self.operand {
+                Operand::Accumulator => 0,
+                Operand::Address(_) if self.instr.mnem.modifies() => 2,
+                _ => 1,
+            };
+        }
+    }
+
+    // Executes the instruction decoded by the last fetch() and returns the
+    // number of cycles it took
+    fn execute(&mut self) -> u8 {
+        match self.instr.mnem {
+            // Load / Store
+            InstructionMnemonic::InstrLDA => { let m = self.load(); self.set_accumulator(m); }
+            InstructionMnemonic::InstrLDX => { self.x = self.load(); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrLDY => { self.y = self.load(); self.set_nz_index(self.y); }
+            InstructionMnemonic::InstrSTA => self.store(self.c),
+            InstructionMnemonic::InstrSTX => self.store(self.x),
+            InstructionMnemonic::InstrSTY => self.store(self.y),
+            InstructionMnemonic::InstrSTZ => self.store(0),
+
+            // Register Transfers
+            InstructionMnemonic::InstrTAX => { self.x = self.c & self.index_mask(); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrTAY => { self.y = self.c & self.index_mask(); self.set_nz_index(self.y); }
+            InstructionMnemonic::InstrTXA => self.set_accumulator(self.x),
+            InstructionMnemonic::InstrTYA => self.set_accumulator(self.y),
+            InstructionMnemonic::InstrTXY => { self.y = self.x; self.set_nz_index(self.y); }
+            InstructionMnemonic::InstrTYX => { self.x = self.y; self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrTSX => { self.x = self.sp & self.index_mask(); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrTXS => self.set_sp(self.x),
+            InstructionMnemonic::InstrTCD => { self.dp = self.c; self.set_nz(self.dp, true); }
+            InstructionMnemonic::InstrTDC => { self.c = self.dp; self.set_nz(self.c, true); }
+            InstructionMnemonic::InstrTCS => self.set_sp(self.c),
+            InstructionMnemonic::InstrTSC => { self.c = self.sp; self.set_nz(self.c, true); }
+            InstructionMnemonic::InstrXBA => {
+                self.c = self.c.rotate_left(8);
+                self.set_nz(self.c, false);
+            }
+
+            // Stack
+            InstructionMnemonic::InstrPHA => { let wide = !self.p.get(StatusFlags::MEMORY); self.push_sized(self.c, wide); }
+            InstructionMnemonic::InstrPHX => { let wide = !self.p.get(StatusFlags::INDEX); self.push_sized(self.x, wide); }
+            InstructionMnemonic::InstrPHY => { let wide = !self.p.get(StatusFlags::INDEX); self.push_sized(self.y, wide); }
+            InstructionMnemonic::InstrPLA => {
+                let data = self.pull_sized(!self.p.get(StatusFlags::MEMORY));
+                self.set_accumulator(data);
+            }
+            InstructionMnemonic::InstrPLX => { self.x = self.pull_sized(!self.p.get(StatusFlags::INDEX)); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrPLY => { self.y = self.pull_sized(!self.p.get(StatusFlags::INDEX)); self.set_nz_index(self.y); }
+            InstructionMnemonic::InstrPHB => self.push(self.dbr),
+            InstructionMnemonic::InstrPHK => self.push(self.pbr),
+            InstructionMnemonic::InstrPHD => self.push_native(self.dp as u32, 2),
+            InstructionMnemonic::InstrPHP => self.push(self.pushed_p(true)),
+            InstructionMnemonic::InstrPLB => { self.dbr = self.pull_native(1) as u8; self.set_nz(self.dbr as u16, false); }
+            InstructionMnemonic::InstrPLD => { self.dp = self.pull_native(2) as u16; self.set_nz(self.dp, true); }
+            InstructionMnemonic::InstrPLP => { let p = self.pull(); self.set_p(p); }
+            InstructionMnemonic::InstrPEA | InstructionMnemonic::InstrPEI | InstructionMnemonic::InstrPER => {
+                self.push_native(self.address() & 0xFFFF, 2);
+            }
+
+            // Logical
+            InstructionMnemonic::InstrAND => { let m = self.load(); self.set_accumulator(self.c & m); }
+            InstructionMnemonic::InstrEOR => { let m = self.load(); self.set_accumulator(self.c ^ m); }
+            InstructionMnemonic::InstrORA => { let m = self.load(); self.set_accumulator(self.c | m); }
+            InstructionMnemonic::InstrBIT => {
+                let m = self.load();
+                let wide = !self.p.get(StatusFlags::MEMORY);
+                let sign = if wide { 0x8000 } else { 0x80 };
+                self.p.set_zero(self.c & m & self.memory_mask() == 0);
+                // BIT #imm only affects Z
+                if !matches!(self.operand, Operand::Immediate(_)) {
+                    self.p.set_negative(m & sign != 0);
+                    self.p.set_overflow(m & (sign >> 1) != 0);
+                }
+            }
+
+            // Arithmetic
+            InstructionMnemonic::InstrADC => { let m = self.load(); self.adc(m); }
+            InstructionMnemonic::InstrSBC => { let m = self.load(); self.sbc(m); }
+            InstructionMnemonic::InstrCMP => { let m = self.load(); self.compare(self.c, m, !self.p.get(StatusFlags::MEMORY)); }
+            InstructionMnemonic::InstrCPX => { let m = self.load(); self.compare(self.x, m, !self.p.get(StatusFlags::INDEX)); }
+            InstructionMnemonic::InstrCPY => { let m = self.load(); self.compare(self.y, m, !self.p.get(StatusFlags::INDEX)); }
+
+            // Increments / Decrements
+            InstructionMnemonic::InstrINC => self.modify(Self::inc),
+            InstructionMnemonic::InstrDEC => self.modify(Self::dec),
+            InstructionMnemonic::InstrINX => { self.x = self.x.wrapping_add(1) & self.index_mask(); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrINY => { self.y = self.y.wrapping_add(1) & self.index_mask(); self.set_nz_index(self.y); }
+            InstructionMnemonic::InstrDEX => { self.x = self.x.wrapping_sub(1) & self.index_mask(); self.set_nz_index(self.x); }
+            InstructionMnemonic::InstrDEY => { self.y = self.y.wrapping_sub(1) & self.index_mask(); self.set_nz_index(self.y); }
+
+            // Shifts
+            InstructionMnemonic::InstrASL => self.modify(Self::asl),
+            InstructionMnemonic::InstrLSR => self.modify(Self::lsr),
+            InstructionMnemonic::InstrROL => self.modify(Self::rol),
+            InstructionMnemonic::InstrROR => self.modify(Self::ror),
+            InstructionMnemonic::InstrTRB => self.modify(Self::trb),
+            InstructionMnemonic::InstrTSB => self.modify(Self::tsb),
+
+            // Jumps / Calls
+            InstructionMnemonic::InstrJMP => self.pc = self.address() as u16,
+            InstructionMnemonic::InstrJML => self.jump_long(self.address()),
+            InstructionMnemonic::InstrJSR => {
+                let ret = self.pc.wrapping_sub(1);
+                // JSR (a,x) is new to the 65C816
+                if matches!(self.instr.mode, AddressingMode::AddrModeABSIndX) {
+                    self.push_native(ret as u32, 2);
+                } else {
+                    self.push_word(ret);
+                }
+                self.pc = self.address() as u16;
+            }
+            InstructionMnemonic::InstrJSL => {
+                self.push_native(((self.pbr as u32) << 16) | self.pc.wrapping_sub(1) as u32, 3);
+                self.jump_long(self.address());
+            }
+            InstructionMnemonic::InstrRTS => self.pc = self.pull_word().wrapping_add(1),
+            InstructionMnemonic::InstrRTL => {
+                let ret = self.pull_native(3);
+                self.pc = (ret as u16).wrapping_add(1);
+                self.pbr = (ret >> 16) as u8;
+            }
+            InstructionMnemonic::InstrRTI => {
+                let p = self.pull();
+                self.set_p(p);
+                self.pc = self.pull_word();
+                if !self.e {
+                    self.pbr = self.pull();
+                    self.instr_cycles += 1;
+                }
+            }
+
+            // Branches
+            InstructionMnemonic::InstrBCC => self.branch(!self.p.carry()),
+            InstructionMnemonic::InstrBCS => self.branch(self.p.carry()),
+            InstructionMnemonic::InstrBEQ => self.branch(self.p.zero()),
+            InstructionMnemonic::InstrBMI => self.branch(self.p.negative()),
+            InstructionMnemonic::InstrBNE => self.branch(!self.p.zero()),
+            InstructionMnemonic::InstrBPL => self.branch(!self.p.negative()),
+            InstructionMnemonic::InstrBVC => self.branch(!self.p.overflow()),
+            InstructionMnemonic::InstrBVS => self.branch(self.p.overflow()),
+            InstructionMnemonic::InstrBRA => self.branch(true),
+            InstructionMnemonic::InstrBRL => self.pc = self.address() as u16,
+
+            // Status Flag Changes
+            InstructionMnemonic::InstrCLC => self.p.set_carry(false),
+            InstructionMnemonic::InstrCLD => self.p.set_decimal(false),
+            InstructionMnemonic::InstrCLI => self.p.set_interrupt_disable(false),
+            InstructionMnemonic::InstrCLV => self.p.set_overflow(false),
+            InstructionMnemonic::InstrSEC => self.p.set_carry(true),
+            InstructionMnemonic::InstrSED => self.p.set_decimal(true),
+            InstructionMnemonic::InstrSEI => self.p.set_interrupt_disable(true),
+            InstructionMnemonic::InstrREP => { let m = self.load() as u8; self.set_p(self.p.bits() & !m); }
+            InstructionMnemonic::InstrSEP => { let m = self.load() as u8; self.set_p(self.p.bits() | m); }
+            InstructionMnemonic::InstrXCE => {
+                let carry = self.p.carry();
+                self.p.set_carry(self.e);
+                self.set_emulation(carry);
+            }
+
+            // Block Moves, one byte per execution. The instruction repeats
+            // until C wraps to $FFFF.
+            InstructionMnemonic::InstrMVN | InstructionMnemonic::InstrMVP => {
+                if let Operand::BlockMove(source, destination) = self.operand {
+                    let data = self.read(((source as u32) << 16) | self.x as u32);
+                    self.write(((destination as u32) << 16) | self.y as u32, data);
+                    self.dbr = destination;
+                    let step = if matches!(self.instr.mnem, InstructionMnemonic::InstrMVN) { 1 } else { 0xFFFF };
+                    self.x = self.x.wrapping_add(step) & self.index_mask();
+                    self.y = self.y.wrapping_add(step) & self.index_mask();
+                    self.c = self.c.wrapping_sub(1);
+                    if self.c != 0xFFFF {
+                        self.pc = self.pc.wrapping_sub(3);
+                    }
+                }
+            }
+
+            // System Functions
+            InstructionMnemonic::InstrBRK => return self.interrupt_sequence(Interrupt::Brk),
+            InstructionMnemonic::InstrCOP => return self.interrupt_sequence(Interrupt::Cop),
+            InstructionMnemonic::InstrNOP | InstructionMnemonic::InstrWDM => {}
+            InstructionMnemonic::InstrWAI => self.waiting = true,
+            InstructionMnemonic::InstrSTP => self.halted = true,
+
+            // Only in the 6502 and 65C02 tables
+            InstructionMnemonic::InstrUndefined | InstructionMnemonic::InstrALR | InstructionMnemonic::InstrANC
+                | InstructionMnemonic::InstrANE | InstructionMnemonic::InstrARR | InstructionMnemonic::InstrDCP
+                | InstructionMnemonic::InstrISC | InstructionMnemonic::InstrJAM | InstructionMnemonic::InstrLAS
+                | InstructionMnemonic::InstrLAX | InstructionMnemonic::InstrLXA | InstructionMnemonic::InstrRLA
+                | InstructionMnemonic::InstrRRA | InstructionMnemonic::InstrSAX | InstructionMnemonic::InstrSBX
+                | InstructionMnemonic::InstrSHA | InstructionMnemonic::InstrSHX | InstructionMnemonic::InstrSHY
+                | InstructionMnemonic::InstrSLO | InstructionMnemonic::InstrSRE | InstructionMnemonic::InstrTAS
+                | InstructionMnemonic::InstrBBR | InstructionMnemonic::InstrBBS | InstructionMnemonic::InstrRMB
+                | InstructionMnemonic::InstrSMB => {}
+        }
+
+        self.cycles += self.instr_cycles as u64;
+        self.instr_cycles
+    }
+
+    // Takes a hardware interrupt between instructions. Unlike BRK/COP no
+    // opcode has been fetched, so the whole sequence is counted here.
+    fn service_interrupt(&mut self, interrupt : Interrupt) -> u8 {
+        self.waiting = false;
+        self.instr_cycles = 7;
+        self.interrupt_sequence(interrupt)
+    }
+
+    // Pushes the return address and P and jumps through the vector of the
+    // interrupt in bank 0. Native mode also pushes PBR, one cycle more.
+    fn interrupt_sequence(&mut self, interrupt : Interrupt) -> u8 {
+        let brk = matches!(interrupt, Interrupt::Brk | Interrupt::Cop);
+        if !self.e {
+            self.push(self.pbr);
+            self.instr_cycles += 1;
+        }
+        self.push_word(self.pc);
+        self.push(self.pushed_p(brk));
+        self.p.set_interrupt_disable(true);
+        self.p.set_decimal(false);
+
+        let vector = match (interrupt, self.e) {
+            (Interrupt::Cop, false) => COP_VEC,
+            (Interrupt::Brk, false) => BRK_VEC,
+            (Interrupt::Nmi, false) => NMI_VEC,
+            (Interrupt::Irq, false) => IRQ_VEC,
+            (Interrupt::Cop, true) => EMU_COP_VEC,
+            (Interrupt::Nmi, true) => EMU_NMI_VEC,
+            (Interrupt::Brk, true) | (Interrupt::Irq, true) => EMU_IRQ_VEC,
+        };
+        self.pbr = 0;
+        self.pc = self.read_word(vector as u32);
+
+        self.cycles += self.instr_cycles as u64;
+        self.instr_cycles
+    }
+
+    // P as pushed to the stack. In emulation mode bit 5 reads as 1 and bit
+    // 4 is the B flag.
+    fn pushed_p(&self, brk : bool) -> u8 {
+        match (self.e, brk) {
+            (true, true) => self.p.bits() | StatusFlags::MEMORY | StatusFlags::INDEX,
+            (true, false) => (self.p.bits() | StatusFlags::MEMORY) & !StatusFlags::INDEX,
+            (false, _) => self.p.bits(),
+        }
+    }
+
+    fn memory_mask(&self) -> u16 {
+        if self.p.get(StatusFlags::MEMORY) { 0x00FF } else { 0xFFFF }
+    }
+
+    fn index_mask(&self) -> u16 {
+        if self.p.get(StatusFlags::INDEX) { 0x00FF } else { 0xFFFF }
+    }
+
+    // Data width of the current instruction
+    fn wide(&self) -> bool {
+        match self.instr.mnem.width() {
+            Width::Index => !self.p.get(StatusFlags::INDEX),
+            _ => !self.p.get(StatusFlags::MEMORY),
+        }
+    }
+
+    fn read(&mut self, address : u32) -> u8 {
+        self.bus.read(address & 0xFFFFFF)
+    }
+
+    fn write(&mut self, address : u32, data : u8){
+        self.bus.write(address & 0xFFFFFF, data);
+    }
+
+    fn read_word(&mut self, address : u32) -> u16 {
+        let lo = self.read(address) as u16;
+        let hi = self.read(address.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    // Pointers in bank 0 (direct page, stack, JMP (a)) wrap within the bank
+    fn read_word_bank0(&mut self, address : u16) -> u16 {
+        let lo = self.read(address as u32) as u16;
+        let hi = self.read(address.wrapping_add(1) as u32) as u16;
+        (hi << 8) | lo
+    }
+
+    fn read_long_bank0(&mut self, address : u16) -> u32 {
+        let word = self.read_word_bank0(address) as u32;
+        let bank = self.read(address.wrapping_add(2) as u32) as u32;
+        (bank << 16) | word
+    }
+
+    // The program counter wraps within the program bank
+    fn fetch_byte(&mut self) -> u8 {
+        let data = self.read(((self.pbr as u32) << 16) | self.pc as u32);
+        self.pc = self.pc.wrapping_add(1);
+        data
+    }
+
+    fn fetch_word(&mut self) -> u16 {
+        let lo = self.fetch_byte() as u16;
+        let hi = self.fetch_byte() as u16;
+        (hi << 8) | lo
+    }
+
+    fn fetch_long(&mut self) -> u32 {
+        let word = self.fetch_word() as u32;
+        let bank = self.fetch_byte() as u32;
+        (bank << 16) | word
+    }
+
+    fn fetch_immediate(&mut self, wide : bool) -> u16 {
+        if wide { self.fetch_word() } else { self.fetch_byte() as u16 }
+    }
+
+    // Direct page address of an operand. Direct page accesses cost one more
+    // cycle unless DP is page aligned. In emulation mode with DL = 0 indexing
+    // wraps within the page as on the 6502.
+    fn direct(&mut self, offset : u8, index : u16) -> u16 {
+        if self.dp & 0x00FF != 0 {
+            self.instr_cycles += 1;
+        } else if self.e {
+            return self.dp | ((offset as u16).wrapping_add(index) & 0x00FF);
+        }
+        self.dp.wrapping_add(offset as u16).wrapping_add(index)
+    }
+
+    // Indexed reads take one more cycle when they cross a page or the index
+    // registers are 16 bit
+    fn index(&mut self, base : u32, index : u16) -> u32 {
+        let address = base.wrapping_add(index as u32) & 0xFFFFFF;
+        if self.instr.mnem.reads() && ((base ^ address) & 0xFFFF00 != 0 || !self.p.get(StatusFlags::INDEX)) {
+            self.instr_cycles += 1;
+        }
+        address
+    }
+
+    fn address(&self) -> u32 {
+        match self.operand {
+            Operand::Address(address) => address,
+            _ => 0,
+        }
+    }
+
+    fn load(&mut self) -> u16 {
+        match self.operand {
+            Operand::Implied | Operand::BlockMove(..) => 0,
+            Operand::Accumulator => self.c & self.memory_mask(),
+            Operand::Immediate(data) => data,
+            Operand::Address(address) => {
+                if self.wide() { self.read_word(address) } else { self.read(address) as u16 }
+            }
+        }
+    }
+
+    fn store(&mut self, data : u16){
+        if let Operand::Address(address) = self.operand {
+            self.write(address, data as u8);
+            if self.wide() {
+                self.write(address.wrapping_add(1), (data >> 8) as u8);
+            }
+        }
+    }
+
+    // Read-modify-write on either the accumulator or memory
+    fn modify(&mut self, op : fn(&mut CPU816<B>, u16) -> u16){
+        let data = self.load();
+        let result = op(self, data);
+        match self.operand {
+            Operand::Accumulator => {
+                let mask = self.memory_mask();
+                self.c = (self.c & !mask) | (result & mask);
+            }
+            _ => self.store(result),
+        }
+    }
+
+    fn inc(&mut self, data : u16) -> u16 {
+        let result = data.wrapping_add(1) & self.memory_mask();
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn dec(&mut self, data : u16) -> u16 {
+        let result = data.wrapping_sub(1) & self.memory_mask();
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn asl(&mut self, data : u16) -> u16 {
+        let sign = (self.memory_mask() >> 1) + 1;
+        self.p.set_carry(data & sign != 0);
+        let result = (data << 1) & self.memory_mask();
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn lsr(&mut self, data : u16) -> u16 {
+        self.p.set_carry(data & 0x0001 != 0);
+        let result = data >> 1;
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn rol(&mut self, data : u16) -> u16 {
+        let sign = (self.memory_mask() >> 1) + 1;
+        let carry = self.p.carry() as u16;
+        self.p.set_carry(data & sign != 0);
+        let result = ((data << 1) | carry) & self.memory_mask();
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn ror(&mut self, data : u16) -> u16 {
+        let sign = (self.memory_mask() >> 1) + 1;
+        let carry = if self.p.carry() { sign } else { 0 };
+        self.p.set_carry(data & 0x0001 != 0);
+        let result = (data >> 1) | carry;
+        self.set_nz(result, !self.p.get(StatusFlags::MEMORY));
+        result
+    }
+
+    fn trb(&mut self, data : u16) -> u16 {
+        self.p.set_zero(self.c & data & self.memory_mask() == 0);
+        data & !self.c
+    }
+
+    fn tsb(&mut self, data : u16) -> u16 {
+        self.p.set_zero(self.c & data & self.memory_mask() == 0);
+        data | self.c
+    }
+
+    // Taken branches cost one extra cycle, in emulation mode one more if
+    // the target is on another page than the next instruction
+    fn branch(&mut self, condition : bool){
+        if condition {
+            let target = self.address() as u16;
+            self.instr_cycles += 1;
+            if self.e && (target ^ self.pc) & 0xFF00 != 0 {
+                self.instr_cycles += 1;
+            }
+            self.pc = target;
+        }
+    }
+
+    fn jump_long(&mut self, address : u32){
+        self.pbr = (address >> 16) as u8;
+        self.pc = address as u16;
+    }
+
+    // Binary or BCD addition of 8 or 16 bit operands as selected by M.
+    // Decimal mode works digit by digit and sets N and Z from the result.
+    fn adc(&mut self, data : u16){
+        let wide = !self.p.get(StatusFlags::MEMORY);
+        let mask = self.memory_mask() as u32;
+        let sign = (mask >> 1) + 1;
+        let a = self.c as u32 & mask;
+        let m = data as u32 & mask;
+        let mut carry = self.p.carry() as u32;
+
+        let result = if self.p.decimal() {
+            let mut result = 0;
+            for shift in (0..if wide { 16 } else { 8 }).step_by(4) {
+                let mut digit = ((a >> shift) & 0x0F) + ((m >> shift) & 0x0F) + carry;
+                carry = (digit > 0x09) as u32;
+                if carry != 0 {
+                    digit += 0x06;
+                }
+                result |= (digit & 0x0F) << shift;
+            }
+            result
+        } else {
+            let sum = a + m + carry;
+            carry = (sum > mask) as u32;
+            sum & mask
+        };
+
+        self.p.set_overflow(!(a ^ m) & (a ^ result) & sign != 0);
+        self.p.set_carry(carry != 0);
+        self.set_accumulator(result as u16);
+    }
+
+    fn sbc(&mut self, data : u16){
+        if !self.p.decimal() {
+            self.adc(!data);
+            return;
+        }
+
+        let wide = !self.p.get(StatusFlags::MEMORY);
+        let mask = self.memory_mask() as i32;
+        let sign = (mask >> 1) + 1;
+        let a = self.c as i32 & mask;
+        let m = data as i32 & mask;
+        let mut borrow = !self.p.carry() as i32;
+
+        let binary = a - m - borrow;
+        let mut result = 0;
+        for shift in (0..if wide { 16 } else { 8 }).step_by(4) {
+            let mut digit = ((a >> shift) & 0x0F) - ((m >> shift) & 0x0F) - borrow;
+            borrow = (digit < 0) as i32;
+            if borrow != 0 {
+                digit += 0x0A;
+            }
+            result |= (digit & 0x0F) << shift;
+        }
+
+        self.p.set_overflow((a ^ m) & (a ^ binary) & sign != 0);
+        self.p.set_carry(borrow == 0);
+        self.set_accumulator(result as u16);
+    }
+
+    fn compare(&mut self, register : u16, data : u16, wide : bool){
+        let mask = if wide { 0xFFFF } else { 0x00FF };
+        let register = register & mask;
+        let data = data & mask;
+        self.p.set_carry(register >= data);
+        self.set_nz(register.wrapping_sub(data) & mask, wide);
+    }
+
+    // Writes A, leaving B alone while M is set, and sets N and Z
+    fn set_accumulator(&mut self, data : u16){
+        let mask = self.memory_mask();
+        self.c = (self.c & !mask) | (data & mask);
+        self.set_nz(data & mask, mask == 0xFFFF);
+    }
+
+    fn set_nz_index(&mut self, data : u16){
+        self.set_nz(data, !self.p.get(StatusFlags::INDEX));
+    }
+
+    fn set_nz(&mut self, data : u16, wide : bool){
+        let data = if wide { data } else { data & 0x00FF };
+        let sign = if wide { 0x8000 } else { 0x0080 };
+        self.p.set_negative(data & sign != 0);
+        self.p.set_zero(data == 0);
+    }
+
+    // The stack lives in bank 0. In emulation mode it wraps within page 1.
+    fn push(&mut self, data : u8){
+        self.write(self.sp as u32, data);
+        self.set_sp(self.sp.wrapping_sub(1));
+    }
+
+    fn pull(&mut self) -> u8 {
+        self.set_sp(self.sp.wrapping_add(1));
+        self.read(self.sp as u32)
+    }
+
+    fn push_word(&mut self, data : u16){
+        self.push((data >> 8) as u8);
+        self.push(data as u8);
+    }
+
+    fn pull_word(&mut self) -> u16 {
+        let lo = self.pull() as u16;
+        let hi = self.pull() as u16;
+        (hi << 8) | lo
+    }
+
+    // The instructions new to the 65C816 address the stack with the full 16
+    // bit SP even in emulation mode, where SP only returns to page 1 once
+    // all bytes have been transferred. Pushes the low bytes of data, most
+    // significant first.
+    fn push_native(&mut self, data : u32, bytes : u8){
+        for i in (0..bytes).rev() {
+            self.write(self.sp as u32, (data >> (8 * i)) as u8);
+            self.sp = self.sp.wrapping_sub(1);
+        }
+        self.set_sp(self.sp);
+    }
+
+    fn pull_native(&mut self, bytes : u8) -> u32 {
+        let mut data = 0;
+        for i in 0..bytes {
+            self.sp = self.sp.wrapping_add(1);
+            data |= (self.read(self.sp as u32) as u32) << (8 * i);
+        }
+        self.set_sp(self.sp);
+        data
+    }
+
+    fn push_sized(&mut self, data : u16, wide : bool){
+        if wide { self.push_word(data) } else { self.push(data as u8) }
+    }
+
+    fn pull_sized(&mut self, wide : bool) -> u16 {
+        if wide { self.pull_word() } else { self.pull() as u16 }
+    }
+}
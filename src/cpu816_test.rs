@@ -0,0 +1,246 @@
+use super::*;
+
+// Runs code placed at $00:8000 from reset, optionally switched to native
+// mode first
+fn run_at(code : &[u8], instructions : usize, native : bool) -> CPU816 {
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(crate::RESET_VEC as u32, &[0x00, 0x80]).unwrap();
+    cpu.mount_mem(0x8000, code).unwrap();
+    cpu.reset();
+    cpu.set_emulation(!native);
+    for _ in 0..instructions {
+        cpu.step();
+    }
+    cpu
+}
+
+fn carry(cpu : &CPU816) -> bool {
+    cpu.p() & StatusFlags::CARRY != 0
+}
+
+#[test]
+fn test_reset_enters_emulation_mode(){
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(0xFFFC, &[0x34, 0x12]).unwrap();
+    cpu.set_emulation(false);
+    cpu.set_p(0x08);
+    cpu.set_x(0x1234);
+    assert_eq!(cpu.reset(), 7);
+    assert!(cpu.emulation());
+    assert_eq!(cpu.pc(), 0x1234);
+    assert_eq!(cpu.p(), 0x34);
+    assert_eq!(cpu.sp() & 0xFF00, 0x0100);
+    assert_eq!(cpu.x(), 0x0034);
+}
+
+#[test]
+fn test_xce_and_rep_select_wide_registers(){
+    // CLC; XCE; REP #$30; LDA #$1234; LDX #$ABCD; TAY
+    let mut cpu = run_at(&[0x18, 0xFB, 0xC2, 0x30, 0xA9, 0x34, 0x12, 0xA2, 0xCD, 0xAB, 0xA8], 3, false);
+    assert!(!cpu.emulation());
+    assert!(carry(&cpu));
+    assert_eq!(cpu.step(), 3);
+    assert_eq!(cpu.step(), 3);
+    cpu.step();
+    assert_eq!((cpu.a(), cpu.x(), cpu.y()), (0x1234, 0xABCD, 0x1234));
+    assert_eq!(cpu.pc(), 0x800B);
+
+    // SEP #$10 truncates the index registers
+    cpu.mount_mem(0x800B, &[0xE2, 0x10, 0xE8]).unwrap();
+    cpu.step();
+    cpu.step();
+    assert_eq!((cpu.x(), cpu.y()), (0x00CE, 0x0034));
+}
+
+#[test]
+fn test_8_bit_accumulator_keeps_b(){
+    // LDA #$12; XBA; LDA #$34; INC A
+    let cpu = run_at(&[0xA9, 0x12, 0xEB, 0xA9, 0x34, 0x1A], 4, true);
+    assert_eq!(cpu.a(), 0x1235);
+}
+
+#[test]
+fn test_wide_arithmetic(){
+    // REP #$20; CLC; LDA #$FFFF; ADC #$0001
+    let cpu = run_at(&[0xC2, 0x20, 0x18, 0xA9, 0xFF, 0xFF, 0x69, 0x01, 0x00], 4, true);
+    assert_eq!(cpu.a(), 0x0000);
+    assert_eq!(cpu.p() & 0x03, 0x03);
+
+    // REP #$20; SED; CLC; LDA #$1999; ADC #$0001; SEC; SBC #$0001
+    let code = [0xC2, 0x20, 0xF8, 0x18, 0xA9, 0x99, 0x19, 0x69, 0x01, 0x00, 0x38, 0xE9, 0x01, 0x00];
+    let mut cpu = run_at(&code, 5, true);
+    assert_eq!(cpu.a(), 0x2000);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a(), 0x1999);
+    assert!(carry(&cpu));
+}
+
+#[test]
+fn test_long_and_bank_addressing(){
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(0x123456, &[0x42]).unwrap();
+    cpu.mount_mem(0x7E2005, &[0x43]).unwrap();
+    cpu.mount_mem(0x0010, &[0x56, 0x34, 0x12]).unwrap();
+    // LDA $123456; LDX #$05; LDA $2000,X; LDA [$10]
+    cpu.mount_mem(0x0200, &[0xAF, 0x56, 0x34, 0x12, 0xA2, 0x05, 0xBD, 0x00, 0x20, 0xA7, 0x10]).unwrap();
+    cpu.set_pc(0x0200);
+    cpu.set_dbr(0x7E);
+    assert_eq!(cpu.step(), 5);
+    assert_eq!(cpu.a(), 0x42);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.a(), 0x43);
+    cpu.set_a(0);
+    assert_eq!(cpu.step(), 6);
+    assert_eq!(cpu.a(), 0x42);
+}
+
+#[test]
+fn test_direct_page_and_stack_relative(){
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(0x1234, &[0x77]).unwrap();
+    // LDA $34; PHA; LDA #$00; LDA $01,S
+    cpu.mount_mem(0x0200, &[0xA5, 0x34, 0x48, 0xA9, 0x00, 0xA3, 0x01]).unwrap();
+    cpu.set_pc(0x0200);
+    cpu.set_emulation(false);
+    cpu.set_sp(0x1FFF);
+    cpu.set_dp(0x1200);
+    assert_eq!(cpu.step(), 3);
+    assert_eq!(cpu.a(), 0x77);
+
+    // a direct page that is not page aligned costs a cycle
+    cpu.set_dp(0x1201);
+    cpu.set_pc(0x0200);
+    cpu.mount_mem(0x1235, &[0x66]).unwrap();
+    assert_eq!(cpu.step(), 4);
+    assert_eq!(cpu.a(), 0x66);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.step(), 4);
+    assert_eq!((cpu.a(), cpu.sp()), (0x66, 0x1FFE));
+}
+
+#[test]
+fn test_jsl_rtl(){
+    let mut cpu = CPU816::new();
+    // $00:0200 JSL $018000; $01:8000 RTL
+    cpu.mount_mem(0x0200, &[0x22, 0x00, 0x80, 0x01]).unwrap();
+    cpu.mount_mem(0x018000, &[0x6B]).unwrap();
+    cpu.set_pc(0x0200);
+    cpu.set_emulation(false);
+    cpu.set_sp(0x01FF);
+    assert_eq!(cpu.step(), 8);
+    assert_eq!((cpu.pbr(), cpu.pc(), cpu.sp()), (0x01, 0x8000, 0x01FC));
+    assert_eq!(cpu.read_mem(0x01FF).unwrap(), 0x00);
+    assert_eq!(cpu.read_mem(0x01FE).unwrap(), 0x02);
+    assert_eq!(cpu.read_mem(0x01FD).unwrap(), 0x03);
+    cpu.step();
+    assert_eq!((cpu.pbr(), cpu.pc(), cpu.sp()), (0x00, 0x0204, 0x01FF));
+}
+
+#[test]
+fn test_new_instructions_leave_page_1(){
+    // PEA $1234 at SP=$0100 in emulation mode writes below page 1, SP
+    // returns to page 1 afterwards
+    let mut cpu = run_at(&[0xF4, 0x34, 0x12, 0x48], 0, false);
+    cpu.set_sp(0x0100);
+    cpu.step();
+    assert_eq!((cpu.read_mem(0x0100).unwrap(), cpu.read_mem(0x00FF).unwrap()), (0x12, 0x34));
+    assert_eq!(cpu.read_mem(0x01FF).unwrap(), 0x00);
+    assert_eq!(cpu.sp(), 0x01FE);
+
+    // PHA still wraps inside page 1
+    cpu.set_sp(0x0100);
+    cpu.set_a(0x56);
+    cpu.step();
+    assert_eq!(cpu.sp(), 0x01FF);
+    assert_eq!(cpu.read_mem(0x0100).unwrap(), 0x56);
+}
+
+#[test]
+fn test_block_move(){
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(0x011000, &[1, 2, 3, 4]).unwrap();
+    // REP #$30; MVN $02,$01
+    cpu.mount_mem(0x0200, &[0xC2, 0x30, 0x54, 0x02, 0x01]).unwrap();
+    cpu.set_pc(0x0200);
+    cpu.set_emulation(false);
+    cpu.step();
+    cpu.set_a(3);
+    cpu.set_x(0x1000);
+    cpu.set_y(0x2000);
+    assert_eq!(cpu.run(28), 28);
+    assert_eq!(cpu.pc(), 0x0205);
+    assert_eq!((cpu.a(), cpu.x(), cpu.y(), cpu.dbr()), (0xFFFF, 0x1004, 0x2004, 0x02));
+    for i in 0..4 {
+        assert_eq!(cpu.read_mem(0x022000 + i).unwrap(), i as u8 + 1);
+    }
+}
+
+#[test]
+fn test_interrupt_vectors(){
+    let mut cpu = CPU816::new();
+    cpu.mount_mem(0xFFE6, &[0x00, 0x90]).unwrap();
+    cpu.mount_mem(0xFFFE, &[0x00, 0xA0]).unwrap();
+    cpu.mount_mem(0x9000, &[0x40]).unwrap();
+    // $03:0200 BRK #$00
+    cpu.mount_mem(0x030200, &[0x00, 0x00]).unwrap();
+    cpu.set_pbr(0x03);
+    cpu.set_pc(0x0200);
+    cpu.set_emulation(false);
+    cpu.set_sp(0x01FF);
+    cpu.set_p(0x08);
+
+    // native BRK also pushes PBR and clears D
+    assert_eq!(cpu.step(), 8);
+    assert_eq!((cpu.pbr(), cpu.pc()), (0x00, 0x9000));
+    assert_eq!(cpu.p() & 0x0C, 0x04);
+    assert_eq!(cpu.read_mem(0x01FF).unwrap(), 0x03);
+    assert_eq!(cpu.step(), 7);
+    assert_eq!((cpu.pbr(), cpu.pc(), cpu.p()), (0x03, 0x0202, 0x08));
+
+    // emulation mode IRQ goes through the 6502 vector with B clear
+    cpu.set_pbr(0x00);
+    cpu.set_emulation(true);
+    cpu.set_p(0x00);
+    cpu.set_irq(true);
+    assert_eq!(cpu.step(), 7);
+    assert_eq!(cpu.pc(), 0xA000);
+    assert_eq!(cpu.read_mem(0x01FD).unwrap() & 0x30, 0x20);
+}
+
+#[test]
+fn test_cycle_penalties(){
+    // LDX #$FF; LDA $20F0,X crosses a page in emulation mode
+    let mut cpu = run_at(&[0xA2, 0xFF, 0xBD, 0xF0, 0x20], 1, false);
+    assert_eq!(cpu.step(), 5);
+
+    // REP #$10; LDX #$0001; LDA $2000,X pays for the 16 bit index
+    let mut cpu = run_at(&[0xC2, 0x10, 0xA2, 0x01, 0x00, 0xBD, 0x00, 0x20], 2, true);
+    assert_eq!(cpu.step(), 5);
+
+    // REP #$20; ASL $10 and INC A with a 16 bit accumulator
+    let mut cpu = run_at(&[0xC2, 0x20, 0x06, 0x10, 0x1A], 1, true);
+    assert_eq!(cpu.step(), 7);
+    assert_eq!(cpu.step(), 2);
+
+    // BRA takes 3 cycles, one more when it crosses a page in emulation mode
+    assert_eq!(run_at(&[0x80, 0x02], 0, false).step(), 3);
+    assert_eq!(run_at(&[0x80, 0x80], 0, false).step(), 4);
+    assert_eq!(run_at(&[0x80, 0x80], 0, true).step(), 3);
+}
+
+#[test]
+fn test_wai_and_stp(){
+    // SEI; WAI; INX; STP
+    let mut cpu = run_at(&[0x78, 0xCB, 0xE8, 0xDB], 2, true);
+    assert!(cpu.waiting());
+    assert_eq!(cpu.step(), 1);
+    cpu.set_irq(true);
+    cpu.step();
+    assert_eq!(cpu.x(), 1);
+    cpu.step();
+    assert!(cpu.halted());
+    assert_eq!(cpu.step(), 0);
+}
@@ -2,20 +2,840 @@ use super::*;
 
 #[test]
 fn test_read_and_write(){
-    let mut cpu : CPU = new();
+    let mut cpu : CPU = CPU::new();
     let test_data : [u8; 2] = [0xde, 0xad];
-    cpu.mount_mem(0x1000, &test_data);
-    let test_byte1 = cpu.read_mem(0x1000);
-    let test_byte2 = cpu.read_mem(0x1001);
-    assert_eq!(test_byte1.clone(), 0xde);
-    assert_eq!(test_byte2.clone(), 0xad);
+    cpu.mount_mem(0x1000, &test_data).unwrap();
+    let test_byte1 = cpu.read_mem(0x1000).unwrap();
+    let test_byte2 = cpu.read_mem(0x1001).unwrap();
+    assert_eq!(test_byte1, 0xde);
+    assert_eq!(test_byte2, 0xad);
 }
 
 #[test]
 fn test_initialized_memory(){
-    let cpu : CPU = new();
-    let test_byte1 = cpu.read_mem(0);
-    let test_byte2 = cpu.read_mem((rs6502::MAX_MEM - 1) as u16);
-    assert_eq!(test_byte1.clone(), 0);
-    assert_eq!(test_byte2.clone(), 0);
-}
\ No newline at end of file
+    let cpu : CPU = CPU::new();
+    let test_byte1 = cpu.read_mem(0).unwrap();
+    let test_byte2 = cpu.read_mem((crate::MAX_MEM - 1) as u16).unwrap();
+    assert_eq!(test_byte1, 0);
+    assert_eq!(test_byte2, 0);
+}
+
+fn run(cpu : &mut CPU, instructions : usize) -> u32 {
+    let mut cycles = 0;
+    for _ in 0..instructions {
+        cpu.fetch();
+        cycles += cpu.execute() as u32;
+    }
+    cycles
+}
+
+#[test]
+fn test_instruction_lengths_match_addressing_modes(){
+    for (opcode, instr) in INSTRUCTION_MATRIX.iter().chain(INSTRUCTION_MATRIX_65C02.iter()).enumerate() {
+        let expected = match instr.mode {
+            AddressingMode::AddrModeUndefined => 0,
+            AddressingMode::AddrModeA | AddressingMode::AddrModeImplied => 1,
+            AddressingMode::AddrModeABS | AddressingMode::AddrModeABSX | AddressingMode::AddrModeABSY
+                | AddressingMode::AddrModeIndirect | AddressingMode::AddrModeZPRelative
+                | AddressingMode::AddrModeABSIndX => 3,
+            _ => 2,
+        };
+        assert_eq!(instr.length, expected, "opcode {:02X}", opcode & 0xFF);
+    }
+}
+
+#[test]
+fn test_counting_loop(){
+    let mut cpu : CPU = CPU::new();
+    // LDX #$05; LDA #$00; CLC; ADC #$03; DEX; BNE -5; STA $0200
+    cpu.mount_mem(0x0600, &[0xA2, 0x05, 0xA9, 0x00, 0x18, 0x69, 0x03, 0xCA, 0xD0, 0xFA, 0x8D, 0x00, 0x02]).unwrap();
+    cpu.pc = 0x0600;
+    run(&mut cpu, 2 + 5 * 4 + 1);
+    assert_eq!(cpu.read_mem(0x0200).unwrap(), 15);
+    assert_eq!(cpu.x, 0);
+    assert!(cpu.zero());
+    assert_eq!(cpu.pc, 0x060D);
+}
+
+#[test]
+fn test_adc_sbc_flags(){
+    let mut cpu : CPU = CPU::new();
+    // LDA #$7F; CLC; ADC #$01; SEC; SBC #$01
+    cpu.mount_mem(0x0600, &[0xA9, 0x7F, 0x18, 0x69, 0x01, 0x38, 0xE9, 0x01]).unwrap();
+    cpu.pc = 0x0600;
+    run(&mut cpu, 3);
+    assert_eq!((cpu.a, cpu.negative(), cpu.overflow(), cpu.carry()), (0x80, true, true, false));
+    run(&mut cpu, 2);
+    assert_eq!((cpu.a, cpu.negative(), cpu.overflow(), cpu.carry()), (0x7F, false, true, true));
+}
+
+#[test]
+fn test_jsr_rts(){
+    let mut cpu : CPU = CPU::new();
+    // JSR $0610; LDA #$01 ... $0610: LDX #$02; RTS
+    cpu.mount_mem(0x0600, &[0x20, 0x10, 0x06, 0xA9, 0x01]).unwrap();
+    cpu.mount_mem(0x0610, &[0xA2, 0x02, 0x60]).unwrap();
+    cpu.pc = 0x0600;
+    cpu.sp = 0xFF;
+    let cycles = run(&mut cpu, 4);
+    assert_eq!((cpu.a, cpu.x, cpu.sp, cpu.pc), (0x01, 0x02, 0xFF, 0x0605));
+    assert_eq!(cpu.read_mem(0x01FF).unwrap(), 0x06);
+    assert_eq!(cpu.read_mem(0x01FE).unwrap(), 0x02);
+    assert_eq!(cycles, 6 + 2 + 6 + 2);
+}
+
+#[test]
+fn test_brk_rti(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFE, &[0x00, 0x80]).unwrap();
+    cpu.mount_mem(0x8000, &[0x40]).unwrap();
+    cpu.mount_mem(0x0600, &[0x00, 0xEA, 0xE8]).unwrap();
+    cpu.pc = 0x0600;
+    cpu.sp = 0xFF;
+    run(&mut cpu, 1);
+    assert_eq!((cpu.pc, cpu.interrupt_disable(), cpu.sp), (0x8000, true, 0xFC));
+    assert_eq!(cpu.read_mem(0x01FD).unwrap() & 0x30, 0x30);
+    run(&mut cpu, 2);
+    assert_eq!((cpu.pc, cpu.interrupt_disable(), cpu.x), (0x0603, false, 1));
+}
+
+#[test]
+fn test_indirect_indexed_addressing(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0x00FF, &[0x34]).unwrap();
+    cpu.mount_mem(0x0000, &[0x12]).unwrap();
+    cpu.mount_mem(0x1234, &[0x00, 0x00, 0x00, 0x42]).unwrap();
+    // LDY #$03; LDA ($FF),Y
+    cpu.mount_mem(0x0600, &[0xA0, 0x03, 0xB1, 0xFF]).unwrap();
+    cpu.pc = 0x0600;
+    run(&mut cpu, 2);
+    assert_eq!(cpu.a, 0x42);
+}
+
+#[test]
+fn test_public_step_and_run(){
+    let mut cpu = CPU::default();
+    // LDA #$80; SEC; NOP; NOP
+    cpu.mount_mem(0x0600, &[0xA9, 0x80, 0x38, 0xEA, 0xEA]).unwrap();
+    cpu.set_pc(0x0600);
+    assert_eq!(cpu.step(), 2);
+    assert!(cpu.negative() && !cpu.zero());
+    assert_eq!(cpu.run(5), 6);
+    assert!(cpu.carry());
+    assert_eq!((cpu.a(), cpu.pc()), (0x80, 0x0605));
+    cpu.set_p(0x00);
+    assert!(!cpu.negative() && !cpu.carry());
+}
+
+#[test]
+fn test_mount_mem_rejects_overflowing_data(){
+    let mut cpu : CPU = CPU::new();
+    let result = cpu.mount_mem(0xFFFE, &[0xAA, 0xBB, 0xCC]);
+    assert!(matches!(result, Err(Error::DataDoesNotFit { addr : 0xFFFE, len : 3 })));
+    // nothing of the image may be left behind
+    assert_eq!(cpu.read_mem(0xFFFE).unwrap(), 0x00);
+}
+
+#[test]
+fn test_reset_sequence(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(crate::RESET_VEC, &[0x34, 0x12]).unwrap();
+    cpu.set_decimal(true);
+    assert_eq!(cpu.reset(), 7);
+    assert_eq!((cpu.pc(), cpu.sp()), (0x1234, 0xFD));
+    assert!(cpu.interrupt_disable());
+    assert!(cpu.decimal());
+
+    // a warm reset moves SP down by another three bytes
+    cpu.set_sp(0xF0);
+    cpu.reset();
+    assert_eq!(cpu.sp(), 0xED);
+}
+
+#[test]
+fn test_stack_wraps_within_page_one(){
+    let mut cpu : CPU = CPU::new();
+    // LDX #$00; TXS; PHA; PLA; PLA
+    cpu.mount_mem(0x0600, &[0xA2, 0x00, 0x9A, 0x48, 0x68, 0x68]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_a(0x42);
+    cpu.set_diagnostics(true);
+    run(&mut cpu, 3);
+    assert_eq!(cpu.sp(), 0xFF);
+    assert_eq!(cpu.read_mem(0x0100).unwrap(), 0x42);
+    assert_eq!(cpu.take_diagnostics(), vec![Diagnostic::StackOverflow { pc : 0x0603 }]);
+    cpu.set_a(0x00);
+    run(&mut cpu, 1);
+    assert_eq!((cpu.sp(), cpu.a()), (0x00, 0x42));
+    assert_eq!(cpu.take_diagnostics(), vec![Diagnostic::StackUnderflow { pc : 0x0604 }]);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.sp(), 0x01);
+    assert_eq!(cpu.take_diagnostics(), vec![]);
+}
+
+#[test]
+fn test_php_plp_break_bit(){
+    let mut cpu : CPU = CPU::new();
+    // LDA #$FF; PHA; PLP; PHP
+    cpu.mount_mem(0x0600, &[0xA9, 0xFF, 0x48, 0x28, 0x08]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    run(&mut cpu, 3);
+    assert_eq!(cpu.p(), 0xEF);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.read_mem(0x01FF).unwrap(), 0xFF);
+}
+
+// Results predicted by Bruce Clark's decimal mode test for an NMOS 6502:
+// sequences 1 and 2 for ADC, sequence 3 with binary flags for SBC.
+// Returned as (A, N, V, Z, C).
+fn clark_adc(a : u8, b : u8, c : bool) -> (u8, bool, bool, bool, bool) {
+    let (a, b, c) = (a as i32, b as i32, c as i32);
+    let mut al = (a & 0x0F) + (b & 0x0F) + c;
+    if al >= 0x0A {
+        al = ((al + 0x06) & 0x0F) + 0x10;
+    }
+    let mut seq1 = (a & 0xF0) + (b & 0xF0) + al;
+    if seq1 >= 0xA0 {
+        seq1 += 0x60;
+    }
+    let seq2 = (a & 0xF0) as u8 as i8 as i32 + (b & 0xF0) as u8 as i8 as i32 + al;
+    ((seq1 & 0xFF) as u8, seq2 & 0x80 != 0, !(-128..=127).contains(&seq2), (a + b + c) & 0xFF == 0, seq1 >= 0x100)
+}
+
+fn clark_sbc(a : u8, b : u8, c : bool) -> (u8, bool, bool, bool, bool) {
+    let (a, b, c) = (a as i32, b as i32, c as i32);
+    let mut al = (a & 0x0F) - (b & 0x0F) + c - 1;
+    if al < 0 {
+        al = ((al - 0x06) & 0x0F) - 0x10;
+    }
+    let mut seq3 = (a & 0xF0) - (b & 0xF0) + al;
+    if seq3 < 0 {
+        seq3 -= 0x60;
+    }
+    let bin = a - b + c - 1;
+    ((seq3 & 0xFF) as u8, bin & 0x80 != 0, (a ^ b) & (a ^ bin) & 0x80 != 0, bin & 0xFF == 0, bin >= 0)
+}
+
+#[test]
+fn test_decimal_mode_exhaustive(){
+    let mut cpu : CPU = CPU::new();
+    for (opcode, expected) in [(0x69u8, clark_adc as fn(u8, u8, bool) -> _), (0xE9, clark_sbc)] {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                cpu.mount_mem(0x0600, &[opcode, b]).unwrap();
+                for c in [false, true] {
+                    cpu.set_pc(0x0600);
+                    cpu.set_a(a);
+                    cpu.set_carry(c);
+                    cpu.set_decimal(true);
+                    cpu.step();
+                    let result = (cpu.a(), cpu.negative(), cpu.overflow(), cpu.zero(), cpu.carry());
+                    assert_eq!(result, expected(a, b, c), "opcode {:02X} A={:02X} M={:02X} C={}", opcode, a, b, c);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decimal_mode_disabled(){
+    let mut cpu : CPU = CPU::new();
+    cpu.set_bcd_enabled(false);
+    // SED; CLC; LDA #$09; ADC #$01
+    cpu.mount_mem(0x0600, &[0xF8, 0x18, 0xA9, 0x09, 0x69, 0x01]).unwrap();
+    cpu.set_pc(0x0600);
+    run(&mut cpu, 4);
+    assert_eq!(cpu.a(), 0x0A);
+
+    cpu.set_bcd_enabled(true);
+    cpu.set_pc(0x0602);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.a(), 0x10);
+}
+
+fn cycles_of(code : &[u8], x : u8, y : u8) -> u8 {
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0x20F0, code).unwrap();
+    cpu.mount_mem(0x0080, &[0xF0, 0x30]).unwrap();
+    cpu.set_pc(0x20F0);
+    cpu.set_x(x);
+    cpu.set_y(y);
+    cpu.step()
+}
+
+#[test]
+fn test_page_crossing_penalties(){
+    // LDA $30F0,X
+    assert_eq!(cycles_of(&[0xBD, 0xF0, 0x30], 0x0F, 0), 4);
+    assert_eq!(cycles_of(&[0xBD, 0xF0, 0x30], 0x10, 0), 5);
+    // LDX $30F0,Y
+    assert_eq!(cycles_of(&[0xBE, 0xF0, 0x30], 0, 0x10), 5);
+    // LDA ($80),Y
+    assert_eq!(cycles_of(&[0xB1, 0x80], 0, 0x0F), 5);
+    assert_eq!(cycles_of(&[0xB1, 0x80], 0, 0x10), 6);
+    // stores and read-modify-write always take the fixed cost
+    assert_eq!(cycles_of(&[0x9D, 0xF0, 0x30], 0x10, 0), 5);
+    assert_eq!(cycles_of(&[0x91, 0x80], 0, 0x10), 6);
+    assert_eq!(cycles_of(&[0xFE, 0xF0, 0x30], 0x10, 0), 7);
+}
+
+#[test]
+fn test_branch_penalties(){
+    // BNE with Z clear after reset of the flags
+    assert_eq!(cycles_of(&[0xF0, 0x02], 0, 0), 2);
+    assert_eq!(cycles_of(&[0xD0, 0x02], 0, 0), 3);
+    // the next instruction is at $20F2, so +$0E lands on the next page
+    assert_eq!(cycles_of(&[0xD0, 0x0E], 0, 0), 4);
+    assert_eq!(cycles_of(&[0xD0, 0x0D], 0, 0), 3);
+}
+
+#[test]
+fn test_total_cycle_counter(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(crate::RESET_VEC, &[0x00, 0x06]).unwrap();
+    // LDX #$02; DEX; BNE -3
+    cpu.mount_mem(0x0600, &[0xA2, 0x02, 0xCA, 0xD0, 0xFD]).unwrap();
+    cpu.reset();
+    run(&mut cpu, 5);
+    assert_eq!(cpu.cycles(), 7 + 2 + 2 + 3 + 2 + 2);
+}
+
+#[test]
+fn test_bus_cycles_match_documented_cycles(){
+    let tables = [(CpuVariant::Nmos6502, &INSTRUCTION_MATRIX), (CpuVariant::Cmos65C02, &INSTRUCTION_MATRIX_65C02)];
+    for (cycle_accurate, (variant, matrix)) in [false, true].into_iter().flat_map(|c| tables.map(|t| (c, t))) {
+        for (opcode, instr) in matrix.iter().enumerate() {
+            if matches!(instr.mnem, InstructionMnemonic::InstrUndefined)
+                || matches!(instr.mode, AddressingMode::AddrModeRelative | AddressingMode::AddrModeZPRelative) {
+                continue;
+            }
+            let mut cpu : CPU = CPU::builder().variant(variant).cycle_accurate(cycle_accurate).build();
+            cpu.mount_mem(0x0200, &[opcode as u8, 0x00, 0x00]).unwrap();
+            cpu.set_pc(0x0200);
+            assert_eq!(cpu.step(), instr.cycles, "{:?} opcode {:02X}", variant, opcode);
+            assert_eq!(cpu.cycles(), instr.cycles as u64);
+        }
+    }
+}
+
+// Asserts IRQ or NMI as soon as the CPU accesses the trigger address
+struct InterruptBus {
+    ram : FlatRam,
+    trigger : u16,
+    nmi : bool,
+    asserted : bool,
+}
+
+impl Bus for InterruptBus {
+    fn read(&mut self, address : u16) -> u8 {
+        self.asserted |= address == self.trigger;
+        self.ram.read(address)
+    }
+    fn write(&mut self, address : u16, data : u8){
+        self.asserted |= address == self.trigger;
+        self.ram.write(address, data)
+    }
+    fn peek(&self, address : u16) -> u8 { self.ram.peek(address) }
+    fn poke(&mut self, address : u16, data : u8){ self.ram.poke(address, data) }
+    fn irq(&self) -> bool { self.asserted && !self.nmi }
+    fn nmi(&self) -> bool { self.asserted && self.nmi }
+}
+
+fn interrupt_cpu(trigger : u16, nmi : bool) -> CPU<InterruptBus> {
+    let mut cpu = CPU::with_bus(InterruptBus{ ram : FlatRam::new(), trigger, nmi, asserted : false });
+    cpu.mount_mem(0xFFFA, &[0x00, 0x90, 0x00, 0x00, 0x00, 0x80]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    cpu.set_cycle_accurate(true);
+    cpu
+}
+
+#[test]
+fn test_irq_is_serviced_after_the_current_instruction(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFE, &[0x00, 0x80]).unwrap();
+    cpu.mount_mem(0x8000, &[0x40]).unwrap();
+    // NOP; NOP
+    cpu.mount_mem(0x0600, &[0xEA, 0xEA]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    cpu.set_carry(true);
+
+    cpu.set_irq(true);
+    cpu.set_interrupt_disable(true);
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x0601);
+
+    cpu.set_interrupt_disable(false);
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x0602);
+    assert_eq!(cpu.step(), 7);
+    assert_eq!(cpu.pc(), 0x8000);
+    assert!(cpu.interrupt_disable());
+    assert_eq!(cpu.read_mem(0x01FD).unwrap(), 0x21);
+    assert_eq!((cpu.read_mem(0x01FE).unwrap(), cpu.read_mem(0x01FF).unwrap()), (0x02, 0x06));
+
+    // I is still set by the interrupt so the handler is not interrupted
+    cpu.step();
+    cpu.set_irq(false);
+    assert_eq!((cpu.pc(), cpu.sp()), (0x0602, 0xFF));
+}
+
+#[test]
+fn test_cli_sei_delay(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFE, &[0x00, 0x80]).unwrap();
+    // CLI; NOP; ... $0610: SEI
+    cpu.mount_mem(0x0600, &[0x58, 0xEA]).unwrap();
+    cpu.mount_mem(0x0610, &[0x78]).unwrap();
+    cpu.set_sp(0xFF);
+    cpu.set_irq(true);
+
+    cpu.set_pc(0x0600);
+    cpu.set_interrupt_disable(true);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x0602);
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x8000);
+
+    // the IRQ is still taken after SEI, with I set in the pushed status
+    cpu.set_pc(0x0610);
+    cpu.set_interrupt_disable(false);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x8000);
+    assert_eq!(cpu.read_mem(0x0100 | (cpu.sp() as u16 + 1)).unwrap() & 0x04, 0x04);
+}
+
+#[test]
+fn test_nmi_is_edge_triggered(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFA, &[0x00, 0x90]).unwrap();
+    // NOP; NOP
+    cpu.mount_mem(0x0600, &[0xEA, 0xEA]).unwrap();
+    cpu.mount_mem(0x9000, &[0xEA, 0xEA, 0xEA]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    cpu.set_interrupt_disable(true);
+    cpu.set_nmi(true);
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x9000);
+    // NMI stays asserted but is not taken again
+    cpu.run(6);
+    assert_eq!(cpu.pc(), 0x9003);
+}
+
+#[test]
+fn test_reset_drops_pending_nmi(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFA, &[0x00, 0x90, 0x00, 0x07]).unwrap();
+    // NOP at $0600, NOPs at the reset handler at $0700
+    cpu.mount_mem(0x0600, &[0xEA]).unwrap();
+    cpu.mount_mem(0x0700, &[0xEA, 0xEA, 0xEA]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    cpu.set_nmi(true);
+    cpu.step();
+    cpu.reset();
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x0702);
+}
+
+#[test]
+fn test_nmi_hijacks_brk(){
+    // NMI asserted while BRK pushes PCH
+    let mut cpu = interrupt_cpu(0x01FF, true);
+    cpu.mount_mem(0x0600, &[0x00]).unwrap();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x9000);
+    assert_eq!(cpu.read_mem(0x01FD).unwrap() & 0x10, 0x10);
+    // the NMI has been consumed by the hijacked BRK
+    cpu.mount_mem(0x9000, &[0xEA]).unwrap();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x9001);
+}
+
+#[test]
+fn test_taken_branch_delays_interrupt(){
+    // IRQ asserted during the operand fetch of BNE *+2
+    let mut cpu = interrupt_cpu(0x0601, false);
+    cpu.mount_mem(0x0600, &[0xD0, 0x00, 0xEA, 0xEA]).unwrap();
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x0603);
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x8000);
+
+    // the same IRQ arriving during the second cycle of a three cycle STA zp
+    // is taken right after it
+    let mut cpu = interrupt_cpu(0x0601, false);
+    cpu.mount_mem(0x0600, &[0x85, 0x10, 0xEA]).unwrap();
+    cpu.step();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x8000);
+}
+
+#[test]
+fn test_jmp_indirect_page_wrap(){
+    let mut cpu : CPU = CPU::new();
+    // JMP ($10FF)
+    cpu.mount_mem(0x0600, &[0x6C, 0xFF, 0x10]).unwrap();
+    cpu.mount_mem(0x10FF, &[0x34, 0x12]).unwrap();
+    cpu.mount_mem(0x1000, &[0x56]).unwrap();
+    cpu.set_diagnostics(true);
+    cpu.set_pc(0x0600);
+    assert_eq!(cpu.step(), 5);
+    assert_eq!(cpu.pc(), 0x5634);
+    assert_eq!(cpu.take_diagnostics(), vec![Diagnostic::JmpIndirectPageWrap { pc : 0x0600, pointer : 0x10FF }]);
+
+    cpu.set_jmp_indirect_bug(false);
+    cpu.set_pc(0x0600);
+    assert_eq!(cpu.step(), 6);
+    assert_eq!(cpu.pc(), 0x1234);
+}
+
+fn run_at(code : &[u8], instructions : usize) -> CPU {
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0x0600, code).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    run(&mut cpu, instructions);
+    cpu
+}
+
+#[test]
+fn test_undocumented_load_store(){
+    // LDA #$F0; LDX #$3C; SAX $10; LAX $10
+    let cpu = run_at(&[0xA9, 0xF0, 0xA2, 0x3C, 0x87, 0x10, 0xA9, 0x00, 0xA7, 0x10], 5);
+    assert_eq!((cpu.a(), cpu.x()), (0x30, 0x30));
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x30);
+}
+
+#[test]
+fn test_undocumented_read_modify_write(){
+    // LDA #$01; DCP $10 ($10 = 2); ISC $11 ($11 = $FF); SLO $12 ($12 = $81)
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0x0010, &[0x02, 0xFF, 0x81]).unwrap();
+    cpu.mount_mem(0x0600, &[0xA9, 0x01, 0xC7, 0x10, 0xE7, 0x11, 0x07, 0x12]).unwrap();
+    cpu.set_pc(0x0600);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x01);
+    assert!(cpu.zero() && cpu.carry());
+    run(&mut cpu, 1);
+    assert_eq!(cpu.read_mem(0x0011).unwrap(), 0x00);
+    assert_eq!(cpu.a(), 0x01);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.read_mem(0x0012).unwrap(), 0x02);
+    assert_eq!(cpu.a(), 0x03);
+    assert!(cpu.carry());
+}
+
+#[test]
+fn test_undocumented_immediate(){
+    // LDA #$C0; ANC #$80
+    let cpu = run_at(&[0xA9, 0xC0, 0x0B, 0x80], 2);
+    assert_eq!(cpu.a(), 0x80);
+    assert!(cpu.negative() && cpu.carry());
+    // SEC; LDA #$FF; ARR #$C0
+    let cpu = run_at(&[0x38, 0xA9, 0xFF, 0x6B, 0xC0], 3);
+    assert_eq!(cpu.a(), 0xE0);
+    assert!(cpu.carry() && !cpu.overflow());
+    // LDA #$0F; LDX #$FC; SBX #$02
+    let cpu = run_at(&[0xA9, 0x0F, 0xA2, 0xFC, 0xCB, 0x02], 3);
+    assert_eq!(cpu.x(), 0x0A);
+    assert!(cpu.carry());
+}
+
+#[test]
+fn test_shx_page_crossing(){
+    // LDX #$05; LDY #$01; SHX $12F0,Y
+    let cpu = run_at(&[0xA2, 0x05, 0xA0, 0x01, 0x9E, 0xF0, 0x12], 3);
+    assert_eq!(cpu.read_mem(0x12F1).unwrap(), 0x05 & 0x13);
+    // with a page crossing $05 & $13 = $01 also replaces the high byte of $1310
+    let cpu = run_at(&[0xA2, 0x05, 0xA0, 0x20, 0x9E, 0xF0, 0x12], 3);
+    assert_eq!(cpu.read_mem(0x1310).unwrap(), 0x00);
+    assert_eq!(cpu.read_mem(0x0110).unwrap(), 0x01);
+}
+
+#[test]
+fn test_jam_halts_until_reset(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(crate::RESET_VEC, &[0x00, 0x06]).unwrap();
+    // INX; JAM; INX
+    cpu.mount_mem(0x0600, &[0xE8, 0x02, 0xE8]).unwrap();
+    cpu.reset();
+    assert_eq!(cpu.run(100), 4);
+    assert!(cpu.halted());
+    assert_eq!(cpu.step(), 0);
+    assert_eq!(cpu.x(), 1);
+    cpu.reset();
+    assert!(!cpu.halted());
+}
+
+#[test]
+fn test_multi_byte_nops(){
+    // NOP #$12; NOP $1234,X; NOP $10; INX
+    let cpu = run_at(&[0x80, 0x12, 0x1C, 0x34, 0x12, 0x04, 0x10, 0xE8], 4);
+    assert_eq!((cpu.pc(), cpu.x()), (0x0608, 1));
+}
+
+#[test]
+fn test_variant_settings(){
+    let cpu : CPU = CPU::builder().build();
+    assert_eq!(cpu.variant(), CpuVariant::Nmos6502);
+    assert!(cpu.bcd_enabled() && cpu.jmp_indirect_bug());
+
+    let cpu = CPU::builder().variant(CpuVariant::Ricoh2A03).bus(FlatRam::new()).cycle_accurate(true).build();
+    assert!(!cpu.bcd_enabled() && cpu.jmp_indirect_bug() && cpu.cycle_accurate());
+
+    let cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    assert!(cpu.bcd_enabled() && !cpu.jmp_indirect_bug());
+}
+
+#[test]
+fn test_65c02_undocumented_opcodes_are_nops(){
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    // $02 #$10; $A3; $DC $1234; $5C $1234
+    cpu.mount_mem(0x0600, &[0x02, 0x10, 0xA3, 0xDC, 0x34, 0x12, 0x5C, 0x34, 0x12]).unwrap();
+    cpu.set_pc(0x0600);
+    assert_eq!(cpu.step(), 2);
+    assert_eq!(cpu.step(), 1);
+    assert_eq!(cpu.step(), 4);
+    assert_eq!(cpu.step(), 8);
+    assert_eq!(cpu.pc(), 0x0609);
+    assert!(!cpu.halted());
+    assert_eq!((cpu.a(), cpu.x(), cpu.y()), (0x00, 0x00, 0x00));
+}
+
+#[test]
+fn test_2a03_ignores_decimal_flag(){
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Ricoh2A03).build();
+    // SED; CLC; LDA #$09; ADC #$01
+    cpu.mount_mem(0x0600, &[0xF8, 0x18, 0xA9, 0x09, 0x69, 0x01]).unwrap();
+    cpu.set_pc(0x0600);
+    run(&mut cpu, 4);
+    assert_eq!(cpu.a(), 0x0A);
+    assert!(cpu.decimal());
+}
+
+fn clark_adc_cmos(a : u8, b : u8, c : bool) -> (u8, bool, bool, bool, bool) {
+    let (result, _, v, _, c) = clark_adc(a, b, c);
+    (result, result & 0x80 != 0, v, result == 0, c)
+}
+
+fn clark_sbc_cmos(a : u8, b : u8, c : bool) -> (u8, bool, bool, bool, bool) {
+    let (_, _, v, _, carry) = clark_sbc(a, b, c);
+    let (a, b, c) = (a as i32, b as i32, c as i32);
+    let al = (a & 0x0F) - (b & 0x0F) + c - 1;
+    let mut seq4 = a - b + c - 1;
+    if seq4 < 0 {
+        seq4 -= 0x60;
+    }
+    if al < 0 {
+        seq4 -= 0x06;
+    }
+    let result = (seq4 & 0xFF) as u8;
+    (result, result & 0x80 != 0, v, result == 0, carry)
+}
+
+#[test]
+fn test_65c02_decimal_mode_exhaustive(){
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    for (opcode, expected) in [(0x69u8, clark_adc_cmos as fn(u8, u8, bool) -> _), (0xE9, clark_sbc_cmos)] {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                cpu.mount_mem(0x0600, &[opcode, b]).unwrap();
+                for c in [false, true] {
+                    cpu.set_pc(0x0600);
+                    cpu.set_a(a);
+                    cpu.set_carry(c);
+                    cpu.set_decimal(true);
+                    // one extra cycle for the decimal adjustment
+                    assert_eq!(cpu.step(), 3);
+                    let result = (cpu.a(), cpu.negative(), cpu.overflow(), cpu.zero(), cpu.carry());
+                    assert_eq!(result, expected(a, b, c), "opcode {:02X} A={:02X} M={:02X} C={}", opcode, a, b, c);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_65c02_clears_decimal_on_reset_and_interrupts(){
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0xFFFC, &[0x00, 0x06, 0x00, 0x80]).unwrap();
+    // SED; BRK
+    cpu.mount_mem(0x0600, &[0xF8, 0x00]).unwrap();
+    cpu.set_decimal(true);
+    cpu.reset();
+    assert!(!cpu.decimal());
+    run(&mut cpu, 2);
+    assert_eq!(cpu.pc(), 0x8000);
+    assert!(!cpu.decimal());
+    assert_eq!(cpu.read_mem(0x0100 | (cpu.sp() as u16 + 1)).unwrap() & 0x08, 0x08);
+
+    // NMOS parts leave D alone
+    cpu.set_variant(CpuVariant::Nmos6502);
+    cpu.set_decimal(true);
+    cpu.reset();
+    assert!(cpu.decimal());
+}
+
+#[test]
+fn test_65c02_brk_is_not_hijacked(){
+    let mut cpu = interrupt_cpu(0x01FF, true);
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.mount_mem(0x0600, &[0x00]).unwrap();
+    cpu.step();
+    assert_eq!(cpu.pc(), 0x8000);
+    // the NMI is taken right after BRK, with B clear in its pushed status
+    assert_eq!(cpu.step(), 7);
+    assert_eq!(cpu.pc(), 0x9000);
+    assert_eq!(cpu.read_mem(0x01FA).unwrap() & 0x10, 0x00);
+}
+
+fn run_65c02(code : &[u8], instructions : usize) -> CPU {
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0x0600, code).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    run(&mut cpu, instructions);
+    cpu
+}
+
+#[test]
+fn test_65c02_stack_and_store_zero(){
+    // LDX #$12; LDY #$34; PHX; PHY; PLX; PLY; STZ $10
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0x0010, &[0xFF]).unwrap();
+    cpu.mount_mem(0x0600, &[0xA2, 0x12, 0xA0, 0x34, 0xDA, 0x5A, 0xFA, 0x7A, 0x64, 0x10]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    run(&mut cpu, 7);
+    assert_eq!((cpu.x(), cpu.y(), cpu.sp()), (0x34, 0x12, 0xFF));
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x00);
+}
+
+#[test]
+fn test_65c02_accumulator_and_bit_instructions(){
+    // LDA #$FF; INC A; DEC A; DEC A
+    let cpu = run_65c02(&[0xA9, 0xFF, 0x1A, 0x3A, 0x3A], 4);
+    assert_eq!(cpu.a(), 0xFE);
+    assert!(cpu.negative());
+    // LDA #$0F; BIT #$F0 leaves N and V alone
+    let cpu = run_65c02(&[0xA9, 0x0F, 0x89, 0xF0], 2);
+    assert!(cpu.zero() && !cpu.negative() && !cpu.overflow());
+    // LDA #$0C; TSB $10 ($10 = $05); TRB $11 ($11 = $0F)
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0x0010, &[0x05, 0x0F]).unwrap();
+    cpu.mount_mem(0x0600, &[0xA9, 0x0C, 0x04, 0x10, 0x14, 0x11]).unwrap();
+    cpu.set_pc(0x0600);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x0D);
+    assert!(!cpu.zero());
+    run(&mut cpu, 1);
+    assert_eq!(cpu.read_mem(0x0011).unwrap(), 0x03);
+}
+
+#[test]
+fn test_65c02_bit_manipulation_and_branches(){
+    // SMB3 $10; RMB0 $10; BBS3 $10,+2; (skipped) INX; INX; BBR3 $10,-3
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0x0010, &[0x01]).unwrap();
+    cpu.mount_mem(0x0600, &[0xB7, 0x10, 0x07, 0x10, 0xBF, 0x10, 0x01, 0xE8, 0xE8, 0x3F, 0x10, 0xFD]).unwrap();
+    cpu.set_pc(0x0600);
+    run(&mut cpu, 2);
+    assert_eq!(cpu.read_mem(0x0010).unwrap(), 0x08);
+    assert_eq!(cpu.step(), 6);
+    assert_eq!(cpu.pc(), 0x0608);
+    run(&mut cpu, 1);
+    assert_eq!(cpu.step(), 5);
+    assert_eq!((cpu.pc(), cpu.x()), (0x060C, 1));
+
+    // BRA -2 loops forever
+    let mut cpu = run_65c02(&[0x80, 0xFE], 3);
+    assert_eq!(cpu.pc(), 0x0600);
+    assert_eq!(cpu.step(), 3);
+}
+
+#[test]
+fn test_65c02_indirect_modes(){
+    // LDA ($10); JMP ($2000,X)
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0x0010, &[0x00, 0x30]).unwrap();
+    cpu.mount_mem(0x3000, &[0x42]).unwrap();
+    cpu.mount_mem(0x2004, &[0x78, 0x56]).unwrap();
+    cpu.mount_mem(0x0600, &[0xB2, 0x10, 0x7C, 0x00, 0x20]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_x(4);
+    assert_eq!(cpu.step(), 5);
+    assert_eq!(cpu.a(), 0x42);
+    assert_eq!(cpu.step(), 6);
+    assert_eq!(cpu.pc(), 0x5678);
+}
+
+#[test]
+fn test_65c02_wai_and_stp(){
+    let mut cpu : CPU = CPU::builder().variant(CpuVariant::Cmos65C02).build();
+    cpu.mount_mem(0xFFFE, &[0x00, 0x80]).unwrap();
+    // WAI; INX; STP
+    cpu.mount_mem(0x0600, &[0xCB, 0xE8, 0xDB]).unwrap();
+    cpu.set_pc(0x0600);
+    cpu.set_sp(0xFF);
+    cpu.set_interrupt_disable(true);
+    assert_eq!(cpu.step(), 3);
+    assert_eq!(cpu.run(10), 10);
+    assert!(cpu.waiting());
+    assert_eq!(cpu.pc(), 0x0601);
+
+    // a masked IRQ only ends the wait
+    cpu.set_irq(true);
+    assert_eq!(cpu.step(), 1);
+    assert!(!cpu.waiting());
+    cpu.step();
+    assert_eq!(cpu.x(), 1);
+    cpu.step();
+    assert!(cpu.halted());
+    assert_eq!(cpu.step(), 0);
+}
+
+// RAM with a one byte "BASIC ROM" at $A000 that is banked in while LORAM
+// and HIRAM are high
+struct BankedBus {
+    ram : FlatRam,
+    pins : u8,
+}
+
+impl Bus for BankedBus {
+    fn read(&mut self, address : u16) -> u8 { self.peek(address) }
+    fn write(&mut self, address : u16, data : u8){ self.ram.write(address, data) }
+    fn peek(&self, address : u16) -> u8 {
+        if address == 0xA000 && self.pins & 0x03 == 0x03 { 0x94 } else { self.ram.peek(address) }
+    }
+    fn io_port(&mut self, pins : u8){ self.pins = pins; }
+}
+
+#[test]
+fn test_6510_io_port_switches_banks(){
+    let mut cpu = CPU::builder().variant(CpuVariant::Mos6510).bus(BankedBus{ ram : FlatRam::new(), pins : 0 }).build();
+    cpu.mount_mem(crate::RESET_VEC, &[0x00, 0x06]).unwrap();
+    // LDA #$2F; STA $00; LDA #$36; STA $01; LDA $A000; LDX $01
+    cpu.mount_mem(0x0600, &[0xA9, 0x2F, 0x85, 0x00, 0xA9, 0x36, 0x85, 0x01, 0xAD, 0x00, 0xA0, 0xA6, 0x01]).unwrap();
+    cpu.mount_mem(0xA000, &[0x11]).unwrap();
+    cpu.reset();
+    assert_eq!(cpu.bus().pins, 0x17);
+    assert_eq!(cpu.read_mem(0xA000).unwrap(), 0x94);
+
+    for _ in 0..6 {
+        cpu.step();
+    }
+    assert_eq!(cpu.bus().pins, 0x36);
+    assert_eq!((cpu.a(), cpu.x()), (0x11, 0x36));
+    // the RAM underneath the port is written as well
+    assert_eq!(cpu.bus().ram.peek(0x0001), 0x36);
+    assert_eq!(cpu.read_mem(0x0000).unwrap(), 0x2F);
+}
@@ -0,0 +1,246 @@
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::cpu::{instruction_matrix, AddressingMode, Instruction, InstructionMnemonic, CPU};
+use crate::error::Result;
+use crate::variant::CpuVariant;
+
+#[cfg(test)]
+#[path="./disasm_test.rs"]
+mod disasm_test;
+
+// A decoded instruction. When the bytes run out before the end of the
+// instruction, instr is None and the line stands for the leftover bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub address : u16,
+    pub bytes : Vec<u8>,
+    pub instr : Option<Instruction>,
+}
+
+impl Line {
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // Address following the instruction
+    pub fn next(&self) -> u16 {
+        self.address.wrapping_add(self.bytes.len() as u16)
+    }
+
+    // Mnemonic including the bit number of BBR, BBS, RMB and SMB, e.g. "BBS3"
+    pub fn mnemonic(&self) -> String {
+        let Some(instr) = self.instr else {
+            return String::from(".byte");
+        };
+        match instr.mnem {
+            InstructionMnemonic::InstrBBR | InstructionMnemonic::InstrBBS
+                | InstructionMnemonic::InstrRMB | InstructionMnemonic::InstrSMB =>
+                format!("{}{}", instr.mnem, (self.bytes[0] >> 4) & 0x07),
+            mnem => mnem.to_string(),
+        }
+    }
+
+    // Whether an assembler produces this encoding from the mnemonic and
+    // operand. Undocumented instructions, the NOPs other than $EA and the
+    // duplicate SBC #imm at $EB have to be written as data instead.
+    pub fn is_documented(&self) -> bool {
+        self.instr.is_some_and(|instr| is_documented(self.bytes[0], instr))
+    }
+
+    fn byte(&self) -> u8 {
+        self.bytes[1]
+    }
+
+    fn word(&self) -> u16 {
+        u16::from_le_bytes([self.bytes[1], self.bytes[2]])
+    }
+
+    fn long(&self) -> u32 {
+        u32::from_le_bytes([self.bytes[1], self.bytes[2], self.bytes[3], 0])
+    }
+
+    // Destination of a branch, resolved from the offset in the last byte or
+    // the last two bytes of BRL
+    pub fn branch_target(&self) -> Option<u16> {
+        match self.instr?.mode {
+            AddressingMode::AddrModeRelative | AddressingMode::AddrModeZPRelative => {
+                let offset = *self.bytes.last()? as i8;
+                Some(self.next().wrapping_add(offset as u16))
+            }
+            AddressingMode::AddrModeRelativeLong if self.instr?.mnem == InstructionMnemonic::InstrBRL =>
+                Some(self.next().wrapping_add(self.word())),
+            _ => None,
+        }
+    }
+
+    // Whether the branch only reaches its target by wrapping around through
+    // $FFFF/$0000, which assemblers reject as out of range
+    pub fn branch_wraps(&self) -> bool {
+        self.branch_target().is_some_and(|target| {
+            let offset = target.wrapping_sub(self.next()) as i16 as i32;
+            let next = self.address as i32 + self.len() as i32;
+            !(0..=0xFFFF).contains(&(next + offset))
+        })
+    }
+
+    // Address the operand refers to, for every mode that names one. For
+    // indirect modes this is the pointer, not the final address.
+    pub fn operand_address(&self) -> Option<u16> {
+        match self.instr?.mode {
+            AddressingMode::AddrModeZP | AddressingMode::AddrModeZPX | AddressingMode::AddrModeZPY
+                | AddressingMode::AddrModeIndX | AddressingMode::AddrModeIndY
+                | AddressingMode::AddrModeZPInd | AddressingMode::AddrModeZPRelative => Some(self.byte() as u16),
+            AddressingMode::AddrModeABS | AddressingMode::AddrModeABSX | AddressingMode::AddrModeABSY
+                | AddressingMode::AddrModeIndirect | AddressingMode::AddrModeABSIndX => Some(self.word()),
+            AddressingMode::AddrModeRelative => self.branch_target(),
+            AddressingMode::AddrModeRelativeLong => Some(self.next().wrapping_add(self.word())),
+            _ => None,
+        }
+    }
+
+    // Operand in the usual assembler syntax, branch targets resolved
+    pub fn operand(&self) -> String {
+        let Some(instr) = self.instr else {
+            return self.bytes.iter().map(|byte| format!("${:02X}", byte)).collect::<Vec<_>>().join(", ");
+        };
+        match instr.mode {
+            AddressingMode::AddrModeUndefined | AddressingMode::AddrModeImplied => String::new(),
+            AddressingMode::AddrModeA => String::from("A"),
+            AddressingMode::AddrModeImmedM | AddressingMode::AddrModeImmedX if self.len() == 3 =>
+                format!("#${:04X}", self.word()),
+            AddressingMode::AddrModeImmed | AddressingMode::AddrModeImmedM
+                | AddressingMode::AddrModeImmedX => format!("#${:02X}", self.byte()),
+            AddressingMode::AddrModeZP => format!("${:02X}", self.byte()),
+            AddressingMode::AddrModeZPX => format!("${:02X},X", self.byte()),
+            AddressingMode::AddrModeZPY => format!("${:02X},Y", self.byte()),
+            AddressingMode::AddrModeABS => format!("${:04X}", self.word()),
+            AddressingMode::AddrModeABSX => format!("${:04X},X", self.word()),
+            AddressingMode::AddrModeABSY => format!("${:04X},Y", self.word()),
+            AddressingMode::AddrModeIndirect => format!("(${:04X})", self.word()),
+            AddressingMode::AddrModeIndX => format!("(${:02X},X)", self.byte()),
+            AddressingMode::AddrModeIndY => format!("(${:02X}),Y", self.byte()),
+            AddressingMode::AddrModeZPInd => format!("(${:02X})", self.byte()),
+            AddressingMode::AddrModeABSIndX => format!("(${:04X},X)", self.word()),
+            AddressingMode::AddrModeRelative => format!("${:04X}", self.branch_target().unwrap()),
+            AddressingMode::AddrModeZPRelative => format!("${:02X},${:04X}", self.byte(), self.branch_target().unwrap()),
+            AddressingMode::AddrModeABSLong => format!("${:06X}", self.long()),
+            AddressingMode::AddrModeABSLongX => format!("${:06X},X", self.long()),
+            AddressingMode::AddrModeABSIndLong => format!("[${:04X}]", self.word()),
+            AddressingMode::AddrModeBlockMove => format!("${:02X},${:02X}", self.bytes[2], self.bytes[1]),
+            AddressingMode::AddrModeZPIndLong => format!("[${:02X}]", self.byte()),
+            AddressingMode::AddrModeZPIndLongY => format!("[${:02X}],Y", self.byte()),
+            AddressingMode::AddrModeRelativeLong => format!("${:04X}", self.operand_address().unwrap()),
+            AddressingMode::AddrModeStackRel => format!("${:02X},S", self.byte()),
+            AddressingMode::AddrModeStackRelIndY => format!("(${:02X},S),Y", self.byte()),
+        }
+    }
+}
+
+// Whether opcode is the encoding an assembler picks for its instruction
+pub(crate) fn is_documented(opcode : u8, instr : Instruction) -> bool {
+    let extra_nop = instr.mnem == InstructionMnemonic::InstrNOP && opcode != 0xEA;
+    let extra_sbc = instr.mnem == InstructionMnemonic::InstrSBC && opcode == 0xEB;
+    !instr.mnem.is_undocumented() && !extra_nop && !extra_sbc
+}
+
+// Listing line, e.g. "C000  BD 00 02  LDA $0200,X"
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ");
+        let operand = self.operand();
+        if operand.is_empty() {
+            write!(f, "{:04X}  {:<8}  {}", self.address, bytes, self.mnemonic())
+        } else {
+            write!(f, "{:04X}  {:<8}  {} {}", self.address, bytes, self.mnemonic(), operand)
+        }
+    }
+}
+
+// Decodes instructions with the opcode table of a CPU variant
+#[derive(Debug, Copy, Clone)]
+pub struct Disassembler {
+    matrix : &'static [Instruction ; crate::NUM_INSTR],
+    // 65C816 immediates of A and memory (M clear) or X and Y (X clear)
+    // are 16 bit
+    m16 : bool,
+    x16 : bool,
+}
+
+impl Default for Disassembler {
+    fn default() -> Self {
+        Self::new(CpuVariant::default())
+    }
+}
+
+impl Disassembler {
+    pub fn new(variant : CpuVariant) -> Disassembler {
+        Disassembler{ matrix : instruction_matrix(variant), m16 : false, x16 : false }
+    }
+
+    // Register widths the 65C816 immediates are decoded with. The default
+    // are the 8 bit registers of emulation mode. The widths are not changed
+    // by REP, SEP or XCE in the code, the source analysis follows those.
+    pub fn with_widths(self, m16 : bool, x16 : bool) -> Disassembler {
+        Disassembler{ m16, x16, ..self }
+    }
+
+    // Opcode table entry of an opcode
+    pub fn instruction(&self, opcode : u8) -> Instruction {
+        self.matrix[opcode as usize]
+    }
+
+    // Number of bytes of the instruction with the current register widths
+    fn length(&self, instr : Instruction) -> usize {
+        let wide = match instr.mode {
+            AddressingMode::AddrModeImmedM => self.m16,
+            AddressingMode::AddrModeImmedX => self.x16,
+            _ => false,
+        };
+        (instr.length as usize).max(1) + wide as usize
+    }
+
+    // Decodes the instruction at the start of bytes, which are located at
+    // address. Returns None for an empty slice.
+    pub fn decode(&self, bytes : &[u8], address : u16) -> Option<Line> {
+        let instr = self.instruction(*bytes.first()?);
+        let length = self.length(instr);
+        if bytes.len() < length {
+            return Some(Line{ address, bytes : bytes.to_vec(), instr : None });
+        }
+        Some(Line{ address, bytes : bytes[..length].to_vec(), instr : Some(instr) })
+    }
+
+    // Decodes all of bytes, the first of which is located at address
+    pub fn disassemble(&self, bytes : &[u8], address : u16) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut pos = 0;
+        while let Some(line) = self.decode(&bytes[pos..], address.wrapping_add(pos as u16)) {
+            pos += line.len();
+            lines.push(line);
+        }
+        lines
+    }
+
+    // Decodes the memory of the CPU from start up to and including end. The
+    // last instruction may extend past end.
+    pub fn disassemble_memory<B : Bus>(&self, cpu : &CPU<B>, start : u16, end : u16) -> Result<Vec<Line>> {
+        let mut lines = Vec::new();
+        let mut address = start as u32;
+        while address <= end as u32 {
+            let length = self.length(self.instruction(cpu.read_mem(address as u16)?)) as u32;
+            let bytes = (address..address + length)
+                .filter(|&addr| addr <= 0xFFFF)
+                .map(|addr| cpu.read_mem(addr as u16))
+                .collect::<Result<Vec<u8>>>()?;
+            let line = self.decode(&bytes, address as u16).unwrap();
+            address += line.len() as u32;
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+}
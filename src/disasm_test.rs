@@ -0,0 +1,128 @@
+use super::*;
+
+fn listing(variant : CpuVariant, code : &[u8], address : u16) -> Vec<String> {
+    Disassembler::new(variant).disassemble(code, address).iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn test_nmos_operands(){
+    let code = [
+        0xA9, 0x42,             // LDA #$42
+        0xB5, 0x10,             // LDA $10,X
+        0xB6, 0x10,             // LDX $10,Y
+        0xBD, 0x00, 0x02,       // LDA $0200,X
+        0xB9, 0x00, 0x02,       // LDA $0200,Y
+        0xA1, 0x20,             // LDA ($20,X)
+        0xB1, 0x20,             // LDA ($20),Y
+        0x6C, 0xFC, 0xFF,       // JMP ($FFFC)
+        0x0A,                   // ASL A
+        0xD0, 0xEC,             // BNE to the start
+        0xA7, 0x30,             // LAX $30
+        0x60,                   // RTS
+    ];
+    assert_eq!(listing(CpuVariant::Nmos6502, &code, 0xC000), vec![
+        "C000  A9 42     LDA #$42",
+        "C002  B5 10     LDA $10,X",
+        "C004  B6 10     LDX $10,Y",
+        "C006  BD 00 02  LDA $0200,X",
+        "C009  B9 00 02  LDA $0200,Y",
+        "C00C  A1 20     LDA ($20,X)",
+        "C00E  B1 20     LDA ($20),Y",
+        "C010  6C FC FF  JMP ($FFFC)",
+        "C013  0A        ASL A",
+        "C014  D0 EC     BNE $C002",
+        "C016  A7 30     LAX $30",
+        "C018  60        RTS",
+    ]);
+}
+
+#[test]
+fn test_65c02_operands(){
+    let code = [
+        0xB2, 0x20,             // LDA ($20)
+        0x7C, 0x00, 0x80,       // JMP ($8000,X)
+        0x9F, 0x12, 0xFB,       // BBS1 $12 back to the start
+        0xF7, 0x12,             // SMB7 $12
+        0x80, 0x00,             // BRA
+    ];
+    assert_eq!(listing(CpuVariant::Cmos65C02, &code, 0x0200), vec![
+        "0200  B2 20     LDA ($20)",
+        "0202  7C 00 80  JMP ($8000,X)",
+        "0205  9F 12 FB  BBS1 $12,$0203",
+        "0208  F7 12     SMB7 $12",
+        "020A  80 00     BRA $020C",
+    ]);
+}
+
+#[test]
+fn test_65816_operands(){
+    let code = [
+        0x22, 0x56, 0x34, 0x12, // JSL $123456
+        0xA3, 0x03,             // LDA $03,S
+        0xB3, 0x05,             // LDA ($05,S),Y
+        0xB7, 0x10,             // LDA [$10],Y
+        0xDC, 0x00, 0x02,       // JML [$0200]
+        0x54, 0x7E, 0x7F,       // MVN $7F,$7E
+        0x62, 0xF0, 0xFF,       // PER $8003
+        0x82, 0xED, 0xFF,       // BRL to the same address
+    ];
+    assert_eq!(listing(CpuVariant::Wdc65C816, &code, 0x8000), vec![
+        "8000  22 56 34 12  JSL $123456",
+        "8004  A3 03     LDA $03,S",
+        "8006  B3 05     LDA ($05,S),Y",
+        "8008  B7 10     LDA [$10],Y",
+        "800A  DC 00 02  JML [$0200]",
+        "800D  54 7E 7F  MVN $7F,$7E",
+        "8010  62 F0 FF  PER $8003",
+        "8013  82 ED FF  BRL $8003",
+    ]);
+    let disasm = Disassembler::new(CpuVariant::Wdc65C816);
+    assert_eq!(disasm.decode(&code[16..], 0x8010).unwrap().branch_target(), None);
+}
+
+#[test]
+fn test_65816_register_widths(){
+    // LDA #; LDX #; AND #
+    let code = [0xA9, 0x34, 0x12, 0xA2, 0x78, 0x56, 0x29, 0xFF, 0x00];
+    let lines = |m16, x16| -> Vec<String> {
+        Disassembler::new(CpuVariant::Wdc65C816).with_widths(m16, x16)
+            .disassemble(&code, 0x8000).iter().map(|line| line.to_string()).collect()
+    };
+    assert_eq!(lines(true, true), vec![
+        "8000  A9 34 12  LDA #$1234",
+        "8003  A2 78 56  LDX #$5678",
+        "8006  29 FF 00  AND #$00FF",
+    ]);
+    assert_eq!(lines(true, false)[1], "8003  A2 78     LDX #$78");
+    assert_eq!(lines(false, false)[0], "8000  A9 34     LDA #$34");
+}
+
+#[test]
+fn test_decode_details(){
+    let disasm = Disassembler::default();
+    let line = disasm.decode(&[0x20, 0x34, 0x12, 0xEA], 0xFFFE).unwrap();
+    assert_eq!((line.len(), line.next()), (3, 0x0001));
+    assert_eq!(line.operand_address(), Some(0x1234));
+    assert_eq!(line.instr.unwrap().mode.to_string(), "Absolute");
+    assert_eq!(format!("{:<5}|", line.instr.unwrap().mnem), "JSR  |");
+
+    // the slice ends in the middle of an instruction
+    let lines = disasm.disassemble(&[0xEA, 0xAD, 0x00], 0x1000);
+    assert_eq!(lines[1].instr, None);
+    assert_eq!(lines[1].to_string(), "1001  AD 00     .byte $AD, $00");
+    assert!(disasm.decode(&[], 0).is_none());
+}
+
+#[test]
+fn test_disassemble_memory(){
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0xFFFA, &[0xEA, 0x4C, 0x00, 0x80, 0x00, 0x90]).unwrap();
+    let lines = Disassembler::default().disassemble_memory(&cpu, 0xFFFA, 0xFFFF).unwrap();
+    let text : Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    assert_eq!(text, vec![
+        "FFFA  EA        NOP",
+        "FFFB  4C 00 80  JMP $8000",
+        "FFFE  00        BRK",
+        "FFFF  90        .byte $90",
+    ]);
+}
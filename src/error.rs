@@ -0,0 +1,62 @@
+use std::fmt;
+use std::io;
+
+// Errors reported by the memory and loading APIs of the crate
+#[derive(Debug)]
+pub enum Error {
+    // Data of the given length does not fit in memory at the given address
+    DataDoesNotFit { addr : u32, len : usize },
+    // The bus has nothing mapped at the given address
+    UnmappedAddress(u32),
+    // Reading or writing a file failed
+    Io(io::Error),
+    // A line of a HEX or S-record file is malformed
+    InvalidRecord { line : usize, reason : &'static str },
+    // The checksum of a record does not match its contents
+    BadChecksum { line : usize },
+    // A binary program file is truncated or has an invalid header
+    InvalidFormat(&'static str),
+    // The iNES mapper number of a cartridge is not implemented
+    UnsupportedMapper(u16),
+    // A line of assembler source is invalid
+    Assembly { file : String, line : usize, message : String },
+    // The init routine at the given address halted or ran without returning
+    InitDidNotReturn(u16),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DataDoesNotFit { addr, len } =>
+                write!(f, "{} bytes of data do not fit in memory at ${:04X}", len, addr),
+            Error::UnmappedAddress(addr) =>
+                write!(f, "nothing is mapped at address ${:04X}", addr),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::InvalidRecord { line, reason } =>
+                write!(f, "line {}: {}", line, reason),
+            Error::BadChecksum { line } =>
+                write!(f, "line {}: checksum mismatch", line),
+            Error::InvalidFormat(reason) => write!(f, "{}", reason),
+            Error::UnsupportedMapper(mapper) => write!(f, "mapper {} is not supported", mapper),
+            Error::Assembly { file, line, message } => write!(f, "{}:{}: {}", file, line, message),
+            Error::InitDidNotReturn(addr) => write!(f, "init routine at ${:04X} did not return", addr),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err : io::Error) -> Error {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::{hex_bytes, Image};
+
+#[cfg(test)]
+#[path="./ihex_test.rs"]
+mod ihex_test;
+
+// Data bytes per record written by to_string
+const RECORD_SIZE : usize = 16;
+
+const DATA : u8 = 0x00;
+const END_OF_FILE : u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS : u8 = 0x02;
+const START_SEGMENT_ADDRESS : u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS : u8 = 0x04;
+const START_LINEAR_ADDRESS : u8 = 0x05;
+
+// Parses an Intel HEX file. Records may come in any order and leave gaps,
+// contiguous data ends up in a single segment. Data past $FFFF is rejected.
+pub fn parse(text : &str) -> Result<Image> {
+    let mut image = Image::new();
+    let mut base : u32 = 0;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let bytes = line.strip_prefix(':')
+            .and_then(hex_bytes)
+            .ok_or(Error::InvalidRecord { line : line_no, reason : "not an Intel HEX record" })?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(Error::InvalidRecord { line : line_no, reason : "record length mismatch" });
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(Error::BadChecksum { line : line_no });
+        }
+
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as u32;
+        let data = &bytes[4..bytes.len() - 1];
+        let invalid = |reason| Error::InvalidRecord { line : line_no, reason };
+        match bytes[3] {
+            DATA => image.append(base + offset, data)?,
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS | EXTENDED_LINEAR_ADDRESS => {
+                if data.len() != 2 {
+                    return Err(invalid("extended address record needs 2 bytes"));
+                }
+                let value = u16::from_be_bytes([data[0], data[1]]) as u32;
+                base = if bytes[3] == EXTENDED_SEGMENT_ADDRESS { value << 4 } else { value << 16 };
+            }
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS => {
+                if data.len() != 4 {
+                    return Err(invalid("start address record needs 4 bytes"));
+                }
+                let entry = if bytes[3] == START_SEGMENT_ADDRESS {
+                    ((u16::from_be_bytes([data[0], data[1]]) as u32) << 4)
+                        + u16::from_be_bytes([data[2], data[3]]) as u32
+                } else {
+                    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+                };
+                image.entry = Some(u16::try_from(entry).map_err(|_| invalid("start address out of range"))?);
+            }
+            _ => return Err(invalid("unknown record type")),
+        }
+    }
+    Ok(image)
+}
+
+// Writes the image as Intel HEX. All addresses fit in 16 bits, so no
+// extended address records are needed.
+pub fn to_string(image : &Image) -> String {
+    let mut text = String::new();
+    for segment in &image.segments {
+        for (i, chunk) in segment.data.chunks(RECORD_SIZE).enumerate() {
+            let address = segment.address.wrapping_add((i * RECORD_SIZE) as u16);
+            record(&mut text, DATA, address, chunk);
+        }
+    }
+    if let Some(entry) = image.entry {
+        record(&mut text, START_LINEAR_ADDRESS, 0, &(entry as u32).to_be_bytes());
+    }
+    record(&mut text, END_OF_FILE, 0, &[]);
+    text
+}
+
+pub fn load<P : AsRef<Path>>(path : P) -> Result<Image> {
+    parse(&fs::read_to_string(path)?)
+}
+
+pub fn save<P : AsRef<Path>>(path : P, image : &Image) -> Result<()> {
+    fs::write(path, to_string(image))?;
+    Ok(())
+}
+
+fn record(text : &mut String, kind : u8, address : u16, data : &[u8]){
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(sum.wrapping_neg());
+
+    text.push(':');
+    for byte in bytes {
+        write!(text, "{:02X}", byte).unwrap();
+    }
+    text.push('\n');
+}
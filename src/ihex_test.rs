@@ -0,0 +1,42 @@
+use super::*;
+use crate::loader::Segment;
+
+#[test]
+fn test_parse_sparse_and_extended_records(){
+    let text = "\
+:020000040000FA
+:10010000214601360121470136007EFE09D2190140
+:02011000214686
+:02200000AABB79
+:0400000500000400F3
+:00000001FF
+";
+    let image = parse(text).unwrap();
+    let mut first = vec![0x21, 0x46, 0x01, 0x36, 0x01, 0x21, 0x47, 0x01, 0x36, 0x00, 0x7E, 0xFE, 0x09, 0xD2, 0x19, 0x01];
+    first.extend_from_slice(&[0x21, 0x46]);
+    assert_eq!(image.segments, vec![
+        Segment{ address : 0x0100, data : first, init : None },
+        Segment{ address : 0x2000, data : vec![0xAA, 0xBB], init : None },
+    ]);
+    assert_eq!(image.entry, Some(0x0400));
+}
+
+#[test]
+fn test_parse_errors(){
+    assert!(matches!(parse(":02011000214687\n"), Err(Error::BadChecksum { line : 1 })));
+    assert!(matches!(parse("\n:03011000214686\n"), Err(Error::InvalidRecord { line : 2, .. })));
+    assert!(matches!(parse("02011000214686\n"), Err(Error::InvalidRecord { line : 1, .. })));
+    // data above $FFFF has nowhere to go
+    assert!(matches!(parse(":020000040001F9\n:02000000214697\n"), Err(Error::DataDoesNotFit { addr : 0x10000, len : 2 })));
+}
+
+#[test]
+fn test_round_trip(){
+    let mut image = Image::from_binary(&(0..40).collect::<Vec<u8>>(), 0xFFF0 - 24).unwrap();
+    image.add_segment(0x0200, &[0xEA]).unwrap();
+    image.entry = Some(0xFFD8);
+    let text = to_string(&image);
+    assert!(text.starts_with(":10FFD800000102030405060708090A0B0C0D0E0F"));
+    assert!(text.ends_with(":00000001FF\n"));
+    assert_eq!(parse(&text).unwrap(), image);
+}
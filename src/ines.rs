@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+#[cfg(test)]
+#[path="./ines_test.rs"]
+mod ines_test;
+
+const MAGIC : [u8 ; 4] = [b'N', b'E', b'S', 0x1A];
+const HEADER_LEN : usize = 16;
+const TRAINER_LEN : usize = 512;
+
+const PRG_ROM_UNIT : usize = 16 * 1024;
+const CHR_ROM_UNIT : usize = 8 * 1024;
+const PRG_RAM_UNIT : usize = 8 * 1024;
+
+// Arrangement of the PPU nametables, fixed by the board or selected by the
+// mapper
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+    FourScreen,
+    SingleScreenLower,
+    SingleScreenUpper,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    INes,
+    Nes20,
+}
+
+// Contents of the 16 byte header. Sizes are in bytes. For plain iNES files
+// the RAM sizes are not recorded and the usual defaults are filled in: 8 KiB
+// of PRG RAM and 8 KiB of CHR RAM for boards without CHR ROM.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub format : Format,
+    pub prg_rom_size : usize,
+    pub chr_rom_size : usize,
+    pub mapper : u16,
+    pub submapper : u8,
+    pub mirroring : Mirroring,
+    pub battery : bool,
+    pub trainer : bool,
+    pub prg_ram_size : usize,
+    pub prg_nvram_size : usize,
+    pub chr_ram_size : usize,
+    pub chr_nvram_size : usize,
+}
+
+// A parsed cartridge image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rom {
+    pub header : Header,
+    // Loaded at $7000 before the game starts
+    pub trainer : Option<Vec<u8>>,
+    pub prg_rom : Vec<u8>,
+    pub chr_rom : Vec<u8>,
+}
+
+impl Header {
+    pub fn parse(bytes : &[u8]) -> Result<Header> {
+        if bytes.len() < HEADER_LEN || bytes[0..4] != MAGIC {
+            return Err(Error::InvalidFormat("not an iNES file"));
+        }
+        let flags6 = bytes[6];
+        let flags7 = bytes[7];
+        let format = if flags7 & 0x0C == 0x08 { Format::Nes20 } else { Format::INes };
+
+        let mirroring = if flags6 & 0x08 != 0 {
+            Mirroring::FourScreen
+        } else if flags6 & 0x01 != 0 {
+            Mirroring::Vertical
+        } else {
+            Mirroring::Horizontal
+        };
+        let battery = flags6 & 0x02 != 0;
+        let trainer = flags6 & 0x04 != 0;
+
+        if format == Format::Nes20 {
+            let mapper = (flags6 >> 4) as u16 | (flags7 & 0xF0) as u16 | ((bytes[8] & 0x0F) as u16) << 8;
+            Ok(Header{
+                format,
+                prg_rom_size : rom_size(bytes[4], bytes[9] & 0x0F, PRG_ROM_UNIT),
+                chr_rom_size : rom_size(bytes[5], bytes[9] >> 4, CHR_ROM_UNIT),
+                mapper,
+                submapper : bytes[8] >> 4,
+                mirroring,
+                battery,
+                trainer,
+                prg_ram_size : ram_size(bytes[10] & 0x0F),
+                prg_nvram_size : ram_size(bytes[10] >> 4),
+                chr_ram_size : ram_size(bytes[11] & 0x0F),
+                chr_nvram_size : ram_size(bytes[11] >> 4),
+            })
+        } else {
+            // Old dumping tools wrote a signature into bytes 7-15, in which
+            // case the upper mapper nibble is garbage
+            let flags7 = if bytes[12..16].iter().any(|&byte| byte != 0) { 0 } else { flags7 };
+            let chr_rom_size = bytes[5] as usize * CHR_ROM_UNIT;
+            let prg_ram_size = bytes[8].max(1) as usize * PRG_RAM_UNIT;
+            Ok(Header{
+                format,
+                prg_rom_size : bytes[4] as usize * PRG_ROM_UNIT,
+                chr_rom_size,
+                mapper : (flags6 >> 4) as u16 | (flags7 & 0xF0) as u16,
+                submapper : 0,
+                mirroring,
+                battery,
+                trainer,
+                prg_ram_size : if battery { 0 } else { prg_ram_size },
+                prg_nvram_size : if battery { prg_ram_size } else { 0 },
+                chr_ram_size : if chr_rom_size == 0 { CHR_ROM_UNIT } else { 0 },
+                chr_nvram_size : 0,
+            })
+        }
+    }
+}
+
+impl Rom {
+    pub fn parse(bytes : &[u8]) -> Result<Rom> {
+        let header = Header::parse(bytes)?;
+        let mut pos = HEADER_LEN;
+        let mut take = |len : usize| {
+            let data = pos.checked_add(len)
+                .and_then(|end| bytes.get(pos..end))
+                .ok_or(Error::InvalidFormat("iNES file is truncated"))?;
+            pos += len;
+            Ok::<Vec<u8>, Error>(data.to_vec())
+        };
+
+        let trainer = if header.trainer { Some(take(TRAINER_LEN)?) } else { None };
+        let prg_rom = take(header.prg_rom_size)?;
+        let chr_rom = take(header.chr_rom_size)?;
+        Ok(Rom{ header, trainer, prg_rom, chr_rom })
+    }
+
+    pub fn load<P : AsRef<Path>>(path : P) -> Result<Rom> {
+        Rom::parse(&fs::read(path)?)
+    }
+}
+
+// NES 2.0 ROM size from the LSB in the header and the MSB nibble. An MSB of
+// $F selects the exponent-multiplier notation, 2^E * (MM * 2 + 1).
+fn rom_size(lsb : u8, msb : u8, unit : usize) -> usize {
+    if msb == 0x0F {
+        (1usize << (lsb >> 2)).saturating_mul((lsb & 0x03) as usize * 2 + 1)
+    } else {
+        ((msb as usize) << 8 | lsb as usize) * unit
+    }
+}
+
+// NES 2.0 RAM size from a shift count, 0 meaning none
+fn ram_size(shift : u8) -> usize {
+    if shift == 0 { 0 } else { 64 << shift }
+}
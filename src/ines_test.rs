@@ -0,0 +1,54 @@
+use super::*;
+
+fn header(bytes : [u8 ; 12]) -> Vec<u8> {
+    let mut file = MAGIC.to_vec();
+    file.extend_from_slice(&bytes);
+    file
+}
+
+#[test]
+fn test_ines_header(){
+    // 2 x 16 KiB PRG, 1 x 8 KiB CHR, mapper $41, vertical, battery, trainer
+    let mut file = header([2, 1, 0x17, 0x40, 0, 0, 0, 0, 0, 0, 0, 0]);
+    file.extend((0..TRAINER_LEN).map(|i| i as u8));
+    file.extend(vec![0xAA ; 2 * PRG_ROM_UNIT]);
+    file.extend(vec![0xBB ; CHR_ROM_UNIT]);
+
+    let rom = Rom::parse(&file).unwrap();
+    let header = rom.header;
+    assert_eq!(header.format, Format::INes);
+    assert_eq!((header.mapper, header.mirroring), (0x41, Mirroring::Vertical));
+    assert!(header.battery && header.trainer);
+    assert_eq!((header.prg_ram_size, header.prg_nvram_size, header.chr_ram_size), (0, PRG_RAM_UNIT, 0));
+    assert_eq!(rom.trainer.unwrap()[511], 0xFF);
+    assert_eq!((rom.prg_rom.len(), rom.chr_rom.len()), (2 * PRG_ROM_UNIT, CHR_ROM_UNIT));
+    assert_eq!((rom.prg_rom[0], rom.chr_rom[0]), (0xAA, 0xBB));
+
+    file.truncate(file.len() - 1);
+    assert!(matches!(Rom::parse(&file), Err(Error::InvalidFormat(_))));
+    assert!(matches!(Rom::parse(b"NES\x1A"), Err(Error::InvalidFormat(_))));
+}
+
+#[test]
+fn test_ines_signature_in_padding(){
+    // "DiskDude!" over bytes 7-15 would make this mapper $41
+    let file = header([1, 0, 0x18, b'D', b'i', b's', b'k', b'D', b'u', b'd', b'e', b'!']);
+    let header = Header::parse(&file).unwrap();
+    assert_eq!(header.mapper, 0x01);
+    assert_eq!(header.mirroring, Mirroring::FourScreen);
+    assert_eq!((header.chr_rom_size, header.chr_ram_size), (0, CHR_ROM_UNIT));
+}
+
+#[test]
+fn test_nes20_header(){
+    // mapper $123 submapper 5, 4 MiB PRG (MSB 1), CHR as 2^10 * 3,
+    // 8 KiB PRG RAM + 32 KiB NVRAM, 8 KiB CHR RAM
+    let file = header([0x00, 0x29, 0x30, 0x08 | 0x20, 0x51, 0xF1, 0x97, 0x07, 0, 0, 0, 0]);
+    let header = Header::parse(&file).unwrap();
+    assert_eq!(header.format, Format::Nes20);
+    assert_eq!((header.mapper, header.submapper), (0x123, 5));
+    assert_eq!(header.prg_rom_size, 256 * PRG_ROM_UNIT);
+    assert_eq!(header.chr_rom_size, 3 * 1024);
+    assert_eq!((header.prg_ram_size, header.prg_nvram_size), (8 * 1024, 32 * 1024));
+    assert_eq!((header.chr_ram_size, header.chr_nvram_size), (8 * 1024, 0));
+}
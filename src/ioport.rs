@@ -0,0 +1,96 @@
+#[cfg(test)]
+#[path="./ioport_test.rs"]
+mod ioport_test;
+
+// Cycles a floating input keeps reading as 1 after it stopped being driven
+// high. The value is the one VICE uses for the C64.
+pub const FADE_CYCLES : u64 = 350_000;
+
+// Pins pulled up on the C64 board: LORAM, HIRAM, CHAREN and cassette sense
+pub const C64_PULL_UPS : u8 = 0x17;
+
+// On-chip I/O port of the 6510. $0000 is the data direction register, a 1
+// makes the matching pin an output driven from the data latch at $0001.
+// Input pins read as 1 when pulled up externally. Otherwise they float and
+// hold the level last driven onto them for a while before fading to 0.
+#[derive(Debug, Copy, Clone)]
+pub struct IoPort {
+    ddr : u8,
+    data : u8,
+    pull_ups : u8,
+
+    // Floating pins last driven high and the cycle their charge is gone
+    charged : u8,
+    fade : [u64 ; 8],
+}
+
+impl Default for IoPort {
+    fn default() -> Self {
+        Self::new(C64_PULL_UPS)
+    }
+}
+
+impl IoPort {
+    pub fn new(pull_ups : u8) -> IoPort {
+        IoPort{ ddr : 0, data : 0, pull_ups, charged : 0, fade : [0 ; 8] }
+    }
+
+    pub fn ddr(&self) -> u8 {
+        self.ddr
+    }
+
+    pub fn data(&self) -> u8 {
+        self.data
+    }
+
+    pub fn pull_ups(&self) -> u8 {
+        self.pull_ups
+    }
+
+    pub fn set_pull_ups(&mut self, pull_ups : u8){
+        self.pull_ups = pull_ups;
+    }
+
+    // Levels on the pins as seen by the hardware connected to the port.
+    // Floating pins read as 0.
+    pub fn pins(&self) -> u8 {
+        (self.ddr & self.data) | (!self.ddr & self.pull_ups)
+    }
+
+    // Value the CPU reads from $0000 or $0001 at the given cycle
+    pub fn read(&self, address : u16, cycle : u64) -> u8 {
+        if address == 0 {
+            return self.ddr;
+        }
+        let floating = !self.ddr & !self.pull_ups;
+        let charged = (0..8)
+            .filter(|&bit| self.charged & (1 << bit) != 0 && cycle < self.fade[bit])
+            .fold(0, |bits, bit| bits | (1 << bit));
+        self.pins() | (floating & charged)
+    }
+
+    // Write by the CPU to $0000 or $0001 at the given cycle
+    pub fn write(&mut self, address : u16, data : u8, cycle : u64){
+        let driven_high = self.ddr & self.data;
+        if address == 0 {
+            self.ddr = data;
+        } else {
+            self.data = data;
+        }
+
+        let released = driven_high & !self.ddr & !self.pull_ups;
+        for bit in 0..8 {
+            if released & (1 << bit) != 0 {
+                self.fade[bit] = cycle + FADE_CYCLES;
+            }
+        }
+        self.charged = (self.charged & !self.ddr) | released;
+    }
+
+    // A reset turns all pins into inputs and clears the data latch
+    pub fn reset(&mut self){
+        self.ddr = 0;
+        self.data = 0;
+        self.charged = 0;
+    }
+}
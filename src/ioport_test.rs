@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn test_ddr_selects_outputs(){
+    let mut port = IoPort::new(C64_PULL_UPS);
+    assert_eq!(port.read(0, 0), 0x00);
+    assert_eq!(port.read(1, 0), C64_PULL_UPS);
+
+    // the C64 KERNAL setup: bits 0-5 outputs, LORAM/HIRAM/CHAREN high
+    port.write(0, 0x2F, 0);
+    port.write(1, 0x37, 0);
+    assert_eq!(port.read(0, 0), 0x2F);
+    assert_eq!(port.pins(), 0x37);
+    assert_eq!(port.read(1, 0), 0x37);
+
+    // inputs keep reading their pull-ups whatever is in the latch
+    port.write(1, 0x00, 0);
+    assert_eq!(port.read(1, 0), 0x10);
+}
+
+#[test]
+fn test_floating_bits_fade(){
+    let mut port = IoPort::new(0x00);
+    port.write(0, 0xC0, 100);
+    port.write(1, 0xC0, 100);
+    port.write(0, 0x00, 200);
+    assert_eq!(port.pins(), 0x00);
+    assert_eq!(port.read(1, 200 + FADE_CYCLES - 1), 0xC0);
+    assert_eq!(port.read(1, 200 + FADE_CYCLES), 0x00);
+
+    // a pin driven low loses its charge right away
+    let mut port = IoPort::new(0x00);
+    port.write(1, 0x80, 300);
+    port.write(0, 0x80, 300);
+    port.write(0, 0x00, 300);
+    assert_eq!(port.read(1, 301), 0x80);
+    port.write(1, 0x00, 302);
+    port.write(0, 0x80, 302);
+    port.write(0, 0x00, 302);
+    assert_eq!(port.read(1, 303), 0x00);
+}
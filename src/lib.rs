@@ -1,3 +1,40 @@
 pub const MAX_MEM : usize = 65536;
+pub const MAX_MEM_24 : usize = 16777216;
+pub const NMI_VEC : u16 = 0xFFFA;
 pub const RESET_VEC : u16 = 0xFFFC;
-pub const NUM_INSTR : usize = 256;
\ No newline at end of file
+pub const IRQ_VEC : u16 = 0xFFFE;
+pub const NUM_INSTR : usize = 256;
+
+pub mod asm;
+pub mod bus;
+pub mod cpu;
+pub mod cpu816;
+pub mod disasm;
+pub mod error;
+pub mod ihex;
+pub mod ines;
+pub mod ioport;
+pub mod loader;
+pub mod mapper;
+pub mod monitor;
+pub mod nes;
+pub mod o65;
+pub mod prg;
+pub mod source;
+pub mod srec;
+pub mod status;
+pub mod variant;
+pub mod xex;
+
+pub use asm::{Assembler, Program};
+pub use bus::{Bus, Bus24, BusCycle, Direction, FlatRam, FlatRam24};
+pub use cpu::{CPUBuilder, Diagnostic, CPU};
+pub use cpu816::CPU816;
+pub use disasm::{Disassembler, Line};
+pub use error::{Error, Result};
+pub use ioport::IoPort;
+pub use loader::{Image, Segment};
+pub use monitor::Monitor;
+pub use nes::NesBus;
+pub use status::StatusFlags;
+pub use variant::CpuVariant;
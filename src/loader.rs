@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::Path;
+
+use crate::asm::Assembler;
+use crate::bus::Bus;
+use crate::cpu::CPU;
+use crate::error::{Error, Result};
+use crate::{ihex, o65, prg, srec, xex};
+
+#[cfg(test)]
+#[path="./loader_test.rs"]
+mod loader_test;
+
+// Cycles an init routine may take before loading gives up on it
+const INIT_CYCLES : u64 = 100_000_000;
+
+// A block of bytes placed in memory starting at address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub address : u16,
+    pub data : Vec<u8>,
+    // Subroutine called by Image::load as soon as the segment is in memory,
+    // e.g. the INITAD routine of an Atari executable
+    pub init : Option<u16>,
+}
+
+// A program ready to be mounted into memory. Segments are mounted in order,
+// so a later segment overwrites an earlier one where they overlap.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Image {
+    pub segments : Vec<Segment>,
+    pub entry : Option<u16>,
+}
+
+impl Image {
+    pub fn new() -> Image {
+        Image::default()
+    }
+
+    // Image of a flat binary placed at base. Fails if it would run past the
+    // end of memory.
+    pub fn from_binary(data : &[u8], base : u16) -> Result<Image> {
+        let mut image = Image::new();
+        image.add_segment(base, data)?;
+        Ok(image)
+    }
+
+    pub fn add_segment(&mut self, address : u16, data : &[u8]) -> Result<()> {
+        if (address as usize) + data.len() > crate::MAX_MEM {
+            return Err(Error::DataDoesNotFit { addr : address as u32, len : data.len() });
+        }
+        self.segments.push(Segment{ address, data : data.to_vec(), init : None });
+        Ok(())
+    }
+
+    // Appends data at a possibly out of range address, extending the last
+    // segment if the data continues it. Used by the record based formats,
+    // which can describe sparse images in many small pieces.
+    pub(crate) fn append(&mut self, address : u32, data : &[u8]) -> Result<()> {
+        if address as usize + data.len() > crate::MAX_MEM {
+            return Err(Error::DataDoesNotFit { addr : address, len : data.len() });
+        }
+        if let Some(last) = self.segments.last_mut() {
+            if last.address as usize + last.data.len() == address as usize {
+                last.data.extend_from_slice(data);
+                return Ok(());
+            }
+        }
+        self.add_segment(address as u16, data)
+    }
+
+    // Image of len bytes of the memory of the CPU starting at address, e.g.
+    // to save a patched ROM
+    pub fn from_memory<B : Bus>(cpu : &CPU<B>, address : u16, len : usize) -> Result<Image> {
+        if (address as usize) + len > crate::MAX_MEM {
+            return Err(Error::DataDoesNotFit { addr : address as u32, len });
+        }
+        let data = (0..len)
+            .map(|i| cpu.read_mem(address.wrapping_add(i as u16)))
+            .collect::<Result<Vec<u8>>>()?;
+        Image::from_binary(&data, address)
+    }
+
+    // Points the 16 bit vector at the given address to target
+    pub fn patch_vector(&mut self, vector : u16, target : u16){
+        self.segments.push(Segment{ address : vector, data : target.to_le_bytes().to_vec(), init : None });
+    }
+
+    // Points the NMI, RESET and IRQ vectors at entry, so the program starts
+    // there after a reset and stray interrupts restart it instead of running
+    // whatever is in the vector area
+    pub fn patch_vectors(&mut self, entry : u16){
+        self.patch_vector(crate::NMI_VEC, entry);
+        self.patch_vector(crate::RESET_VEC, entry);
+        self.patch_vector(crate::IRQ_VEC, entry);
+        self.entry = Some(entry);
+    }
+
+    // Copies every segment into the memory of the CPU, without running the
+    // init routines
+    pub fn mount<B : Bus>(&self, cpu : &mut CPU<B>) -> Result<()> {
+        for segment in &self.segments {
+            cpu.mount_mem(segment.address, &segment.data)?;
+        }
+        Ok(())
+    }
+
+    // Mounts the segments in order like the Atari DOS loads them, running
+    // the init routine of a segment before the next one is mounted. That
+    // way a later segment may overwrite the code and data of an init
+    // routine that has already run.
+    pub fn load<B : Bus>(&self, cpu : &mut CPU<B>) -> Result<()> {
+        for segment in &self.segments {
+            cpu.mount_mem(segment.address, &segment.data)?;
+            if let Some(init) = segment.init {
+                call(cpu, init)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Points PC at the entry of the program, normally right after a reset
+    pub fn start<B : Bus>(&self, cpu : &mut CPU<B>) -> Result<()> {
+        if let Some(entry) = self.entry {
+            cpu.set_pc(entry);
+        }
+        Ok(())
+    }
+}
+
+// Runs the subroutine at address until its RTS returns to the current PC,
+// which is pushed as the return address
+fn call<B : Bus>(cpu : &mut CPU<B>, address : u16) -> Result<()> {
+    let (pc, sp) = (cpu.pc(), cpu.sp());
+    let [lo, hi] = pc.wrapping_sub(1).to_le_bytes();
+    cpu.write_mem(0x0100 | sp as u16, hi)?;
+    cpu.write_mem(0x0100 | sp.wrapping_sub(1) as u16, lo)?;
+    cpu.set_sp(sp.wrapping_sub(2));
+    cpu.set_pc(address);
+
+    let mut elapsed = 0;
+    while cpu.pc() != pc || cpu.sp() != sp {
+        if cpu.halted() || elapsed >= INIT_CYCLES {
+            return Err(Error::InitDidNotReturn(address));
+        }
+        elapsed += cpu.step() as u64;
+    }
+    Ok(())
+}
+
+// Reads a flat binary, e.g. the output of ld65, and places it at base
+pub fn load_binary<P : AsRef<Path>>(path : P, base : u16) -> Result<Image> {
+    let data = fs::read(path)?;
+    Image::from_binary(&data, base)
+}
+
+// Reads a program in the format given by the extension of the file: Intel
+// HEX (.hex, .ihx), S-records (.s19, .s28, .s37, .srec), Commodore (.prg),
+// Atari (.xex), o65 (.o65), assembler source (.s, .asm) or else a flat
+// binary. Flat binaries are placed at base, o65 files are relocated there.
+pub fn load_file<P : AsRef<Path>>(path : P, base : Option<u16>) -> Result<Image> {
+    let path = path.as_ref();
+    match extension(path).as_deref() {
+        Some("hex" | "ihx") => ihex::load(path),
+        Some("s19" | "s28" | "s37" | "srec") => srec::load(path),
+        Some("prg") => prg::load(path),
+        Some("xex") => xex::load(path),
+        Some("s" | "asm") => Ok(Assembler::default().assemble_file(path)?.image),
+        Some("o65") => {
+            let bytes = fs::read(path)?;
+            o65::parse(&bytes, base.map(|text| o65_bases(&bytes, text)).transpose()?)
+        }
+        _ => load_binary(path, base.unwrap_or(0)),
+    }
+}
+
+// Writes an image in the format given by the extension of the file, as
+// load_file reads it. Flat binaries run from the lowest to the highest
+// address of the image with the gaps filled with zeros.
+pub fn save_file<P : AsRef<Path>>(path : P, image : &Image) -> Result<()> {
+    let path = path.as_ref();
+    match extension(path).as_deref() {
+        Some("hex" | "ihx") => ihex::save(path, image),
+        Some("s19" | "s28" | "s37" | "srec") => srec::save(path, image),
+        Some("prg") => prg::save(path, image),
+        Some("xex") => xex::save(path, image),
+        _ => Ok(fs::write(path, flat_binary(image))?),
+    }
+}
+
+// Lower case extension of a file name
+pub(crate) fn extension(path : &Path) -> Option<String> {
+    path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+fn flat_binary(image : &Image) -> Vec<u8> {
+    let start = image.segments.iter().map(|segment| segment.address as usize).min().unwrap_or(0);
+    let end = image.segments.iter().map(|segment| segment.address as usize + segment.data.len()).max().unwrap_or(0);
+    let mut bytes = vec![0 ; end.saturating_sub(start)];
+    for segment in &image.segments {
+        let offset = segment.address as usize - start;
+        bytes[offset..offset + segment.data.len()].copy_from_slice(&segment.data);
+    }
+    bytes
+}
+
+// Relocates an o65 file to text, with the data and bss segments following
+// it and the zero page segment left where it is
+fn o65_bases(bytes : &[u8], text : u16) -> Result<o65::Bases> {
+    let header = o65::header(bytes)?;
+    let data = text.wrapping_add(header.text_len);
+    let bss = data.wrapping_add(header.data_len);
+    Ok(o65::Bases{ text, data, bss, zero : header.bases.zero })
+}
+
+// Decodes a string of hex digit pairs
+pub(crate) fn hex_bytes(text : &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+#[test]
+fn test_binary_must_fit(){
+    let image = Image::from_binary(&[0xEA ; 0x100], 0xFF00).unwrap();
+    assert_eq!(image.segments, vec![Segment{ address : 0xFF00, data : vec![0xEA ; 0x100], init : None }]);
+    assert_eq!(image.entry, None);
+
+    let result = Image::from_binary(&[0xEA ; 0x101], 0xFF00);
+    assert!(matches!(result, Err(Error::DataDoesNotFit { addr : 0xFF00, len : 0x101 })));
+}
+
+#[test]
+fn test_patched_vectors_start_program(){
+    // LDA #$42; JAM
+    let mut image = Image::from_binary(&[0xA9, 0x42, 0x02], 0xC000).unwrap();
+    image.patch_vectors(0xC000);
+    assert_eq!(image.entry, Some(0xC000));
+
+    let mut cpu : CPU = CPU::new();
+    image.mount(&mut cpu).unwrap();
+    for vector in [crate::NMI_VEC, crate::RESET_VEC, crate::IRQ_VEC] {
+        assert_eq!(cpu.read_mem(vector).unwrap(), 0x00);
+        assert_eq!(cpu.read_mem(vector + 1).unwrap(), 0xC0);
+    }
+    cpu.reset();
+    cpu.run(100);
+    assert!(cpu.halted());
+    assert_eq!(cpu.a(), 0x42);
+}
+
+#[test]
+fn test_load_binary_from_file(){
+    let path = std::env::temp_dir().join(format!("rs6502-loader-{}.bin", std::process::id()));
+    fs::write(&path, [1, 2, 3]).unwrap();
+    let image = load_binary(&path, 0x0800).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(image.segments[0], Segment{ address : 0x0800, data : vec![1, 2, 3], init : None });
+
+    assert!(matches!(load_binary(&path, 0x0800), Err(Error::Io(_))));
+}
+
+#[test]
+fn test_load_runs_init_routines(){
+    // $0300: INX; RTS  $0310: INY; RTS  $0320: JAM
+    let mut image = Image::from_binary(&[0xE8, 0x60], 0x0300).unwrap();
+    image.add_segment(0x0310, &[0xC8, 0x60]).unwrap();
+    image.add_segment(0x0320, &[0x02]).unwrap();
+    image.segments[0].init = Some(0x0300);
+    image.segments[1].init = Some(0x0310);
+    image.entry = Some(0x0320);
+
+    let mut cpu : CPU = CPU::new();
+    let (pc, sp) = (cpu.pc(), cpu.sp());
+    image.load(&mut cpu).unwrap();
+    assert_eq!((cpu.pc(), cpu.x(), cpu.y(), cpu.sp()), (pc, 1, 1, sp));
+    image.start(&mut cpu).unwrap();
+    assert_eq!(cpu.pc(), 0x0320);
+
+    // an init routine that never returns
+    image.segments[2].init = Some(0x0320);
+    assert!(matches!(image.load(&mut cpu), Err(Error::InitDidNotReturn(0x0320))));
+}
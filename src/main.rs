@@ -1,11 +1,304 @@
-mod cpu;
+use std::env;
+use std::io;
+use std::process;
+
+use rs6502::ines::Rom;
+use rs6502::source::Analysis;
+use rs6502::{loader, Assembler, Bus, CpuVariant, Disassembler, Image, Monitor, NesBus, CPU};
+
+const USAGE : &str = "\
+usage: rs6502 run <file> [--base ADDR] [--entry ADDR] [--vectors] [--cycles N] [--variant CPU]
+       rs6502 disasm <file> [--base ADDR] [--start ADDR] [--end ADDR] [--variant CPU]
+                     [--source [--entry ADDR]...]
+       rs6502 asm <file> --output FILE [--symbols] [--variant CPU]
+       rs6502 monitor [file] [--base ADDR] [--entry ADDR] [--variant CPU]
+
+The file is read as Intel HEX (.hex, .ihx), S-records (.s19, .s28, .s37,
+.srec), a Commodore program (.prg), an Atari executable (.xex), an o65
+executable (.o65), assembler source (.s, .asm) or otherwise as a flat
+binary. NES cartridges (.nes) run on a 2A03 with their mapper and start
+at the RESET vector. The asm command writes the same formats, picked by
+the extension of the output file. The monitor command starts an
+interactive debugger, type h at its prompt for the commands.
+
+options:
+    --base ADDR      load address of a flat binary (default $0000), or
+                     where to relocate the text segment of an o65 file
+    --entry ADDR     start address, otherwise the one in the file or the
+                     RESET vector is used. With --source, an additional
+                     entry point to trace code from; may be repeated.
+    --vectors        point the NMI, RESET and IRQ vectors at the entry
+    --cycles N       stop after N cycles (default 1000000)
+    --start ADDR     first address to disassemble, otherwise every
+                     segment of the file is listed
+    --end ADDR       last address to disassemble (default $FFFF)
+    --source         trace the code from the vectors and entry points and
+                     write ca65 source that reassembles to the same bytes
+    --output FILE    file to write the assembled program to
+    --symbols        print the symbol table of the program
+    --variant CPU    6502, 65c02, 2a03 or 6510 (default 6502)
+
+Numbers are decimal, or hexadecimal with a $ or 0x prefix.";
 
 fn main() {
-    let mut cpu : cpu::CPU = cpu::new();
-    
+    let args : Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("disasm") => disasm(&args[1..]),
+        Some("asm") => asm(&args[1..]),
+        Some("monitor") => monitor(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
+}
+
+// Command line of the commands
+struct Options {
+    file : Option<String>,
+    base : Option<u16>,
+    entries : Vec<u16>,
+    vectors : bool,
+    cycles : u64,
+    start : Option<u16>,
+    end : Option<u16>,
+    variant : Option<CpuVariant>,
+    source : bool,
+    output : Option<String>,
+    symbols : bool,
+}
+
+impl Options {
+    // Parses the arguments after the command, accepting only the options
+    // listed in allowed
+    fn parse(args : &[String], allowed : &[&str]) -> Result<Options, String> {
+        let mut options = Options{
+            file : None, base : None, entries : Vec::new(), vectors : false,
+            cycles : 1_000_000, start : None, end : None, variant : None, source : false,
+            output : None, symbols : false,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg.starts_with("--") && !allowed.contains(&arg.as_str()) {
+                return Err(format!("unknown option {}", arg));
+            }
+            match arg.as_str() {
+                "--base" => options.base = Some(parse_address(value(&mut args, arg)?)?),
+                "--entry" => options.entries.push(parse_address(value(&mut args, arg)?)?),
+                "--vectors" => options.vectors = true,
+                "--cycles" => options.cycles = parse_number(value(&mut args, arg)?)?,
+                "--start" => options.start = Some(parse_address(value(&mut args, arg)?)?),
+                "--end" => options.end = Some(parse_address(value(&mut args, arg)?)?),
+                "--variant" => options.variant = Some(parse_variant(value(&mut args, arg)?)?),
+                "--source" => options.source = true,
+                "--output" => options.output = Some(value(&mut args, arg)?.to_string()),
+                "--symbols" => options.symbols = true,
+                _ if options.file.is_none() => options.file = Some(arg.clone()),
+                _ => return Err(format!("unexpected argument {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    fn file(&self) -> Result<&str, String> {
+        self.file.as_deref().ok_or_else(|| String::from("no input file given"))
+    }
+
+    fn is_nes(&self) -> bool {
+        self.file.as_ref().is_some_and(|file| file.to_ascii_lowercase().ends_with(".nes"))
+    }
+
+    // CPU with a NES cartridge on its bus, a 2A03 unless asked otherwise
+    fn nes_cpu(&self) -> Result<CPU<NesBus>, String> {
+        let file = self.file()?;
+        let rom = Rom::load(file).map_err(|e| format!("{}: {}", file, e))?;
+        let bus = NesBus::from_rom(rom).map_err(|e| format!("{}: {}", file, e))?;
+        Ok(CPU::builder().bus(bus).variant(self.variant.unwrap_or(CpuVariant::Ricoh2A03)).build())
+    }
+
+    fn image(&self) -> Result<Image, String> {
+        let file = self.file()?;
+        loader::load_file(file, self.base).map_err(|e| file_error(file, e))
+    }
+}
+
+// Loads a program and runs it until the CPU halts or the cycle budget
+// is used up
+fn run(args : &[String]) -> Result<(), String> {
+    let options = Options::parse(args, &["--base", "--entry", "--vectors", "--cycles", "--variant"])?;
+
+    if options.is_nes() {
+        let mut cpu = options.nes_cpu()?;
+        cpu.reset();
+        if let Some(&entry) = options.entries.last() {
+            cpu.set_pc(entry);
+        }
+        execute(&mut cpu, options.cycles);
+        return Ok(());
+    }
+
+    let mut image = options.image()?;
+    image.entry = options.entries.last().copied().or(image.entry);
+    if options.vectors {
+        image.patch_vectors(image.entry.ok_or_else(|| String::from("--vectors needs --entry"))?);
+    }
+
+    let mut cpu = CPU::builder().variant(options.variant.unwrap_or_default()).build();
+    image.load(&mut cpu).map_err(|e| e.to_string())?;
     cpu.reset();
+    image.start(&mut cpu).map_err(|e| e.to_string())?;
+    execute(&mut cpu, options.cycles);
+    Ok(())
+}
 
+fn execute<B : Bus>(cpu : &mut CPU<B>, cycles : u64){
+    let elapsed = cpu.run(cycles);
     println!("{:#?}", cpu);
-    cpu.fetch();
-    cpu.execute();
+    println!("Executed {} cycles{}", elapsed, if cpu.halted() { ", CPU halted" } else { "" });
+}
+
+// Lists a range of a program, or all of its segments
+fn disasm(args : &[String]) -> Result<(), String> {
+    let options = Options::parse(args, &["--base", "--start", "--end", "--variant", "--source", "--entry"])?;
+
+    if options.is_nes() {
+        let cpu = options.nes_cpu()?;
+        let range = (options.start.unwrap_or(0x8000), options.end.unwrap_or(0xFFFF));
+        if options.source {
+            return source(&cpu, range, &options.entries);
+        }
+        return list(&cpu, &[range]);
+    }
+
+    let image = options.image()?;
+    let mut cpu = CPU::builder().variant(options.variant.unwrap_or_default()).build();
+    image.mount(&mut cpu).map_err(|e| e.to_string())?;
+    let ranges : Vec<(u16, u16)> = match options.start {
+        Some(start) => vec![(start, options.end.unwrap_or(0xFFFF))],
+        None => image.segments.iter()
+            .filter(|segment| !segment.data.is_empty())
+            .map(|segment| (segment.address, segment.address + (segment.data.len() - 1) as u16))
+            .collect(),
+    };
+    if options.source {
+        // one listing from the first to the last byte of the file
+        let start = ranges.iter().map(|range| range.0).min().ok_or("the file is empty")?;
+        let end = options.end.unwrap_or(ranges.iter().map(|range| range.1).max().unwrap());
+        return source(&cpu, (start, end), &options.entries);
+    }
+    list(&cpu, &ranges)
+}
+
+fn source<B : Bus>(cpu : &CPU<B>, (start, end) : (u16, u16), entries : &[u16]) -> Result<(), String> {
+    let bytes = (start..=end)
+        .map(|address| cpu.read_mem(address))
+        .collect::<rs6502::Result<Vec<u8>>>()
+        .map_err(|e| e.to_string())?;
+    let analysis = Analysis::new(&bytes, start, cpu.variant(), entries).map_err(|e| e.to_string())?;
+    print!("{}", analysis.to_source());
+    Ok(())
+}
+
+// Assembles a source file and writes the program
+fn asm(args : &[String]) -> Result<(), String> {
+    let options = Options::parse(args, &["--output", "--symbols", "--variant"])?;
+    let output = options.output.as_deref().ok_or_else(|| String::from("asm needs --output"))?;
+    let file = options.file()?;
+    let program = Assembler::new(options.variant.unwrap_or_default())
+        .assemble_file(file)
+        .map_err(|e| file_error(file, e))?;
+    loader::save_file(output, &program.image).map_err(|e| format!("{}: {}", output, e))?;
+    if options.symbols {
+        for (name, value) in &program.symbols {
+            println!("{:<24} ${:04X}", name, value);
+        }
+    }
+    Ok(())
+}
+
+// Loads a program if one is given and debugs it in the monitor, reading
+// commands from the terminal
+fn monitor(args : &[String]) -> Result<(), String> {
+    let options = Options::parse(args, &["--base", "--entry", "--variant"])?;
+
+    if options.is_nes() {
+        let mut cpu = options.nes_cpu()?;
+        cpu.reset();
+        if let Some(&entry) = options.entries.last() {
+            cpu.set_pc(entry);
+        }
+        return interact(Monitor::new(cpu));
+    }
+
+    let mut monitor = Monitor::new(CPU::builder().variant(options.variant.unwrap_or_default()).build());
+    let image = match &options.file {
+        Some(file) => monitor.load(file, options.base).map_err(|e| file_error(file, e))?,
+        None => Image::new(),
+    };
+    monitor.cpu_mut().reset();
+    image.start(monitor.cpu_mut()).map_err(|e| e.to_string())?;
+    if let Some(&entry) = options.entries.last() {
+        monitor.cpu_mut().set_pc(entry);
+    }
+    interact(monitor)
+}
+
+fn interact<B : Bus>(mut monitor : Monitor<B>) -> Result<(), String> {
+    monitor.run(io::stdin().lock(), io::stdout()).map_err(|e| e.to_string())
+}
+
+fn list<B : Bus>(cpu : &CPU<B>, ranges : &[(u16, u16)]) -> Result<(), String> {
+    let disassembler = Disassembler::new(cpu.variant());
+    for (i, &(start, end)) in ranges.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        for line in disassembler.disassemble_memory(cpu, start, end).map_err(|e| e.to_string())? {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+// Error message naming the file, unless the error is from the assembler and
+// already names the line it is on
+fn file_error(file : &str, e : rs6502::Error) -> String {
+    match e {
+        rs6502::Error::Assembly { .. } => e.to_string(),
+        _ => format!("{}: {}", file, e),
+    }
+}
+
+fn value<'a>(args : &mut std::slice::Iter<'a, String>, option : &str) -> Result<&'a str, String> {
+    args.next().map(String::as_str).ok_or_else(|| format!("{} needs a value", option))
+}
+
+fn parse_number(text : &str) -> Result<u64, String> {
+    let parsed = if let Some(hex) = text.strip_prefix('$').or_else(|| text.strip_prefix("0x")) {
+        u64::from_str_radix(hex, 16)
+    } else {
+        text.parse()
+    };
+    parsed.map_err(|_| format!("invalid number {}", text))
+}
+
+fn parse_address(text : &str) -> Result<u16, String> {
+    u16::try_from(parse_number(text)?).map_err(|_| format!("address {} is out of range", text))
+}
+
+fn parse_variant(text : &str) -> Result<CpuVariant, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "6502" | "nmos" => Ok(CpuVariant::Nmos6502),
+        "65c02" | "cmos" => Ok(CpuVariant::Cmos65C02),
+        "2a03" | "nes" => Ok(CpuVariant::Ricoh2A03),
+        "6510" | "c64" => Ok(CpuVariant::Mos6510),
+        _ => Err(format!("unknown CPU variant {}", text)),
+    }
 }
@@ -0,0 +1,540 @@
+use crate::bus::{BusCycle, Direction};
+use crate::error::{Error, Result};
+use crate::ines::{Mirroring, Rom};
+
+#[cfg(test)]
+#[path="./mapper_test.rs"]
+mod mapper_test;
+
+const PRG_WINDOW : usize = 8 * 1024;
+const CHR_WINDOW : usize = 1024;
+const CHR_RAM_SIZE : usize = 8 * 1024;
+const TRAINER_ADDR : usize = 0x1000;
+
+// ROM, RAM and the current bank layout of a cartridge. The CPU sees PRG RAM
+// at $6000-$7FFF and PRG ROM through four 8 KiB windows at $8000-$FFFF, the
+// PPU sees CHR ROM or RAM through eight 1 KiB windows at $0000-$1FFF.
+// Mappers only decide which bank goes into which window.
+#[derive(Debug, Clone)]
+pub struct Cartridge {
+    prg_rom : Vec<u8>,
+    prg_ram : Vec<u8>,
+    chr : Vec<u8>,
+    chr_writable : bool,
+    prg_windows : [usize ; 4],
+    chr_windows : [usize ; 8],
+    prg_ram_enabled : bool,
+    prg_ram_writable : bool,
+    mirroring : Mirroring,
+    battery : bool,
+}
+
+impl Cartridge {
+    pub fn new(rom : Rom) -> Cartridge {
+        let header = rom.header;
+        let mut prg_ram = vec![0 ; header.prg_ram_size + header.prg_nvram_size];
+        if let Some(trainer) = &rom.trainer {
+            if prg_ram.len() < TRAINER_ADDR + trainer.len() {
+                prg_ram.resize(PRG_WINDOW, 0);
+            }
+            prg_ram[TRAINER_ADDR..TRAINER_ADDR + trainer.len()].copy_from_slice(trainer);
+        }
+        let chr_writable = rom.chr_rom.is_empty();
+        let chr = if chr_writable {
+            vec![0 ; (header.chr_ram_size + header.chr_nvram_size).max(CHR_RAM_SIZE)]
+        } else {
+            rom.chr_rom
+        };
+
+        let mut cartridge = Cartridge{
+            prg_rom : rom.prg_rom,
+            prg_ram,
+            chr,
+            chr_writable,
+            prg_windows : [0 ; 4],
+            chr_windows : [0 ; 8],
+            prg_ram_enabled : true,
+            prg_ram_writable : true,
+            mirroring : header.mirroring,
+            battery : header.battery,
+        };
+        cartridge.set_prg_32k(0);
+        cartridge.set_chr_8k(0);
+        cartridge
+    }
+
+    // Number of banks of the given size, at least one so that bank numbers
+    // can always be reduced modulo it
+    fn prg_banks(&self, size : usize) -> usize {
+        (self.prg_rom.len() / size).max(1)
+    }
+
+    fn chr_banks(&self, size : usize) -> usize {
+        (self.chr.len() / size).max(1)
+    }
+
+    // Maps a bank into one of the windows, counting banks in units of the
+    // given size. Bank numbers wrap around the size of the ROM like the
+    // unconnected upper bank lines of real boards, and ROMs smaller than
+    // the bank size are mirrored within it.
+    pub fn set_prg_8k(&mut self, window : usize, bank : usize){
+        self.prg_windows[window] = (bank % self.prg_banks(PRG_WINDOW)) * PRG_WINDOW;
+    }
+
+    pub fn set_prg_16k(&mut self, window : usize, bank : usize){
+        let base = (bank % self.prg_banks(2 * PRG_WINDOW)) * 2 * PRG_WINDOW;
+        for i in 0..2 {
+            self.prg_windows[window * 2 + i] = base + i * PRG_WINDOW;
+        }
+    }
+
+    pub fn set_prg_32k(&mut self, bank : usize){
+        let base = (bank % self.prg_banks(4 * PRG_WINDOW)) * 4 * PRG_WINDOW;
+        for i in 0..4 {
+            self.prg_windows[i] = base + i * PRG_WINDOW;
+        }
+    }
+
+    pub fn set_chr_1k(&mut self, window : usize, bank : usize){
+        self.chr_windows[window] = (bank % self.chr_banks(CHR_WINDOW)) * CHR_WINDOW;
+    }
+
+    pub fn set_chr_4k(&mut self, window : usize, bank : usize){
+        let base = (bank % self.chr_banks(4 * CHR_WINDOW)) * 4 * CHR_WINDOW;
+        for i in 0..4 {
+            self.chr_windows[window * 4 + i] = base + i * CHR_WINDOW;
+        }
+    }
+
+    pub fn set_chr_8k(&mut self, bank : usize){
+        let base = (bank % self.chr_banks(8 * CHR_WINDOW)) * 8 * CHR_WINDOW;
+        for i in 0..8 {
+            self.chr_windows[i] = base + i * CHR_WINDOW;
+        }
+    }
+
+    // Number of the last bank of the given size, e.g. for fixed windows
+    pub fn last_prg_bank(&self, size : usize) -> usize {
+        self.prg_banks(size) - 1
+    }
+
+    pub fn set_prg_ram_access(&mut self, enabled : bool, writable : bool){
+        self.prg_ram_enabled = enabled;
+        self.prg_ram_writable = writable;
+    }
+
+    pub fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    // Four screen boards wire the nametables themselves and ignore the
+    // mirroring selected by the mapper
+    pub fn set_mirroring(&mut self, mirroring : Mirroring){
+        if self.mirroring != Mirroring::FourScreen {
+            self.mirroring = mirroring;
+        }
+    }
+
+    pub fn battery(&self) -> bool {
+        self.battery
+    }
+
+    // Contents of the PRG RAM, to be saved when the board has a battery
+    pub fn prg_ram(&self) -> &[u8] {
+        &self.prg_ram
+    }
+
+    pub fn prg_ram_mut(&mut self) -> &mut [u8] {
+        &mut self.prg_ram
+    }
+
+    fn prg_ram_index(&self, address : u16) -> Option<usize> {
+        match address {
+            0x6000..=0x7FFF if self.prg_ram_enabled && !self.prg_ram.is_empty() =>
+                Some((address as usize - 0x6000) % self.prg_ram.len()),
+            _ => None,
+        }
+    }
+
+    fn prg_rom_index(&self, address : u16) -> Option<usize> {
+        match address {
+            0x8000..=0xFFFF if !self.prg_rom.is_empty() => {
+                let offset = address as usize - 0x8000;
+                Some((self.prg_windows[offset / PRG_WINDOW] + offset % PRG_WINDOW) % self.prg_rom.len())
+            }
+            _ => None,
+        }
+    }
+
+    fn chr_index(&self, address : u16) -> usize {
+        let offset = address as usize & 0x1FFF;
+        (self.chr_windows[offset / CHR_WINDOW] + offset % CHR_WINDOW) % self.chr.len()
+    }
+
+    pub fn is_mapped(&self, address : u16) -> bool {
+        self.prg_ram_index(address).is_some() || self.prg_rom_index(address).is_some()
+    }
+
+    // CPU read of cartridge space, None where nothing drives the bus
+    pub fn read_prg(&self, address : u16) -> Option<u8> {
+        if let Some(index) = self.prg_ram_index(address) {
+            return Some(self.prg_ram[index]);
+        }
+        self.prg_rom_index(address).map(|index| self.prg_rom[index])
+    }
+
+    // CPU write to PRG RAM. Writes to ROM go to the mapper registers instead.
+    pub fn write_prg_ram(&mut self, address : u16, data : u8){
+        if self.prg_ram_writable {
+            if let Some(index) = self.prg_ram_index(address) {
+                self.prg_ram[index] = data;
+            }
+        }
+    }
+
+    // Writes the byte currently visible at address, ROM included. Used to
+    // load or patch code without touching the mapper registers.
+    pub fn poke_prg(&mut self, address : u16, data : u8){
+        if let Some(index) = self.prg_ram_index(address) {
+            self.prg_ram[index] = data;
+        } else if let Some(index) = self.prg_rom_index(address) {
+            self.prg_rom[index] = data;
+        }
+    }
+
+    // PPU access to the pattern tables
+    pub fn read_chr(&self, address : u16) -> u8 {
+        self.chr[self.chr_index(address)]
+    }
+
+    pub fn write_chr(&mut self, address : u16, data : u8){
+        if self.chr_writable {
+            let index = self.chr_index(address);
+            self.chr[index] = data;
+        }
+    }
+}
+
+// Bank switching hardware of a cartridge. The bus forwards the CPU accesses
+// to $4020-$FFFF to the mapper, which owns the cartridge memory. Only writes
+// change the bank layout, so reads go straight to the cartridge.
+pub trait Mapper {
+    fn cartridge(&self) -> &Cartridge;
+
+    fn cartridge_mut(&mut self) -> &mut Cartridge;
+
+    // CPU write to cartridge space
+    fn write(&mut self, address : u16, data : u8);
+
+    // Interrupt line driven by the cartridge
+    fn irq(&self) -> bool {
+        false
+    }
+
+    // Called by the PPU once per rendered scanline, when A12 rises as it
+    // starts fetching sprite patterns
+    fn scanline(&mut self){}
+
+    // Called after every CPU cycle when the CPU runs in cycle accurate mode
+    fn cycle(&mut self, _cycle : BusCycle){}
+
+    fn read(&self, address : u16) -> Option<u8> {
+        self.cartridge().read_prg(address)
+    }
+}
+
+// Builds the mapper the header of the ROM asks for
+pub fn from_rom(rom : Rom) -> Result<Box<dyn Mapper>> {
+    let number = rom.header.mapper;
+    let cartridge = Cartridge::new(rom);
+    Ok(match number {
+        0 => Box::new(Nrom::new(cartridge)),
+        1 => Box::new(Mmc1::new(cartridge)),
+        2 => Box::new(UxRom::new(cartridge)),
+        3 => Box::new(CnRom::new(cartridge)),
+        4 => Box::new(Mmc3::new(cartridge)),
+        _ => return Err(Error::UnsupportedMapper(number)),
+    })
+}
+
+// Mapper 0: 16 or 32 KiB of PRG ROM and 8 KiB of CHR, no bank switching.
+// A 16 KiB ROM appears twice.
+pub struct Nrom {
+    cartridge : Cartridge,
+}
+
+impl Nrom {
+    pub fn new(mut cartridge : Cartridge) -> Nrom {
+        cartridge.set_prg_16k(0, 0);
+        let last = cartridge.last_prg_bank(2 * PRG_WINDOW);
+        cartridge.set_prg_16k(1, last);
+        Nrom{ cartridge }
+    }
+}
+
+impl Mapper for Nrom {
+    fn cartridge(&self) -> &Cartridge { &self.cartridge }
+    fn cartridge_mut(&mut self) -> &mut Cartridge { &mut self.cartridge }
+
+    fn write(&mut self, address : u16, data : u8){
+        self.cartridge.write_prg_ram(address, data);
+    }
+}
+
+// Mapper 1: Nintendo MMC1. Registers are loaded serially, one bit per write
+// LSB first, and the fifth write stores the value into the register picked
+// by bits 13-14 of its address. Writing a value with bit 7 set resets the
+// shift register and selects the mode with the last PRG bank fixed. A
+// write on the cycle after another one, as by the double write of INC, is
+// ignored.
+pub struct Mmc1 {
+    cartridge : Cartridge,
+    shift : u8,
+    writes : u8,
+    written : bool,     // the last cycle wrote to the registers
+    control : u8,
+    chr0 : u8,
+    chr1 : u8,
+    prg : u8,
+}
+
+impl Mmc1 {
+    pub fn new(cartridge : Cartridge) -> Mmc1 {
+        let mut mapper = Mmc1{ cartridge, shift : 0, writes : 0, written : false, control : 0x0C, chr0 : 0, chr1 : 0, prg : 0 };
+        mapper.update();
+        mapper
+    }
+
+    fn update(&mut self){
+        let cart = &mut self.cartridge;
+        cart.set_mirroring(match self.control & 0x03 {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        });
+
+        let prg = (self.prg & 0x0F) as usize;
+        match (self.control >> 2) & 0x03 {
+            0 | 1 => cart.set_prg_32k(prg >> 1),
+            2 => {
+                cart.set_prg_16k(0, 0);
+                cart.set_prg_16k(1, prg);
+            }
+            _ => {
+                let last = cart.last_prg_bank(2 * PRG_WINDOW);
+                cart.set_prg_16k(0, prg);
+                cart.set_prg_16k(1, last);
+            }
+        }
+        cart.set_prg_ram_access(self.prg & 0x10 == 0, true);
+
+        if self.control & 0x10 == 0 {
+            cart.set_chr_8k((self.chr0 >> 1) as usize);
+        } else {
+            cart.set_chr_4k(0, self.chr0 as usize);
+            cart.set_chr_4k(1, self.chr1 as usize);
+        }
+    }
+}
+
+impl Mapper for Mmc1 {
+    fn cartridge(&self) -> &Cartridge { &self.cartridge }
+    fn cartridge_mut(&mut self) -> &mut Cartridge { &mut self.cartridge }
+
+    fn write(&mut self, address : u16, data : u8){
+        if address < 0x8000 {
+            self.cartridge.write_prg_ram(address, data);
+            return;
+        }
+        if self.written {
+            return;
+        }
+        if data & 0x80 != 0 {
+            self.shift = 0;
+            self.writes = 0;
+            self.control |= 0x0C;
+            self.update();
+            return;
+        }
+
+        self.shift |= (data & 0x01) << self.writes;
+        self.writes += 1;
+        if self.writes == 5 {
+            let value = self.shift;
+            match (address >> 13) & 0x03 {
+                0 => self.control = value,
+                1 => self.chr0 = value,
+                2 => self.chr1 = value,
+                _ => self.prg = value,
+            }
+            self.shift = 0;
+            self.writes = 0;
+            self.update();
+        }
+    }
+
+    fn cycle(&mut self, cycle : BusCycle){
+        self.written = cycle.direction == Direction::Write && cycle.address >= 0x8000;
+    }
+}
+
+// Mapper 2: UxROM. Any write to ROM selects the 16 KiB bank at $8000, the
+// last bank is fixed at $C000. CHR is 8 KiB of RAM.
+pub struct UxRom {
+    cartridge : Cartridge,
+}
+
+impl UxRom {
+    pub fn new(mut cartridge : Cartridge) -> UxRom {
+        let last = cartridge.last_prg_bank(2 * PRG_WINDOW);
+        cartridge.set_prg_16k(0, 0);
+        cartridge.set_prg_16k(1, last);
+        UxRom{ cartridge }
+    }
+}
+
+impl Mapper for UxRom {
+    fn cartridge(&self) -> &Cartridge { &self.cartridge }
+    fn cartridge_mut(&mut self) -> &mut Cartridge { &mut self.cartridge }
+
+    fn write(&mut self, address : u16, data : u8){
+        if address < 0x8000 {
+            self.cartridge.write_prg_ram(address, data);
+        } else {
+            self.cartridge.set_prg_16k(0, data as usize);
+        }
+    }
+}
+
+// Mapper 3: CNROM. PRG is fixed like NROM, any write to ROM selects the
+// 8 KiB CHR bank.
+pub struct CnRom {
+    cartridge : Cartridge,
+}
+
+impl CnRom {
+    pub fn new(cartridge : Cartridge) -> CnRom {
+        CnRom{ cartridge : Nrom::new(cartridge).cartridge }
+    }
+}
+
+impl Mapper for CnRom {
+    fn cartridge(&self) -> &Cartridge { &self.cartridge }
+    fn cartridge_mut(&mut self) -> &mut Cartridge { &mut self.cartridge }
+
+    fn write(&mut self, address : u16, data : u8){
+        if address < 0x8000 {
+            self.cartridge.write_prg_ram(address, data);
+        } else {
+            self.cartridge.set_chr_8k(data as usize);
+        }
+    }
+}
+
+// Mapper 4: Nintendo MMC3. Eight bank registers select two switchable
+// 8 KiB PRG banks, two 2 KiB and four 1 KiB CHR banks. The scanline
+// counter asserts IRQ when it is clocked down to zero while enabled.
+pub struct Mmc3 {
+    cartridge : Cartridge,
+    select : u8,
+    registers : [u8 ; 8],
+    irq_latch : u8,
+    irq_counter : u8,
+    irq_reload : bool,
+    irq_enabled : bool,
+    irq_pending : bool,
+}
+
+impl Mmc3 {
+    pub fn new(cartridge : Cartridge) -> Mmc3 {
+        let mut mapper = Mmc3{
+            cartridge,
+            select : 0,
+            registers : [0, 2, 4, 5, 6, 7, 0, 1],
+            irq_latch : 0,
+            irq_counter : 0,
+            irq_reload : false,
+            irq_enabled : false,
+            irq_pending : false,
+        };
+        mapper.update();
+        mapper
+    }
+
+    fn update(&mut self){
+        let cart = &mut self.cartridge;
+        let r = self.registers.map(|register| register as usize);
+        let second_last = cart.last_prg_bank(PRG_WINDOW).saturating_sub(1);
+        let last = cart.last_prg_bank(PRG_WINDOW);
+
+        // PRG mode 1 swaps $8000 and $C000
+        let (low, high) = if self.select & 0x40 == 0 { (r[6], second_last) } else { (second_last, r[6]) };
+        cart.set_prg_8k(0, low);
+        cart.set_prg_8k(1, r[7]);
+        cart.set_prg_8k(2, high);
+        cart.set_prg_8k(3, last);
+
+        // CHR inversion swaps the 2 KiB and the 1 KiB halves
+        let invert = if self.select & 0x80 == 0 { 0 } else { 4 };
+        cart.set_chr_1k(invert, r[0] & !1);
+        cart.set_chr_1k(invert + 1, r[0] | 1);
+        cart.set_chr_1k(invert + 2, r[1] & !1);
+        cart.set_chr_1k(invert + 3, r[1] | 1);
+        for i in 0..4 {
+            cart.set_chr_1k((4 - invert) + i, r[2 + i]);
+        }
+    }
+}
+
+impl Mapper for Mmc3 {
+    fn cartridge(&self) -> &Cartridge { &self.cartridge }
+    fn cartridge_mut(&mut self) -> &mut Cartridge { &mut self.cartridge }
+
+    fn write(&mut self, address : u16, data : u8){
+        let even = address & 0x01 == 0;
+        match address {
+            0x6000..=0x7FFF => self.cartridge.write_prg_ram(address, data),
+            0x8000..=0x9FFF if even => {
+                self.select = data;
+                self.update();
+            }
+            0x8000..=0x9FFF => {
+                self.registers[(self.select & 0x07) as usize] = data;
+                self.update();
+            }
+            0xA000..=0xBFFF if even => {
+                let mirroring = if data & 0x01 == 0 { Mirroring::Vertical } else { Mirroring::Horizontal };
+                self.cartridge.set_mirroring(mirroring);
+            }
+            0xA000..=0xBFFF => self.cartridge.set_prg_ram_access(data & 0x80 != 0, data & 0x40 == 0),
+            0xC000..=0xDFFF if even => self.irq_latch = data,
+            0xC000..=0xDFFF => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            0xE000..=0xFFFF if even => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            }
+            0xE000..=0xFFFF => self.irq_enabled = true,
+            _ => (),
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn scanline(&mut self){
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+use crate::ines::{Format, Header};
+
+// Cartridge whose 8 KiB PRG banks and 1 KiB CHR banks are filled with their
+// own bank number
+fn rom(mapper : u16, prg_16k : usize, chr_8k : usize) -> Rom {
+    let header = Header{
+        format : Format::INes,
+        prg_rom_size : prg_16k * 2 * PRG_WINDOW,
+        chr_rom_size : chr_8k * 8 * CHR_WINDOW,
+        mapper,
+        submapper : 0,
+        mirroring : Mirroring::Horizontal,
+        battery : false,
+        trainer : false,
+        prg_ram_size : PRG_WINDOW,
+        prg_nvram_size : 0,
+        chr_ram_size : if chr_8k == 0 { CHR_RAM_SIZE } else { 0 },
+        chr_nvram_size : 0,
+    };
+    let prg_rom = (0..header.prg_rom_size).map(|i| (i / PRG_WINDOW) as u8).collect();
+    let chr_rom = (0..header.chr_rom_size).map(|i| (i / CHR_WINDOW) as u8).collect();
+    Rom{ header, trainer : None, prg_rom, chr_rom }
+}
+
+// Bank visible in each PRG window
+fn prg(mapper : &dyn Mapper) -> [u8 ; 4] {
+    [0x8000, 0xA000, 0xC000, 0xE000].map(|address| mapper.read(address).unwrap())
+}
+
+fn chr(mapper : &dyn Mapper) -> [u8 ; 8] {
+    [0, 1, 2, 3, 4, 5, 6, 7].map(|window| mapper.cartridge().read_chr(window * 0x400))
+}
+
+#[test]
+fn test_nrom_and_prg_ram(){
+    let mut mapper = from_rom(rom(0, 1, 1)).unwrap();
+    assert_eq!(prg(mapper.as_ref()), [0, 1, 0, 1]);
+    mapper.write(0x8000, 0x05);
+    assert_eq!(prg(mapper.as_ref()), [0, 1, 0, 1]);
+
+    mapper.write(0x6123, 0x42);
+    assert_eq!(mapper.read(0x6123), Some(0x42));
+    assert_eq!(mapper.read(0x5000), None);
+    assert!(matches!(from_rom(rom(9, 1, 1)), Err(Error::UnsupportedMapper(9))));
+}
+
+#[test]
+fn test_uxrom_and_cnrom(){
+    let mut mapper = from_rom(rom(2, 8, 0)).unwrap();
+    assert_eq!(prg(mapper.as_ref()), [0, 1, 14, 15]);
+    mapper.write(0x8000, 3);
+    assert_eq!(prg(mapper.as_ref()), [6, 7, 14, 15]);
+    // CHR RAM
+    mapper.cartridge_mut().write_chr(0x1234, 0x99);
+    assert_eq!(mapper.cartridge().read_chr(0x1234), 0x99);
+
+    let mut mapper = from_rom(rom(3, 2, 4)).unwrap();
+    mapper.write(0xFFFF, 2);
+    assert_eq!(chr(mapper.as_ref()), [16, 17, 18, 19, 20, 21, 22, 23]);
+    mapper.cartridge_mut().write_chr(0x0000, 0x99);
+    assert_eq!(mapper.cartridge().read_chr(0x0000), 16);
+}
+
+// Loads an MMC1 register through the serial port
+fn mmc1_write(mapper : &mut dyn Mapper, address : u16, value : u8){
+    for bit in 0..5 {
+        mapper.write(address, (value >> bit) & 0x01);
+    }
+}
+
+#[test]
+fn test_mmc1(){
+    let mut mapper = from_rom(rom(1, 8, 4)).unwrap();
+    assert_eq!(prg(mapper.as_ref()), [0, 1, 14, 15]);
+
+    mmc1_write(mapper.as_mut(), 0xE000, 5);
+    assert_eq!(prg(mapper.as_ref()), [10, 11, 14, 15]);
+
+    // fixed first bank, 4 KiB CHR mode, vertical mirroring
+    mmc1_write(mapper.as_mut(), 0x8000, 0x1A);
+    assert_eq!(prg(mapper.as_ref()), [0, 1, 10, 11]);
+    assert_eq!(mapper.cartridge().mirroring(), Mirroring::Vertical);
+    mmc1_write(mapper.as_mut(), 0xA000, 3);
+    mmc1_write(mapper.as_mut(), 0xC000, 6);
+    assert_eq!(chr(mapper.as_ref()), [12, 13, 14, 15, 24, 25, 26, 27]);
+
+    // 32 KiB mode ignores the low bit of the bank
+    mmc1_write(mapper.as_mut(), 0x8000, 0x00);
+    assert_eq!(prg(mapper.as_ref()), [8, 9, 10, 11]);
+    assert_eq!(mapper.cartridge().mirroring(), Mirroring::SingleScreenLower);
+
+    // a reset in the middle of a load discards the bits so far
+    mapper.write(0xE000, 1);
+    mapper.write(0xE000, 0x80);
+    mmc1_write(mapper.as_mut(), 0xE000, 2);
+    assert_eq!(prg(mapper.as_ref()), [4, 5, 14, 15]);
+}
+
+#[test]
+fn test_mmc1_ignores_consecutive_writes(){
+    let mut mapper = from_rom(rom(1, 8, 4)).unwrap();
+    // each bit is written like by INC, the old value and then the new one
+    // on the next cycle. Only the first write counts.
+    for bit in 0..5 {
+        let old = (5 >> bit) & 0x01;
+        for data in [old, old ^ 0x01] {
+            mapper.write(0xE000, data);
+            mapper.cycle(BusCycle{ direction : Direction::Write, address : 0xE000, data, sync : false });
+        }
+        mapper.cycle(BusCycle{ direction : Direction::Read, address : 0x0200, data : 0xEE, sync : true });
+    }
+    assert_eq!(prg(mapper.as_ref()), [10, 11, 14, 15]);
+}
+
+#[test]
+fn test_mmc3_banks(){
+    let mut mapper = from_rom(rom(4, 8, 32)).unwrap();
+    for (register, bank) in [(0, 10), (1, 21), (2, 40), (3, 41), (4, 42), (5, 43), (6, 7), (7, 9)] {
+        mapper.write(0x8000, register);
+        mapper.write(0x8001, bank);
+    }
+    assert_eq!(prg(mapper.as_ref()), [7, 9, 14, 15]);
+    assert_eq!(chr(mapper.as_ref()), [10, 11, 20, 21, 40, 41, 42, 43]);
+
+    mapper.write(0x8000, 0xC0);
+    assert_eq!(prg(mapper.as_ref()), [14, 9, 7, 15]);
+    assert_eq!(chr(mapper.as_ref()), [40, 41, 42, 43, 10, 11, 20, 21]);
+
+    mapper.write(0xA000, 1);
+    assert_eq!(mapper.cartridge().mirroring(), Mirroring::Horizontal);
+
+    // write protected PRG RAM keeps its contents
+    mapper.write(0x6000, 0x11);
+    mapper.write(0xA001, 0xC0);
+    mapper.write(0x6000, 0x22);
+    assert_eq!(mapper.read(0x6000), Some(0x11));
+}
+
+#[test]
+fn test_mmc3_irq_counter(){
+    let mut mapper = from_rom(rom(4, 2, 1)).unwrap();
+    mapper.write(0xC000, 2);
+    mapper.write(0xC001, 0);
+    mapper.write(0xE001, 0);
+
+    // reload to 2, then 1, then 0 asserts IRQ
+    mapper.scanline();
+    mapper.scanline();
+    assert!(!mapper.irq());
+    mapper.scanline();
+    assert!(mapper.irq());
+
+    // acknowledged by disabling, the counter keeps reloading
+    mapper.write(0xE000, 0);
+    assert!(!mapper.irq());
+    mapper.write(0xE001, 0);
+    for _ in 0..2 {
+        mapper.scanline();
+    }
+    assert!(!mapper.irq());
+    mapper.scanline();
+    assert!(mapper.irq());
+}
@@ -0,0 +1,537 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::asm::Assembler;
+use crate::bus::Bus;
+use crate::cpu::{InstructionMnemonic, CPU};
+use crate::disasm::Disassembler;
+use crate::error::{Error, Result};
+use crate::loader::{self, Image};
+
+#[cfg(test)]
+#[path="./monitor_test.rs"]
+mod monitor_test;
+
+// Cycles g and n run before giving control back, unless changed with
+// set_cycle_limit
+const CYCLE_LIMIT : u64 = 10_000_000;
+
+// Lines of 16 bytes shown by m without an end address
+const DUMP_LINES : u16 = 8;
+
+// Instructions shown by d without an end address
+const DISASM_LINES : usize = 10;
+
+const HELP : &str = "\
+r [reg=value ...]      show or change the registers: pc a x y sp p, flags n v d i z c
+s [count]              step instructions
+n [count]              step, running subroutines called with JSR until they return
+g [addr]               continue until a breakpoint, a halt or the cycle limit
+b [addr]               set a breakpoint, or list them
+del [addr]             delete a breakpoint, or all of them
+m [start [end]]        dump memory
+> addr byte...         write bytes
+f start end byte...    fill memory with a pattern
+d [start [end]]        disassemble
+a addr instruction     assemble an instruction
+l file [addr]          load a program, a flat binary at addr
+sv file start end      save memory in the format given by the file extension
+reset                  reset the CPU
+history                list the commands entered, !n or !! runs one again
+x                      exit
+
+Addresses and values are hexadecimal with an optional $, or the symbols of
+a loaded source file, counts are decimal. An empty line repeats s, n, m
+and d.
+";
+
+// Why the CPU stopped running
+enum Stop {
+    Done,
+    Breakpoint,
+    Halted,
+    Limit(u64),
+}
+
+// Interactive machine language monitor in the style of the VICE monitor.
+// Commands are read line by line from any reader, so it can be driven from
+// a terminal or a script.
+pub struct Monitor<B : Bus> {
+    cpu : CPU<B>,
+    breakpoints : BTreeSet<u16>,
+    symbols : BTreeMap<String, u16>,
+    history : Vec<String>,
+    // Command an empty line repeats
+    repeat : Option<String>,
+    // Where m and d continue, d starts at PC after the CPU ran
+    dump_next : u16,
+    disasm_next : Option<u16>,
+    cycle_limit : u64,
+}
+
+impl<B : Bus> Monitor<B> {
+    pub fn new(cpu : CPU<B>) -> Monitor<B> {
+        Monitor{
+            cpu,
+            breakpoints : BTreeSet::new(),
+            symbols : BTreeMap::new(),
+            history : Vec::new(),
+            repeat : None,
+            dump_next : 0,
+            disasm_next : None,
+            cycle_limit : CYCLE_LIMIT,
+        }
+    }
+
+    pub fn cpu(&self) -> &CPU<B> {
+        &self.cpu
+    }
+
+    pub fn cpu_mut(&mut self) -> &mut CPU<B> {
+        &mut self.cpu
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = u16> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    pub fn add_breakpoint(&mut self, address : u16){
+        self.breakpoints.insert(address);
+    }
+
+    // Cycles g and n run at most before they stop, so a program stuck in a
+    // loop gives control back
+    pub fn set_cycle_limit(&mut self, cycles : u64){
+        self.cycle_limit = cycles;
+    }
+
+    // Loads a program into memory, see loader::load_file and Image::load.
+    // The labels of assembler source become symbols that commands accept as
+    // addresses.
+    pub fn load<P : AsRef<Path>>(&mut self, path : P, base : Option<u16>) -> Result<Image> {
+        let path = path.as_ref();
+        let image = match loader::extension(path).as_deref() {
+            Some("s" | "asm") => {
+                let program = Assembler::new(self.cpu.variant()).assemble_file(path)?;
+                self.symbols.extend(program.symbols);
+                program.image
+            }
+            _ => loader::load_file(path, base)?,
+        };
+        image.load(&mut self.cpu)?;
+        Ok(image)
+    }
+
+    // Reads commands until x or the end of the input, writing a prompt with
+    // the PC before each one
+    pub fn run<R : BufRead, W : Write>(&mut self, input : R, mut output : W) -> io::Result<()> {
+        let mut lines = input.lines();
+        loop {
+            write!(output, "(${:04X}) ", self.cpu.pc())?;
+            output.flush()?;
+            let Some(line) = lines.next() else {
+                writeln!(output)?;
+                return Ok(());
+            };
+            match self.command(&line?) {
+                Some(text) => output.write_all(text.as_bytes())?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    // Runs one command line and returns its output, or None if it is the
+    // exit command. Errors are reported in the output after a "?".
+    pub fn command(&mut self, line : &str) -> Option<String> {
+        let mut line = line.trim().to_string();
+        if line.starts_with('!') {
+            let recalled = match &line[1..] {
+                "!" => self.history.last(),
+                number => number.parse::<usize>().ok().and_then(|n| self.history.get(n.wrapping_sub(1))),
+            };
+            match recalled {
+                Some(recalled) => line = recalled.clone(),
+                None => return Some(format!("? no command {} in the history\n", line)),
+            }
+        }
+        if line.is_empty() {
+            match self.repeat.clone() {
+                Some(repeat) => line = repeat,
+                None => return Some(String::new()),
+            }
+        } else {
+            self.history.push(line.clone());
+        }
+
+        let (word, args) = match line.split_once(char::is_whitespace) {
+            Some((word, args)) => (word, args.trim()),
+            None => (line.as_str(), ""),
+        };
+        // memory writes are often typed without a space, e.g. ">0200 a9"
+        let (word, args) = match word.strip_prefix('>') {
+            Some(rest) if !rest.is_empty() => (">", line[1..].trim()),
+            _ => (word, args),
+        };
+        let word = word.to_ascii_lowercase();
+        // steps repeat with their count, dumps continue where they ended
+        self.repeat = match word.as_str() {
+            "s" | "n" => Some(line.clone()),
+            "m" | "d" => Some(word.clone()),
+            _ => None,
+        };
+
+        let mut out = String::new();
+        let result = match word.as_str() {
+            "x" | "q" | "exit" | "quit" => return None,
+            "r" => self.registers(args, &mut out),
+            "s" => self.step(args, false, &mut out),
+            "n" => self.step(args, true, &mut out),
+            "g" => self.go(args, &mut out),
+            "b" => self.breakpoint(args, &mut out),
+            "del" => self.delete(args, &mut out),
+            "m" => self.dump(args, &mut out),
+            ">" => self.write(args),
+            "f" => self.fill(args),
+            "d" => self.disassemble(args, &mut out),
+            "a" => self.assemble(args, &mut out),
+            "l" => self.load_command(args, &mut out),
+            "sv" => self.save(args, &mut out),
+            "reset" => {
+                self.cpu.reset();
+                self.disasm_next = None;
+                self.status(&mut out);
+                Ok(())
+            }
+            "history" => {
+                for (i, entry) in self.history.iter().enumerate() {
+                    writeln!(out, "{:>4}  {}", i + 1, entry).unwrap();
+                }
+                Ok(())
+            }
+            "h" | "help" | "?" => {
+                out.push_str(HELP);
+                Ok(())
+            }
+            _ => Err(format!("unknown command {}, h lists them", word)),
+        };
+        if let Err(message) = result {
+            writeln!(out, "? {}", message).unwrap();
+        }
+        Some(out)
+    }
+
+    fn address(&self, text : &str) -> std::result::Result<u16, String> {
+        if let Some(&address) = self.symbols.get(text) {
+            return Ok(address);
+        }
+        let digits = text.strip_prefix('$').unwrap_or(text);
+        u16::from_str_radix(digits, 16).map_err(|_| format!("invalid address {}", text))
+    }
+
+    fn byte(text : &str) -> std::result::Result<u8, String> {
+        let digits = text.strip_prefix('$').unwrap_or(text);
+        u8::from_str_radix(digits, 16).map_err(|_| format!("invalid byte {}", text))
+    }
+
+    fn count(text : Option<&str>) -> std::result::Result<u32, String> {
+        match text {
+            None => Ok(1),
+            Some(text) => text.parse().map_err(|_| format!("invalid count {}", text)),
+        }
+    }
+
+    fn disassembler(&self) -> Disassembler {
+        Disassembler::new(self.cpu.variant())
+    }
+
+    // Disassembly of the instruction at address and the address after it
+    fn listing(&self, address : u16) -> (String, u16) {
+        let disasm = self.disassembler();
+        let length = self.cpu.read_mem(address)
+            .map(|opcode| disasm.instruction(opcode).length.max(1) as usize)
+            .unwrap_or(1);
+        let bytes : Vec<u8> = (0..length)
+            .map_while(|i| address.checked_add(i as u16).and_then(|addr| self.cpu.read_mem(addr).ok()))
+            .collect();
+        match disasm.decode(&bytes, address) {
+            Some(line) => (line.to_string(), line.next()),
+            None => (format!("{:04X}  --", address), address.wrapping_add(1)),
+        }
+    }
+
+    // Register line and the next instruction, e.g.
+    // "PC=0200 A=00 X=00 Y=00 SP=FD P=24 nv-bdIzc cycles=7"
+    fn status(&self, out : &mut String){
+        let p = self.cpu.p();
+        let flags : String = "NV-BDIZC".chars()
+            .enumerate()
+            .map(|(i, flag)| if p & (0x80 >> i) != 0 || flag == '-' { flag } else { flag.to_ascii_lowercase() })
+            .collect();
+        writeln!(out, "PC={:04X} A={:02X} X={:02X} Y={:02X} SP={:02X} P={:02X} {} cycles={}",
+            self.cpu.pc(), self.cpu.a(), self.cpu.x(), self.cpu.y(), self.cpu.sp(), p, flags, self.cpu.cycles()).unwrap();
+        writeln!(out, "{}", self.listing(self.cpu.pc()).0).unwrap();
+    }
+
+    fn registers(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        for assignment in args.split_whitespace() {
+            let (name, value) = assignment.split_once('=').ok_or_else(|| format!("expected register=value, not {}", assignment))?;
+            let flag = || match value {
+                "0" => Ok(false),
+                "1" => Ok(true),
+                _ => Err(format!("flag {} is 0 or 1", name)),
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "pc" => {
+                    self.cpu.set_pc(self.address(value)?);
+                    self.disasm_next = None;
+                }
+                "a" => self.cpu.set_a(Self::byte(value)?),
+                "x" => self.cpu.set_x(Self::byte(value)?),
+                "y" => self.cpu.set_y(Self::byte(value)?),
+                "sp" => self.cpu.set_sp(Self::byte(value)?),
+                "p" => self.cpu.set_p(Self::byte(value)?),
+                "n" => self.cpu.set_negative(flag()?),
+                "v" => self.cpu.set_overflow(flag()?),
+                "d" => self.cpu.set_decimal(flag()?),
+                "i" => self.cpu.set_interrupt_disable(flag()?),
+                "z" => self.cpu.set_zero(flag()?),
+                "c" => self.cpu.set_carry(flag()?),
+                _ => return Err(format!("unknown register {}", name)),
+            }
+        }
+        self.status(out);
+        Ok(())
+    }
+
+    // Runs until stop returns true, a breakpoint is hit, the CPU halts or
+    // the cycle limit is reached
+    fn run_until(&mut self, stop : impl Fn(&CPU<B>) -> bool) -> Stop {
+        let mut elapsed = 0;
+        loop {
+            if self.cpu.halted() {
+                return Stop::Halted;
+            }
+            if elapsed >= self.cycle_limit {
+                return Stop::Limit(elapsed);
+            }
+            elapsed += self.cpu.step().max(1) as u64;
+            if stop(&self.cpu) {
+                return Stop::Done;
+            }
+            if self.breakpoints.contains(&self.cpu.pc()) {
+                return Stop::Breakpoint;
+            }
+        }
+    }
+
+    fn report(&self, stop : Stop, out : &mut String){
+        match stop {
+            Stop::Done => {}
+            Stop::Breakpoint => writeln!(out, "break at ${:04X}", self.cpu.pc()).unwrap(),
+            Stop::Halted => writeln!(out, "CPU halted at ${:04X}", self.cpu.pc()).unwrap(),
+            Stop::Limit(cycles) => writeln!(out, "stopped after {} cycles", cycles).unwrap(),
+        }
+    }
+
+    // s and n. Each instruction is listed before it runs, the registers are
+    // shown at the end.
+    fn step(&mut self, args : &str, over : bool, out : &mut String) -> std::result::Result<(), String> {
+        let count = Self::count(args.split_whitespace().next())?;
+        self.disasm_next = None;
+        for _ in 0..count {
+            if self.cpu.halted() {
+                self.report(Stop::Halted, out);
+                break;
+            }
+            let pc = self.cpu.pc();
+            writeln!(out, "{}", self.listing(pc).0).unwrap();
+            let call = self.cpu.read_mem(pc)
+                .is_ok_and(|opcode| self.disassembler().instruction(opcode).mnem == InstructionMnemonic::InstrJSR);
+            if over && call {
+                let (ret, sp) = (pc.wrapping_add(3), self.cpu.sp());
+                // the SP check keeps recursive calls from stopping early
+                let stop = self.run_until(|cpu| cpu.pc() == ret && cpu.sp() >= sp);
+                if !matches!(stop, Stop::Done) {
+                    self.report(stop, out);
+                    break;
+                }
+            } else {
+                self.cpu.step();
+            }
+        }
+        self.status(out);
+        Ok(())
+    }
+
+    fn go(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        if !args.is_empty() {
+            self.cpu.set_pc(self.address(args)?);
+        }
+        self.disasm_next = None;
+        let stop = self.run_until(|_| false);
+        self.report(stop, out);
+        self.status(out);
+        Ok(())
+    }
+
+    fn breakpoint(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        if args.is_empty() {
+            if self.breakpoints.is_empty() {
+                writeln!(out, "no breakpoints").unwrap();
+            }
+            for address in &self.breakpoints {
+                writeln!(out, "${:04X}", address).unwrap();
+            }
+            return Ok(());
+        }
+        let address = self.address(args)?;
+        self.breakpoints.insert(address);
+        writeln!(out, "breakpoint at ${:04X}", address).unwrap();
+        Ok(())
+    }
+
+    fn delete(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        if args.is_empty() {
+            self.breakpoints.clear();
+            writeln!(out, "all breakpoints deleted").unwrap();
+            return Ok(());
+        }
+        let address = self.address(args)?;
+        if !self.breakpoints.remove(&address) {
+            return Err(format!("no breakpoint at ${:04X}", address));
+        }
+        Ok(())
+    }
+
+    fn bounds(&self, start : &str, end : &str) -> std::result::Result<(u16, u16), String> {
+        let (start, end) = (self.address(start)?, self.address(end)?);
+        if end < start {
+            return Err(String::from("the end is before the start"));
+        }
+        Ok((start, end))
+    }
+
+    // Start and end of an optional "start [end]" range
+    fn range(&self, args : &str) -> std::result::Result<(Option<u16>, Option<u16>), String> {
+        let mut args = args.split_whitespace();
+        match (args.next(), args.next()) {
+            (Some(start), Some(end)) => self.bounds(start, end).map(|(start, end)| (Some(start), Some(end))),
+            (Some(start), None) => Ok((Some(self.address(start)?), None)),
+            _ => Ok((None, None)),
+        }
+    }
+
+    // Lines of 16 bytes followed by their characters, unmapped bytes are
+    // shown as --
+    fn dump(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        let (start, end) = self.range(args)?;
+        let start = start.unwrap_or(self.dump_next);
+        let end = end.unwrap_or(start.saturating_add(DUMP_LINES * 16 - 1));
+        let mut address = start as u32;
+        while address <= end as u32 {
+            let line_end = (address + 15).min(end as u32);
+            let bytes : Vec<Option<u8>> = (address..=line_end).map(|addr| self.cpu.read_mem(addr as u16).ok()).collect();
+            let hex : Vec<String> = bytes.iter()
+                .map(|byte| byte.map_or(String::from("--"), |byte| format!("{:02X}", byte)))
+                .collect();
+            let text : String = bytes.iter()
+                .map(|byte| byte.filter(|byte| (0x20..0x7F).contains(byte)).map_or('.', char::from))
+                .collect();
+            writeln!(out, "{:04X}  {:<47}  {}", address, hex.join(" "), text).unwrap();
+            address = line_end + 1;
+        }
+        self.dump_next = (end as u32 + 1) as u16;
+        Ok(())
+    }
+
+    fn write(&mut self, args : &str) -> std::result::Result<(), String> {
+        let mut args = args.split_whitespace();
+        let address = self.address(args.next().ok_or("expected an address")?)?;
+        let bytes = args.map(Self::byte).collect::<std::result::Result<Vec<u8>, String>>()?;
+        self.cpu.mount_mem(address, &bytes).map_err(|e| e.to_string())
+    }
+
+    fn fill(&mut self, args : &str) -> std::result::Result<(), String> {
+        let parts : Vec<&str> = args.split_whitespace().collect();
+        let [start, end, ref pattern @ ..] = parts[..] else {
+            return Err(String::from("expected start, end and bytes"));
+        };
+        let (start, end) = self.bounds(start, end)?;
+        let pattern = pattern.iter().map(|byte| Self::byte(byte)).collect::<std::result::Result<Vec<u8>, String>>()?;
+        if pattern.is_empty() {
+            return Err(String::from("expected bytes to fill with"));
+        }
+        let data : Vec<u8> = pattern.iter().copied().cycle().take(end as usize - start as usize + 1).collect();
+        self.cpu.mount_mem(start, &data).map_err(|e| e.to_string())
+    }
+
+    fn disassemble(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        let (start, end) = self.range(args)?;
+        let mut address = start.or(self.disasm_next).unwrap_or(self.cpu.pc());
+        let mut lines = 0;
+        loop {
+            let (text, next) = self.listing(address);
+            let marker = if self.breakpoints.contains(&address) { "*" } else { " " };
+            writeln!(out, "{}{}", marker, text).unwrap();
+            lines += 1;
+            let done = match end {
+                Some(end) => next > end || next <= address,
+                None => lines == DISASM_LINES,
+            };
+            address = next;
+            if done {
+                break;
+            }
+        }
+        self.disasm_next = Some(address);
+        Ok(())
+    }
+
+    // Assembles one line at an address, e.g. "a 0200 LDA #$01"
+    fn assemble(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        let (address, instruction) = args.split_once(char::is_whitespace).ok_or("expected an address and an instruction")?;
+        let address = self.address(address)?;
+        let mut asm = Assembler::new(self.cpu.variant());
+        for (name, &value) in &self.symbols {
+            asm.define(name, value as i64);
+        }
+        let program = asm.assemble(&format!(".org ${:04X}\n{}", address, instruction)).map_err(|e| match e {
+            Error::Assembly { message, .. } => message,
+            e => e.to_string(),
+        })?;
+        program.image.mount(&mut self.cpu).map_err(|e| e.to_string())?;
+        writeln!(out, "{}", self.listing(address).0).unwrap();
+        Ok(())
+    }
+
+    fn load_command(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        let mut args = args.split_whitespace();
+        let file = args.next().ok_or("expected a file name")?;
+        let base = args.next().map(|arg| self.address(arg)).transpose()?;
+        let image = self.load(file, base).map_err(|e| e.to_string())?;
+        for segment in image.segments.iter().filter(|segment| !segment.data.is_empty()) {
+            writeln!(out, "loaded ${:04X}-${:04X}", segment.address, segment.address as usize + segment.data.len() - 1).unwrap();
+        }
+        if image.entry.is_some() {
+            image.start(&mut self.cpu).map_err(|e| e.to_string())?;
+            self.disasm_next = None;
+            writeln!(out, "PC set to ${:04X}", self.cpu.pc()).unwrap();
+        }
+        Ok(())
+    }
+
+    fn save(&mut self, args : &str, out : &mut String) -> std::result::Result<(), String> {
+        let parts : Vec<&str> = args.split_whitespace().collect();
+        let [file, start, end] = parts[..] else {
+            return Err(String::from("expected a file name, start and end"));
+        };
+        let (start, end) = self.bounds(start, end)?;
+        let len = end as usize - start as usize + 1;
+        let image = Image::from_memory(&self.cpu, start, len).map_err(|e| e.to_string())?;
+        loader::save_file(file, &image).map_err(|e| e.to_string())?;
+        writeln!(out, "saved ${:04X}-${:04X}", start, end).unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::io::Cursor;
+
+use super::*;
+use crate::bus::FlatRam;
+
+// $0200: LDX #0, JSR $0207, INX, JAM; $0207: LDA #$42, RTS
+fn monitor() -> Monitor<FlatRam> {
+    let mut cpu : CPU = CPU::new();
+    cpu.mount_mem(0x0200, &[0xA2, 0x00, 0x20, 0x07, 0x02, 0xE8, 0x02, 0xA9, 0x42, 0x60]).unwrap();
+    cpu.set_pc(0x0200);
+    cpu.set_sp(0xFF);
+    Monitor::new(cpu)
+}
+
+fn command(monitor : &mut Monitor<FlatRam>, line : &str) -> String {
+    monitor.command(line).unwrap()
+}
+
+#[test]
+fn test_step_and_registers(){
+    let mut monitor = monitor();
+    assert_eq!(command(&mut monitor, "r"), "\
+PC=0200 A=00 X=00 Y=00 SP=FF P=20 nv-bdizc cycles=0
+0200  A2 00     LDX #$00
+");
+    assert_eq!(command(&mut monitor, "s"), "\
+0200  A2 00     LDX #$00
+PC=0202 A=00 X=00 Y=00 SP=FF P=22 nv-bdiZc cycles=2
+0202  20 07 02  JSR $0207
+");
+    // steps into the subroutine, then over it
+    assert!(command(&mut monitor, "s").starts_with("0202  20 07 02  JSR $0207\nPC=0207 "));
+    assert!(command(&mut monitor, "r pc=0202 sp=ff a=01 c=1").starts_with("PC=0202 A=01 X=00 Y=00 SP=FF P=23 "));
+    assert_eq!(command(&mut monitor, "r q=1"), "? unknown register q\n");
+    assert!(command(&mut monitor, "n").starts_with("0202  20 07 02  JSR $0207\nPC=0205 A=42 "));
+
+    // the empty line repeats the step
+    assert!(command(&mut monitor, "").starts_with("0205  E8        INX\nPC=0206 A=42 X=01 "));
+    assert!(command(&mut monitor, "g").starts_with("CPU halted at $0207\n"));
+    assert!(command(&mut monitor, "s").starts_with("CPU halted at $0207\n"));
+}
+
+#[test]
+fn test_breakpoints(){
+    let mut monitor = monitor();
+    assert_eq!(command(&mut monitor, "b"), "no breakpoints\n");
+    assert_eq!(command(&mut monitor, "b $0209"), "breakpoint at $0209\n");
+    assert!(command(&mut monitor, "g").starts_with("break at $0209\nPC=0209 A=42 "));
+    assert!(command(&mut monitor, "d 0207 0209").ends_with(" 0207  A9 42     LDA #$42\n*0209  60        RTS\n"));
+    assert_eq!(command(&mut monitor, "del 0208"), "? no breakpoint at $0208\n");
+    assert_eq!(command(&mut monitor, "del 0209"), "");
+    assert_eq!(monitor.breakpoints().count(), 0);
+
+    // a subroutine that never returns stops at the cycle limit
+    let mut monitor = self::monitor();
+    monitor.cpu_mut().write_mem(0x0209, 0xEA).unwrap();
+    monitor.cpu_mut().mount_mem(0x020A, &[0x4C, 0x0A, 0x02]).unwrap();
+    monitor.set_cycle_limit(100);
+    command(&mut monitor, "s");
+    assert!(command(&mut monitor, "n").contains("stopped after 100 cycles\n"));
+}
+
+#[test]
+fn test_memory_commands(){
+    let mut monitor = monitor();
+    assert_eq!(command(&mut monitor, ">0300 48 69"), "");
+    assert_eq!(command(&mut monitor, "f 0302 0307 aa 55"), "");
+    assert_eq!(command(&mut monitor, "m 0300 0307"), "0300  48 69 AA 55 AA 55 AA 55                          Hi.U.U.U\n");
+    assert_eq!(command(&mut monitor, "m").lines().next(), Some("0308  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  ................"));
+    assert_eq!(command(&mut monitor, "m 0400 0300"), "? the end is before the start\n");
+
+    assert_eq!(command(&mut monitor, "a 0300 STA $0400,X"), "0300  9D 00 04  STA $0400,X\n");
+    assert_eq!(command(&mut monitor, "a 0300 BNE $1000"), "? branch target is 3326 bytes away\n");
+    let listing = command(&mut monitor, "d 0200");
+    assert_eq!(listing.lines().count(), 10);
+    assert!(command(&mut monitor, "d").starts_with(" 020E  00        BRK\n"));
+}
+
+#[test]
+fn test_run_load_and_save(){
+    let dir = std::env::temp_dir().join(format!("rs6502-monitor-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("prog.s");
+    fs::write(&source, "        .org $1000\nstart:  LDA #1\n        .byte $02\n").unwrap();
+    let saved = dir.join("saved.hex");
+
+    let mut monitor = monitor();
+    let script = format!("l {}\nb start\nsv {} 0200 0209\nfoo\n!1\n\nhistory\nx\nr\n", source.display(), saved.display());
+    let mut output = Vec::new();
+    monitor.run(Cursor::new(script), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("\
+($0200) loaded $1000-$1002
+($0200) breakpoint at $1000
+($0200) saved $0200-$0209
+($0200) ? unknown command foo, h lists them
+($0200) loaded $1000-$1002
+($0200) ($0200)    1  l {0}
+   2  b start
+   3  sv {1} 0200 0209
+   4  foo
+   5  l {0}
+   6  history
+($0200) ", source.display(), saved.display()));
+
+    let image = loader::load_file(&saved, None).unwrap();
+    assert_eq!(image.segments[0].address, 0x0200);
+    assert_eq!(image.segments[0].data[..3], [0xA2, 0x00, 0x20]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
@@ -0,0 +1,83 @@
+use crate::bus::{Bus, BusCycle};
+use crate::error::Result;
+use crate::ines::Rom;
+use crate::mapper::{self, Mapper};
+
+#[cfg(test)]
+#[path="./nes_test.rs"]
+mod nes_test;
+
+const RAM_SIZE : usize = 0x0800;
+
+// CPU side memory map of the NES: 2 KiB of RAM mirrored up to $1FFF and the
+// cartridge from $4020 up. The PPU and APU registers in between are not
+// emulated and read as 0.
+pub struct NesBus {
+    ram : [u8 ; RAM_SIZE],
+    mapper : Box<dyn Mapper>,
+}
+
+impl NesBus {
+    pub fn new(mapper : Box<dyn Mapper>) -> NesBus {
+        NesBus{ ram : [0 ; RAM_SIZE], mapper }
+    }
+
+    pub fn from_rom(rom : Rom) -> Result<NesBus> {
+        Ok(NesBus::new(mapper::from_rom(rom)?))
+    }
+
+    pub fn mapper(&self) -> &dyn Mapper {
+        self.mapper.as_ref()
+    }
+
+    pub fn mapper_mut(&mut self) -> &mut dyn Mapper {
+        self.mapper.as_mut()
+    }
+}
+
+impl Bus for NesBus {
+    fn read(&mut self, address : u16) -> u8 {
+        self.peek(address)
+    }
+
+    fn write(&mut self, address : u16, data : u8){
+        match address {
+            0x0000..=0x1FFF => self.ram[address as usize % RAM_SIZE] = data,
+            0x4020..=0xFFFF => self.mapper.write(address, data),
+            _ => (),
+        }
+    }
+
+    fn peek(&self, address : u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.ram[address as usize % RAM_SIZE],
+            0x4020..=0xFFFF => self.mapper.read(address).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    // Loading into ROM patches the bank currently mapped instead of
+    // switching banks
+    fn poke(&mut self, address : u16, data : u8){
+        match address {
+            0x4020..=0xFFFF => self.mapper.cartridge_mut().poke_prg(address, data),
+            _ => self.write(address, data),
+        }
+    }
+
+    fn is_mapped(&self, address : u16) -> bool {
+        match address {
+            0x0000..=0x1FFF => true,
+            0x4020..=0xFFFF => self.mapper.cartridge().is_mapped(address),
+            _ => false,
+        }
+    }
+
+    fn cycle(&mut self, cycle : BusCycle){
+        self.mapper.cycle(cycle);
+    }
+
+    fn irq(&self) -> bool {
+        self.mapper.irq()
+    }
+}
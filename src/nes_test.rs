@@ -0,0 +1,54 @@
+use super::*;
+use crate::cpu::CPU;
+use crate::ines::{Format, Header, Mirroring};
+
+// UxROM cartridge with 4 x 16 KiB banks, each starting with its number
+fn uxrom() -> Rom {
+    let header = Header{
+        format : Format::INes,
+        prg_rom_size : 4 * 0x4000,
+        chr_rom_size : 0,
+        mapper : 2,
+        submapper : 0,
+        mirroring : Mirroring::Vertical,
+        battery : false,
+        trainer : false,
+        prg_ram_size : 0,
+        prg_nvram_size : 0,
+        chr_ram_size : 0x2000,
+        chr_nvram_size : 0,
+    };
+    let mut prg_rom = vec![0 ; header.prg_rom_size];
+    for bank in 0..4 {
+        prg_rom[bank * 0x4000] = bank as u8;
+    }
+    Rom{ header, trainer : None, prg_rom, chr_rom : vec![] }
+}
+
+#[test]
+fn test_memory_map(){
+    let mut cpu = CPU::with_bus(NesBus::from_rom(uxrom()).unwrap());
+    cpu.write_mem(0x0001, 0x42).unwrap();
+    assert_eq!(cpu.read_mem(0x1801).unwrap(), 0x42);
+    assert!(cpu.read_mem(0x2002).is_err());
+    assert!(cpu.read_mem(0x6000).is_err());
+    assert!(matches!(NesBus::from_rom(Rom{ header : Header{ mapper : 99, ..uxrom().header }, ..uxrom() }),
+        Err(crate::Error::UnsupportedMapper(99))));
+}
+
+#[test]
+fn test_program_switches_banks(){
+    let mut cpu = CPU::with_bus(NesBus::from_rom(uxrom()).unwrap());
+    // loading into the fixed bank does not switch banks
+    // $C010: LDA #$02; STA $8000; LDX $8000; JAM
+    cpu.mount_mem(0xC010, &[0xA9, 0x02, 0x8D, 0x00, 0x80, 0xAE, 0x00, 0x80, 0x02]).unwrap();
+    cpu.mount_mem(crate::RESET_VEC, &[0x10, 0xC0]).unwrap();
+    assert_eq!(cpu.read_mem(0x8000).unwrap(), 0);
+    assert_eq!(cpu.read_mem(0xC000).unwrap(), 3);
+
+    cpu.reset();
+    cpu.run(100);
+    assert!(cpu.halted());
+    assert_eq!(cpu.x(), 2);
+    assert_eq!(cpu.read_mem(0x8000).unwrap(), 2);
+}
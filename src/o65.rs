@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::Image;
+
+#[cfg(test)]
+#[path="./o65_test.rs"]
+mod o65_test;
+
+// Header magic: a non-C64 marker, "o65" and version 0
+const MAGIC : [u8 ; 6] = [0x01, 0x00, b'o', b'6', b'5', 0x00];
+
+// Mode bits
+const MODE_65816 : u16 = 0x8000;
+const MODE_PAGED : u16 = 0x4000;
+const MODE_SIZE_32 : u16 = 0x2000;
+const MODE_BSS_ZERO : u16 = 0x0200;
+
+// Segment IDs used by relocation entries and exported symbols
+const SEG_UNDEFINED : u8 = 0;
+const SEG_ABSOLUTE : u8 = 1;
+const SEG_TEXT : u8 = 2;
+const SEG_DATA : u8 = 3;
+const SEG_BSS : u8 = 4;
+const SEG_ZERO : u8 = 5;
+
+// Relocation entry types
+const RELOC_WORD : u8 = 0x80;
+const RELOC_HIGH : u8 = 0x40;
+const RELOC_LOW : u8 = 0x20;
+
+// Addresses of the four segments of an o65 file
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Bases {
+    pub text : u16,
+    pub data : u16,
+    pub bss : u16,
+    pub zero : u16,
+}
+
+impl Bases {
+    fn of(&self, segment : u8) -> u16 {
+        match segment {
+            SEG_TEXT => self.text,
+            SEG_DATA => self.data,
+            SEG_BSS => self.bss,
+            SEG_ZERO => self.zero,
+            _ => 0,
+        }
+    }
+}
+
+// The parts of an o65 header needed to load the file
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub mode : u16,
+    pub bases : Bases,
+    pub text_len : u16,
+    pub data_len : u16,
+    pub bss_len : u16,
+    pub zero_len : u16,
+    pub stack_len : u16,
+}
+
+// Reads the header of an o65 file, to find out the segment sizes before
+// choosing where to relocate it
+pub fn header(bytes : &[u8]) -> Result<Header> {
+    Reader{ bytes, pos : 0 }.header()
+}
+
+// Parses an o65 executable and relocates it to the given bases, or leaves
+// it at the bases in its header when none are given. The text and data
+// segments are loaded, the bss only if the file asks for it to be zeroed.
+// The entry is the start of the text segment. Files importing undefined
+// symbols cannot be loaded since there is no linker to resolve them.
+pub fn parse(bytes : &[u8], bases : Option<Bases>) -> Result<Image> {
+    let mut reader = Reader{ bytes, pos : 0 };
+    let header = reader.header()?;
+    let bases = bases.unwrap_or(header.bases);
+
+    let mut text = reader.take(header.text_len as usize)?.to_vec();
+    let mut data = reader.take(header.data_len as usize)?.to_vec();
+    let undefined = reader.word()?;
+    for _ in 0..undefined {
+        reader.string()?;
+    }
+
+    let relocation = Relocation{ from : header.bases, to : bases, paged : header.mode & MODE_PAGED != 0 };
+    relocation.apply(&mut reader, &mut text)?;
+    relocation.apply(&mut reader, &mut data)?;
+
+    let mut image = Image::new();
+    image.add_segment(bases.text, &text)?;
+    image.add_segment(bases.data, &data)?;
+    if header.mode & MODE_BSS_ZERO != 0 {
+        image.add_segment(bases.bss, &vec![0 ; header.bss_len as usize])?;
+    }
+    image.segments.retain(|segment| !segment.data.is_empty());
+    image.entry = Some(bases.text);
+    Ok(image)
+}
+
+pub fn load<P : AsRef<Path>>(path : P, bases : Option<Bases>) -> Result<Image> {
+    parse(&fs::read(path)?, bases)
+}
+
+struct Reader<'a> {
+    bytes : &'a [u8],
+    pos : usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len : usize) -> Result<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos + len)
+            .ok_or(Error::InvalidFormat("o65 file is truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn word(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn string(&mut self) -> Result<&'a [u8]> {
+        let len = self.bytes[self.pos.min(self.bytes.len())..].iter()
+            .position(|&byte| byte == 0)
+            .ok_or(Error::InvalidFormat("o65 file is truncated"))?;
+        let string = self.take(len)?;
+        self.pos += 1;
+        Ok(string)
+    }
+
+    fn header(&mut self) -> Result<Header> {
+        if self.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(Error::InvalidFormat("not an o65 file"));
+        }
+        let mode = self.word()?;
+        if mode & (MODE_SIZE_32 | MODE_65816) != 0 {
+            return Err(Error::InvalidFormat("only 16 bit 6502 o65 files are supported"));
+        }
+
+        let text = self.word()?;
+        let text_len = self.word()?;
+        let data = self.word()?;
+        let data_len = self.word()?;
+        let bss = self.word()?;
+        let bss_len = self.word()?;
+        let zero = self.word()?;
+        let zero_len = self.word()?;
+        let stack_len = self.word()?;
+
+        // header options are only informational
+        loop {
+            let len = self.byte()? as usize;
+            if len == 0 {
+                break;
+            }
+            self.take(len.saturating_sub(1))?;
+        }
+
+        Ok(Header{
+            mode,
+            bases : Bases{ text, data, bss, zero },
+            text_len, data_len, bss_len, zero_len, stack_len,
+        })
+    }
+}
+
+struct Relocation {
+    from : Bases,
+    to : Bases,
+    paged : bool,
+}
+
+impl Relocation {
+    fn offset(&self, segment : u8) -> u16 {
+        self.to.of(segment).wrapping_sub(self.from.of(segment))
+    }
+
+    // Applies one relocation table to a segment. Each entry gives the
+    // distance to the previous relocated byte, starting one before the
+    // segment, with 255 meaning "254 further and keep counting".
+    fn apply(&self, reader : &mut Reader, segment : &mut [u8]) -> Result<()> {
+        let mut pos : isize = -1;
+        loop {
+            let distance = reader.byte()?;
+            match distance {
+                0 => return Ok(()),
+                255 => {
+                    pos += 254;
+                    continue;
+                }
+                _ => pos += distance as isize,
+            }
+
+            let kind = reader.byte()?;
+            let target = kind & 0x0F;
+            if target == SEG_UNDEFINED {
+                return Err(Error::InvalidFormat("o65 file references undefined symbols"));
+            }
+            let offset = if target == SEG_ABSOLUTE { 0 } else { self.offset(target) };
+            let pos = pos as usize;
+            let out_of_range = Error::InvalidFormat("o65 relocation outside of its segment");
+
+            match kind & 0xE0 {
+                RELOC_WORD => {
+                    let bytes = segment.get_mut(pos..pos + 2).ok_or(out_of_range)?;
+                    let value = u16::from_le_bytes([bytes[0], bytes[1]]).wrapping_add(offset);
+                    bytes.copy_from_slice(&value.to_le_bytes());
+                }
+                RELOC_HIGH => {
+                    let low = if self.paged { 0 } else { reader.byte()? };
+                    let byte = segment.get_mut(pos).ok_or(out_of_range)?;
+                    let value = u16::from_le_bytes([low, *byte]).wrapping_add(offset);
+                    *byte = (value >> 8) as u8;
+                }
+                RELOC_LOW => {
+                    let byte = segment.get_mut(pos).ok_or(out_of_range)?;
+                    *byte = byte.wrapping_add(offset as u8);
+                }
+                _ => return Err(Error::InvalidFormat("unsupported o65 relocation type")),
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+use crate::loader::Segment;
+
+// text at $1000: LDA $2000; LDA #<$2000; LDX #>$2000; RTS
+// data at $2000: .word $1000
+fn sample(mode : u16) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    for word in [mode, 0x1000, 8, 0x2000, 2, 0x3000, 4, 0x0010, 0, 0] {
+        bytes.extend_from_slice(&u16::to_le_bytes(word));
+    }
+    // one header option, then the end of the options
+    bytes.extend_from_slice(&[4, 0, b'x', b'y', 0]);
+    bytes.extend_from_slice(&[0xAD, 0x00, 0x20, 0xA9, 0x00, 0xA2, 0x20, 0x60]);
+    bytes.extend_from_slice(&[0x00, 0x10]);
+    // no undefined symbols
+    bytes.extend_from_slice(&[0, 0]);
+    // text: word at 1, low byte at 4, high byte at 6, all in data
+    bytes.extend_from_slice(&[2, RELOC_WORD | SEG_DATA, 3, RELOC_LOW | SEG_DATA, 2, RELOC_HIGH | SEG_DATA, 0x00, 0]);
+    // data: word at 0 in text
+    bytes.extend_from_slice(&[1, RELOC_WORD | SEG_TEXT, 0]);
+    // no exported symbols
+    bytes.extend_from_slice(&[0, 0]);
+    bytes
+}
+
+#[test]
+fn test_header(){
+    let header = header(&sample(0)).unwrap();
+    assert_eq!(header.bases, Bases{ text : 0x1000, data : 0x2000, bss : 0x3000, zero : 0x0010 });
+    assert_eq!((header.text_len, header.data_len, header.bss_len), (8, 2, 4));
+}
+
+#[test]
+fn test_load_in_place_and_relocated(){
+    let image = parse(&sample(0), None).unwrap();
+    assert_eq!(image.segments, vec![
+        Segment{ address : 0x1000, data : vec![0xAD, 0x00, 0x20, 0xA9, 0x00, 0xA2, 0x20, 0x60], init : None },
+        Segment{ address : 0x2000, data : vec![0x00, 0x10], init : None },
+    ]);
+    assert_eq!(image.entry, Some(0x1000));
+
+    let bases = Bases{ text : 0xC000, data : 0xC180, bss : 0xC200, zero : 0x0020 };
+    let image = parse(&sample(MODE_BSS_ZERO), Some(bases)).unwrap();
+    assert_eq!(image.segments, vec![
+        Segment{ address : 0xC000, data : vec![0xAD, 0x80, 0xC1, 0xA9, 0x80, 0xA2, 0xC1, 0x60], init : None },
+        Segment{ address : 0xC180, data : vec![0x00, 0xC0], init : None },
+        Segment{ address : 0xC200, data : vec![0 ; 4], init : None },
+    ]);
+    assert_eq!(image.entry, Some(0xC000));
+}
+
+#[test]
+fn test_rejected_files(){
+    assert!(matches!(parse(b"o65", None), Err(Error::InvalidFormat(_))));
+    assert!(matches!(parse(&sample(MODE_SIZE_32), None), Err(Error::InvalidFormat(_))));
+
+    let mut truncated = sample(0);
+    truncated.truncate(30);
+    assert!(matches!(parse(&truncated, None), Err(Error::InvalidFormat(_))));
+
+    // the first text relocation refers to undefined symbol 0
+    let mut undefined = sample(0);
+    let pos = undefined.len() - 12;
+    undefined[pos] = RELOC_WORD | SEG_UNDEFINED;
+    assert!(matches!(parse(&undefined, None), Err(Error::InvalidFormat(_))));
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::Image;
+
+#[cfg(test)]
+#[path="./prg_test.rs"]
+mod prg_test;
+
+// BASIC token of the SYS statement
+const TOKEN_SYS : u8 = 0x9E;
+
+// Parses a Commodore program file: a little endian load address followed by
+// the data. Programs loaded at the start of BASIC memory ($0801 on the C64,
+// $0401 on the PET) usually begin with a `SYS nnnn` stub, in which case the
+// entry is the address of the SYS. Otherwise it is the load address.
+pub fn parse(bytes : &[u8]) -> Result<Image> {
+    if bytes.len() < 2 {
+        return Err(Error::InvalidFormat("PRG file has no load address"));
+    }
+    let address = u16::from_le_bytes([bytes[0], bytes[1]]);
+    let data = &bytes[2..];
+
+    let mut image = Image::from_binary(data, address)?;
+    image.entry = Some(sys_address(data).unwrap_or(address));
+    Ok(image)
+}
+
+pub fn load<P : AsRef<Path>>(path : P) -> Result<Image> {
+    parse(&fs::read(path)?)
+}
+
+// Writes the image as a PRG file. The format holds a single block, so the
+// image must consist of exactly one segment.
+pub fn to_bytes(image : &Image) -> Result<Vec<u8>> {
+    let [segment] = image.segments.as_slice() else {
+        return Err(Error::InvalidFormat("a PRG file holds exactly one segment"));
+    };
+    let mut bytes = segment.address.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&segment.data);
+    Ok(bytes)
+}
+
+pub fn save<P : AsRef<Path>>(path : P, image : &Image) -> Result<()> {
+    fs::write(path, to_bytes(image)?)?;
+    Ok(())
+}
+
+// Target of a SYS statement in the first BASIC line: after the link and the
+// line number, optional spaces or a parenthesis and then decimal digits
+fn sys_address(data : &[u8]) -> Option<u16> {
+    let line = data.get(4..)?;
+    let start = line.iter().position(|&byte| byte != b' ')?;
+    if line[start] != TOKEN_SYS {
+        return None;
+    }
+    let digits : String = line[start + 1..].iter()
+        .skip_while(|&&byte| byte == b' ' || byte == b'(')
+        .take_while(|byte| byte.is_ascii_digit())
+        .map(|&byte| byte as char)
+        .collect();
+    digits.parse().ok()
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn test_basic_stub_entry(){
+    // $0801: 10 SYS 2061, then the machine code at $080D
+    let bytes = [
+        0x01, 0x08,
+        0x0B, 0x08, 0x0A, 0x00, TOKEN_SYS, b'2', b'0', b'6', b'1', 0x00, 0x00, 0x00,
+        0xEE, 0x20, 0xD0, 0x60,
+    ];
+    let image = parse(&bytes).unwrap();
+    assert_eq!(image.segments[0].address, 0x0801);
+    assert_eq!(image.segments[0].data.len(), 16);
+    assert_eq!(image.entry, Some(2061));
+    assert_eq!(to_bytes(&image).unwrap(), bytes);
+}
+
+#[test]
+fn test_machine_code_entry(){
+    let image = parse(&[0x00, 0xC0, 0xEE, 0x20, 0xD0, 0x60]).unwrap();
+    assert_eq!(image.segments[0].address, 0xC000);
+    assert_eq!(image.entry, Some(0xC000));
+
+    assert!(matches!(parse(&[0x01]), Err(Error::InvalidFormat(_))));
+    assert_eq!(parse(&[0x00, 0xFF, 0x00, 0x00, 0x00]).unwrap().entry, Some(0xFF00));
+    assert!(matches!(parse(&[0xFF, 0xFF, 0x00, 0x00]), Err(Error::DataDoesNotFit { addr : 0xFFFF, len : 2 })));
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::cpu::{AddressingMode, InstructionMnemonic};
+use crate::disasm::{Disassembler, Line};
+use crate::error::{Error, Result};
+use crate::status::StatusFlags;
+use crate::variant::CpuVariant;
+
+#[cfg(test)]
+#[path="./source_test.rs"]
+mod source_test;
+
+// Data bytes per .byte line
+const BYTES_PER_LINE : usize = 8;
+
+// Vectors traced as entry points, named after the interrupt they serve
+const VECTORS : [(u16, &str) ; 3] = [(crate::RESET_VEC, "reset"), (crate::NMI_VEC, "nmi"), (crate::IRQ_VEC, "irq")];
+
+// Mode of the 65C816 at an instruction, followed through REP, SEP and XCE
+// on the way there. The other variants always run with 8 bit registers.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+struct Widths {
+    native : bool,
+    m16 : bool,
+    x16 : bool,
+}
+
+impl Widths {
+    // Entry points other than the vectors are taken to be native mode
+    // code, which starts with 8 bit registers until a REP
+    fn entry(variant : CpuVariant) -> Widths {
+        Widths{ native : variant == CpuVariant::Wdc65C816, ..Widths::default() }
+    }
+
+    // Widths after REP (set false) or SEP (set true) with the bits
+    fn change(self, bits : u8, set : bool) -> Widths {
+        if !self.native {
+            return self;
+        }
+        Widths{
+            m16 : if bits & StatusFlags::MEMORY != 0 { !set } else { self.m16 },
+            x16 : if bits & StatusFlags::INDEX != 0 { !set } else { self.x16 },
+            ..self
+        }
+    }
+}
+
+// Recursive descent disassembly of a memory image. Code is traced from the
+// interrupt vectors found in the image and from the given entry points,
+// following jumps, calls and both sides of every branch. Everything the
+// trace does not reach is data. The result can be written out as ca65
+// source that assembles back to the same bytes.
+//
+// 65C816 code is decoded with the register widths set by REP and SEP. The
+// vectors start in emulation mode, XCE only changes the mode right after a
+// CLC or SEC and the trace stops at an XCE with an unknown carry.
+pub struct Analysis {
+    origin : u16,
+    bytes : Vec<u8>,
+    variant : CpuVariant,
+    // Instructions by offset into the image
+    code : BTreeMap<usize, Line>,
+    // Register widths each instruction was decoded with
+    widths : BTreeMap<usize, Widths>,
+    // Offset of the instruction covering each byte, if any
+    owner : Vec<Option<usize>>,
+    labels : BTreeMap<usize, String>,
+}
+
+impl Analysis {
+    pub fn new(bytes : &[u8], origin : u16, variant : CpuVariant, entries : &[u16]) -> Result<Analysis> {
+        if origin as usize + bytes.len() > crate::MAX_MEM {
+            return Err(Error::DataDoesNotFit { addr : origin as u32, len : bytes.len() });
+        }
+        let mut analysis = Analysis{
+            origin,
+            bytes : bytes.to_vec(),
+            variant,
+            code : BTreeMap::new(),
+            widths : BTreeMap::new(),
+            owner : vec![None ; bytes.len()],
+            labels : BTreeMap::new(),
+        };
+
+        let mut names = BTreeMap::new();
+        let mut starts = Vec::new();
+        let mut pending = Vec::new();
+        for (vector, name) in VECTORS {
+            if let Some(target) = analysis.word_at(vector) {
+                names.entry(target).or_insert(name);
+                starts.push(target);
+                pending.push((target, Widths::default()));
+            }
+        }
+        starts.extend_from_slice(entries);
+        pending.extend(entries.iter().map(|&entry| (entry, Widths::entry(variant))));
+
+        analysis.trace(pending);
+        analysis.assign_labels(&starts, &names);
+        Ok(analysis)
+    }
+
+    fn offset(&self, address : u16) -> Option<usize> {
+        let offset = address.wrapping_sub(self.origin) as usize;
+        (address >= self.origin && offset < self.bytes.len()).then_some(offset)
+    }
+
+    fn word_at(&self, address : u16) -> Option<u16> {
+        let lo = self.bytes[self.offset(address)?];
+        let hi = self.bytes[self.offset(address.checked_add(1)?)?];
+        Some(u16::from_le_bytes([lo, hi]))
+    }
+
+    // Whether the byte at address belongs to an instruction
+    pub fn is_code(&self, address : u16) -> bool {
+        self.offset(address).is_some_and(|offset| self.owner[offset].is_some())
+    }
+
+    pub fn label(&self, address : u16) -> Option<&str> {
+        self.labels.get(&self.offset(address)?).map(String::as_str)
+    }
+
+    // Instructions found by the trace, in address order
+    pub fn instructions(&self) -> impl Iterator<Item = &Line> {
+        self.code.values()
+    }
+
+    // Whether ca65 assembles the instruction back to the same bytes. The
+    // signature byte of the 65C816 BRK, COP and WDM is kept as data.
+    fn is_reassembled(&self, line : &Line) -> bool {
+        let signature = self.variant == CpuVariant::Wdc65C816 && line.instr.is_some_and(|instr| matches!(instr.mnem,
+            InstructionMnemonic::InstrBRK | InstructionMnemonic::InstrCOP | InstructionMnemonic::InstrWDM));
+        line.is_documented() && !signature && !line.branch_wraps()
+    }
+
+    fn trace(&mut self, mut pending : Vec<(u16, Widths)>){
+        let disasm = Disassembler::new(self.variant);
+
+        while let Some((start, mut widths)) = pending.pop() {
+            let mut address = start;
+            // carry set by the previous instruction, for XCE
+            let mut carry = None;
+            while let Some(offset) = self.offset(address) {
+                if self.code.contains_key(&offset) {
+                    break;
+                }
+                let line = disasm.with_widths(widths.m16, widths.x16).decode(&self.bytes[offset..], address).unwrap();
+                // stop at anything that cannot be reassembled, or that
+                // overlaps an instruction found before
+                if !self.is_reassembled(&line)
+                    || (offset..offset + line.len()).any(|i| self.owner[i].is_some()) {
+                    break;
+                }
+                for i in offset..offset + line.len() {
+                    self.owner[i] = Some(offset);
+                }
+
+                let instr = line.instr.unwrap();
+                self.widths.insert(offset, widths);
+                pending.extend(line.branch_target().map(|target| (target, widths)));
+                let falls_through = match instr.mnem {
+                    InstructionMnemonic::InstrJSR => {
+                        pending.extend(line.operand_address().map(|target| (target, widths)));
+                        true
+                    }
+                    InstructionMnemonic::InstrJMP => {
+                        if matches!(instr.mode, AddressingMode::AddrModeABS) {
+                            pending.extend(line.operand_address().map(|target| (target, widths)));
+                        }
+                        false
+                    }
+                    InstructionMnemonic::InstrREP | InstructionMnemonic::InstrSEP => {
+                        widths = widths.change(line.bytes[1], instr.mnem == InstructionMnemonic::InstrSEP);
+                        true
+                    }
+                    // emulation mode forces 8 bit registers
+                    InstructionMnemonic::InstrXCE => {
+                        if let Some(carry) = carry {
+                            widths = if carry { Widths::default() } else { Widths{ native : true, ..widths } };
+                        }
+                        carry.is_some()
+                    }
+                    InstructionMnemonic::InstrBRA | InstructionMnemonic::InstrRTS | InstructionMnemonic::InstrRTI
+                        | InstructionMnemonic::InstrBRK | InstructionMnemonic::InstrSTP | InstructionMnemonic::InstrBRL
+                        | InstructionMnemonic::InstrJML | InstructionMnemonic::InstrRTL => false,
+                    _ => true,
+                };
+                carry = match instr.mnem {
+                    InstructionMnemonic::InstrCLC => Some(false),
+                    InstructionMnemonic::InstrSEC => Some(true),
+                    _ => None,
+                };
+                let end = offset + line.len();
+                address = line.next();
+                self.code.insert(offset, line);
+                if !falls_through || end >= self.bytes.len() {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Addresses in the image an instruction refers to. Zero page operands
+    // are left as numbers, a label would make ca65 pick an absolute mode.
+    fn references(line : &Line) -> Option<u16> {
+        match line.instr?.mode {
+            AddressingMode::AddrModeRelative | AddressingMode::AddrModeZPRelative => line.branch_target(),
+            AddressingMode::AddrModeABS | AddressingMode::AddrModeABSX | AddressingMode::AddrModeABSY
+                | AddressingMode::AddrModeIndirect | AddressingMode::AddrModeABSIndX => line.operand_address(),
+            _ => None,
+        }
+    }
+
+    fn assign_labels(&mut self, starts : &[u16], names : &BTreeMap<u16, &str>){
+        let mut targets : Vec<u16> = self.code.values().filter_map(Analysis::references).collect();
+        targets.extend_from_slice(starts);
+        if let Some(offset) = self.offset(0xFFFA) {
+            if (offset..offset + 6).all(|i| i < self.bytes.len() && self.owner[i].is_none()) {
+                targets.extend((0..3).filter_map(|i| self.word_at(0xFFFA + i * 2)));
+            }
+        }
+
+        for target in targets {
+            let Some(offset) = self.offset(target) else {
+                continue;
+            };
+            // references into the middle of an instruction are written
+            // relative to its start
+            let start = self.owner[offset].unwrap_or(offset);
+            let address = self.origin + start as u16;
+            let name = match names.get(&address) {
+                Some(name) => name.to_string(),
+                None => format!("L_{:04X}", address),
+            };
+            self.labels.entry(start).or_insert(name);
+        }
+    }
+
+    // Operand naming a location, as a label when there is one. Absolute
+    // operands in the zero page need the a: prefix to keep their size.
+    fn reference(&self, address : u16, absolute : bool) -> String {
+        let label = self.offset(address).and_then(|offset| {
+            let start = self.owner[offset].unwrap_or(offset);
+            let name = self.labels.get(&start)?;
+            Some(if start == offset { name.clone() } else { format!("{}+{}", name, offset - start) })
+        });
+        let text = label.unwrap_or_else(|| format!("${:04X}", address));
+        if absolute && address < 0x100 { format!("a:{}", text) } else { text }
+    }
+
+    fn operand(&self, line : &Line) -> String {
+        let instr = line.instr.unwrap();
+        match instr.mode {
+            AddressingMode::AddrModeRelative => self.reference(line.branch_target().unwrap(), false),
+            AddressingMode::AddrModeZPRelative =>
+                format!("${:02X}, {}", line.bytes[1], self.reference(line.branch_target().unwrap(), false)),
+            AddressingMode::AddrModeABS => self.reference(line.operand_address().unwrap(), true),
+            AddressingMode::AddrModeABSX => format!("{},X", self.reference(line.operand_address().unwrap(), true)),
+            AddressingMode::AddrModeABSY => format!("{},Y", self.reference(line.operand_address().unwrap(), true)),
+            AddressingMode::AddrModeIndirect => format!("({})", self.reference(line.operand_address().unwrap(), false)),
+            AddressingMode::AddrModeABSIndX => format!("({},X)", self.reference(line.operand_address().unwrap(), false)),
+            AddressingMode::AddrModeRelativeLong => self.reference(line.operand_address().unwrap(), false),
+            // ca65 takes long operands below $10000 as absolute
+            AddressingMode::AddrModeABSLong | AddressingMode::AddrModeABSLongX => format!("f:{}", line.operand()),
+            _ => line.operand(),
+        }
+    }
+
+    // Writes the image as ca65 source
+    pub fn to_source(&self) -> String {
+        let mut text = String::new();
+        let cpu = match self.variant {
+            CpuVariant::Wdc65C816 => "65816",
+            _ if self.variant.is_cmos() => "65C02",
+            _ => "6502",
+        };
+        let end = self.origin as usize + self.bytes.len();
+        writeln!(text, "; ${:04X}-${:04X}", self.origin, end.saturating_sub(1)).unwrap();
+        writeln!(text, "        .setcpu \"{}\"", cpu).unwrap();
+        writeln!(text, "        .org ${:04X}", self.origin).unwrap();
+
+        // ca65 starts out with 8 bit registers
+        let mut current = Widths::default();
+        let mut offset = 0;
+        while offset < self.bytes.len() {
+            if let Some(name) = self.labels.get(&offset) {
+                writeln!(text, "{}:", name).unwrap();
+            }
+
+            if let Some(line) = self.code.get(&offset) {
+                let widths = self.widths[&offset];
+                if widths.m16 != current.m16 {
+                    writeln!(text, "        {}", if widths.m16 { ".a16" } else { ".a8" }).unwrap();
+                }
+                if widths.x16 != current.x16 {
+                    writeln!(text, "        {}", if widths.x16 { ".i16" } else { ".i8" }).unwrap();
+                }
+                current = widths;
+                let operand = self.operand(line);
+                if operand.is_empty() {
+                    writeln!(text, "        {}", line.mnemonic()).unwrap();
+                } else {
+                    writeln!(text, "        {} {}", line.mnemonic(), operand).unwrap();
+                }
+                offset += line.len();
+            } else if self.is_vector_table(offset) {
+                let vectors : Vec<String> = (0..3)
+                    .map(|i| self.reference(self.word_at(0xFFFA + i * 2).unwrap(), false))
+                    .collect();
+                writeln!(text, "        .word {}", vectors.join(", ")).unwrap();
+                offset += 6;
+            } else {
+                // a run of data up to the next label, instruction or the
+                // vector table
+                let mut run = vec![self.bytes[offset]];
+                offset += 1;
+                while offset < self.bytes.len() && run.len() < BYTES_PER_LINE
+                    && !self.labels.contains_key(&offset) && self.owner[offset].is_none()
+                    && !self.is_vector_table(offset) {
+                    run.push(self.bytes[offset]);
+                    offset += 1;
+                }
+                let run : Vec<String> = run.iter().map(|byte| format!("${:02X}", byte)).collect();
+                writeln!(text, "        .byte {}", run.join(",")).unwrap();
+            }
+        }
+        text
+    }
+
+    // Whether the six bytes at offset are the interrupt vectors at the end
+    // of memory, all of them data and with no label after the first
+    fn is_vector_table(&self, offset : usize) -> bool {
+        self.origin as usize + offset == 0xFFFA
+            && offset + 6 == self.bytes.len()
+            && (offset..offset + 6).all(|i| self.owner[i].is_none())
+            && (offset + 1..offset + 6).all(|i| !self.labels.contains_key(&i))
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+// A small ROM at $FFE0 with its vectors
+const ROM : [u8 ; 32] = [
+    0xA2, 0xFF,             // FFE0 reset: LDX #$FF
+    0x9A,                   // FFE2 TXS
+    0x20, 0xEE, 0xFF,       // FFE3 JSR $FFEE
+    0xAD, 0xEC, 0xFF,       // FFE6 LDA $FFEC
+    0x4C, 0xE3, 0xFF,       // FFE9 JMP $FFE3
+    0x12, 0x34,             // FFEC table
+    0x8D, 0x11, 0x00,       // FFEE STA $0011 with an absolute operand
+    0xEE, 0xE7, 0xFF,       // FFF1 INC $FFE7, the operand of the LDA
+    0xF0, 0x02,             // FFF4 BEQ $FFF8
+    0x02, 0xFF,             // FFF6 JAM and a byte that is never reached
+    0x60,                   // FFF8 nmi: RTS
+    0xEA,                   // FFF9 unreached
+    0xF8, 0xFF, 0xE0, 0xFF, 0xF8, 0xFF,
+];
+
+#[test]
+fn test_trace_and_source(){
+    let analysis = Analysis::new(&ROM, 0xFFE0, CpuVariant::Nmos6502, &[]).unwrap();
+    assert!(analysis.is_code(0xFFE7));
+    assert!(!analysis.is_code(0xFFEC));
+    assert!(!analysis.is_code(0xFFF6));
+    assert!(!analysis.is_code(0xFFF9));
+    assert_eq!(analysis.label(0xFFF8), Some("nmi"));
+    assert_eq!(analysis.instructions().count(), 9);
+
+    assert_eq!(analysis.to_source(), "\
+; $FFE0-$FFFF
+        .setcpu \"6502\"
+        .org $FFE0
+reset:
+        LDX #$FF
+        TXS
+L_FFE3:
+        JSR L_FFEE
+L_FFE6:
+        LDA L_FFEC
+        JMP L_FFE3
+L_FFEC:
+        .byte $12,$34
+L_FFEE:
+        STA a:$0011
+        INC L_FFE6+1
+        BEQ nmi
+        .byte $02,$FF
+nmi:
+        RTS
+        .byte $EA
+        .word nmi, reset, nmi
+");
+}
+
+#[test]
+fn test_entry_points(){
+    // $0200: JMP ($0300); BRA $0200 on the 65C02
+    let code = [0x6C, 0x00, 0x03, 0x80, 0xFB, 0x00];
+    let analysis = Analysis::new(&code, 0x0200, CpuVariant::Cmos65C02, &[]).unwrap();
+    assert_eq!(analysis.instructions().count(), 0);
+
+    let analysis = Analysis::new(&code, 0x0200, CpuVariant::Cmos65C02, &[0x0200, 0x0203]).unwrap();
+    let source = analysis.to_source();
+    assert!(source.contains(".setcpu \"65C02\""));
+    assert!(source.ends_with("L_0200:\n        JMP ($0300)\nL_0203:\n        BRA L_0200\n        .byte $00\n"));
+
+    assert!(Analysis::new(&[0 ; 2], 0xFFFF, CpuVariant::Nmos6502, &[]).is_err());
+}
+
+#[test]
+fn test_wrapping_branch_stays_data(){
+    // $0000: LDA #1; BPL $FFD0; RTS. No assembler takes a branch through
+    // $FFFF/$0000, so it is written as data.
+    let code = [0xA9, 0x01, 0x10, 0xCC, 0x60];
+    let analysis = Analysis::new(&code, 0x0000, CpuVariant::Nmos6502, &[0x0000]).unwrap();
+    assert_eq!(analysis.instructions().count(), 1);
+
+    let source = analysis.to_source();
+    assert!(!source.contains("BPL"));
+    let program = crate::asm::Assembler::new(CpuVariant::Nmos6502).assemble(&source).unwrap();
+    assert_eq!(program.image, crate::loader::Image::from_binary(&code, 0x0000).unwrap());
+}
+
+#[test]
+fn test_65816_register_widths(){
+    let code = [
+        0xC2, 0x20,             // 8000 REP #$20
+        0xA9, 0x34, 0x12,       // 8002 LDA #$1234
+        0xA2, 0x56,             // 8005 LDX #$56
+        0xAF, 0x34, 0x12, 0x00, // 8007 LDA $001234
+        0xE2, 0x20,             // 800B SEP #$20
+        0xA9, 0x78,             // 800D LDA #$78
+        0x38, 0xFB,             // 800F SEC; XCE
+        0xC2, 0x30,             // 8011 REP #$30, no effect in emulation mode
+        0xA0, 0x9A,             // 8013 LDY #$9A
+        0xDB,                   // 8015 STP
+    ];
+    let analysis = Analysis::new(&code, 0x8000, CpuVariant::Wdc65C816, &[0x8000]).unwrap();
+    assert_eq!(analysis.instructions().count(), 11);
+    assert_eq!(analysis.to_source(), "\
+; $8000-$8015
+        .setcpu \"65816\"
+        .org $8000
+L_8000:
+        REP #$20
+        .a16
+        LDA #$1234
+        LDX #$56
+        LDA f:$001234
+        SEP #$20
+        .a8
+        LDA #$78
+        SEC
+        XCE
+        REP #$30
+        LDY #$9A
+        STP
+");
+
+    // an XCE with an unknown carry ends the trace, as does the signature
+    // byte of BRK
+    let code = [0xFB, 0xEA, 0x00, 0x00];
+    let analysis = Analysis::new(&code, 0x8000, CpuVariant::Wdc65C816, &[0x8000, 0x8002]).unwrap();
+    assert_eq!(analysis.instructions().count(), 1);
+}
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::{hex_bytes, Image};
+
+#[cfg(test)]
+#[path="./srec_test.rs"]
+mod srec_test;
+
+// Data bytes per record written by to_string
+const RECORD_SIZE : usize = 16;
+
+// Parses a Motorola S-record file (.s19, .s28 or .s37). The header record is
+// ignored, count records are checked against the number of data records and
+// the termination record sets the entry point.
+pub fn parse(text : &str) -> Result<Image> {
+    let mut image = Image::new();
+    let mut data_records : u32 = 0;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = |reason| Error::InvalidRecord { line : line_no, reason };
+
+        let kind = line.strip_prefix('S')
+            .and_then(|rest| rest.chars().next())
+            .and_then(|kind| kind.to_digit(10))
+            .ok_or(invalid("not an S-record"))?;
+        let bytes = hex_bytes(&line[2..]).ok_or(invalid("not an S-record"))?;
+        if bytes.len() < 3 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(invalid("record length mismatch"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xFF {
+            return Err(Error::BadChecksum { line : line_no });
+        }
+
+        let address_len = match kind {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            3 | 7 => 4,
+            _ => return Err(invalid("unknown record type")),
+        };
+        if bytes.len() < address_len + 2 {
+            return Err(invalid("record too short for its address"));
+        }
+        let address = bytes[1..=address_len].iter().fold(0u32, |value, byte| (value << 8) | *byte as u32);
+        let data = &bytes[address_len + 1..bytes.len() - 1];
+
+        match kind {
+            0 => (),
+            1..=3 => {
+                image.append(address, data)?;
+                data_records += 1;
+            }
+            5 | 6 => {
+                if address != data_records {
+                    return Err(invalid("record count mismatch"));
+                }
+            }
+            _ => {
+                image.entry = Some(u16::try_from(address).map_err(|_| invalid("start address out of range"))?);
+                break;
+            }
+        }
+    }
+    Ok(image)
+}
+
+// Writes the image as S19. A 64K image needs at most 4096 data records, so
+// the count always fits an S5 record. The termination record holds the
+// entry point, or 0 if there is none.
+pub fn to_string(image : &Image) -> String {
+    let mut text = String::new();
+    record(&mut text, 0, 0, &[]);
+    let mut data_records = 0;
+    for segment in &image.segments {
+        for (i, chunk) in segment.data.chunks(RECORD_SIZE).enumerate() {
+            let address = segment.address.wrapping_add((i * RECORD_SIZE) as u16);
+            record(&mut text, 1, address, chunk);
+            data_records += 1;
+        }
+    }
+    record(&mut text, 5, data_records, &[]);
+    record(&mut text, 9, image.entry.unwrap_or(0), &[]);
+    text
+}
+
+pub fn load<P : AsRef<Path>>(path : P) -> Result<Image> {
+    parse(&fs::read_to_string(path)?)
+}
+
+pub fn save<P : AsRef<Path>>(path : P, image : &Image) -> Result<()> {
+    fs::write(path, to_string(image))?;
+    Ok(())
+}
+
+fn record(text : &mut String, kind : u8, address : u16, data : &[u8]){
+    let mut bytes = vec![(data.len() + 3) as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(!sum);
+
+    write!(text, "S{}", kind).unwrap();
+    for byte in bytes {
+        write!(text, "{:02X}", byte).unwrap();
+    }
+    text.push('\n');
+}
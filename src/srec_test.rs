@@ -0,0 +1,42 @@
+use super::*;
+use crate::loader::Segment;
+
+#[test]
+fn test_parse_s19_and_s28(){
+    let text = "\
+S00600004844521B
+S1130000285F245F2212226A000424290008237C2A
+S11300100002000800082629001853812341001813
+S5030002FA
+S9030000FC
+";
+    let image = parse(text).unwrap();
+    assert_eq!(image.segments.len(), 1);
+    assert_eq!(image.segments[0].data.len(), 32);
+    assert_eq!(&image.segments[0].data[..4], &[0x28, 0x5F, 0x24, 0x5F]);
+    assert_eq!(image.entry, Some(0x0000));
+
+    let image = parse("S20600123412346D\nS804001234B5\n").unwrap();
+    assert_eq!(image.segments, vec![Segment{ address : 0x1234, data : vec![0x12, 0x34], init : None }]);
+    assert_eq!(image.entry, Some(0x1234));
+}
+
+#[test]
+fn test_parse_errors(){
+    assert!(matches!(parse("S1050000AABB94\n"), Err(Error::BadChecksum { line : 1 })));
+    assert!(matches!(parse("S1050000AABB95\nS5030002FA\n"), Err(Error::InvalidRecord { line : 2, .. })));
+    assert!(matches!(parse("S4030000FC\n"), Err(Error::InvalidRecord { line : 1, .. })));
+    assert!(matches!(parse("S2060100001234B2\n"), Err(Error::DataDoesNotFit { addr : 0x10000, len : 2 })));
+}
+
+#[test]
+fn test_round_trip_from_memory(){
+    let mut cpu : crate::CPU = crate::CPU::new();
+    cpu.mount_mem(0xE000, &[0xA9, 0x01, 0x8D, 0x00, 0x02, 0x4C, 0x00, 0xE0]).unwrap();
+    let mut image = Image::from_memory(&cpu, 0xE000, 40).unwrap();
+    image.entry = Some(0xE000);
+    let text = to_string(&image);
+    assert!(text.starts_with("S0030000FC\nS113E000A9018D00024C00E0"));
+    assert!(text.ends_with("S5030003F9\nS903E0001C\n"));
+    assert_eq!(parse(&text).unwrap(), image);
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+#[cfg(test)]
+#[path="./status_test.rs"]
+mod status_test;
+
+// Processor status register P: N V - B D I Z C
+//
+// Only N, V, D, I, Z and C are actual latches in the CPU. Bit 5 is not
+// connected and always reads as 1, and B only exists in the copies of P
+// pushed to the stack: it is set when pushed by PHP/BRK and clear when
+// pushed by IRQ/NMI.
+//
+// The 65C816 latches all eight bits in native mode, bit 5 is its M and bit
+// 4 its X flag. Such a status is created by from_bits_65816().
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct StatusFlags {
+    bits : u8,
+    latches : u8,
+}
+
+impl StatusFlags {
+    pub const NEGATIVE : u8 = 0x80;
+    pub const OVERFLOW : u8 = 0x40;
+    pub const UNUSED : u8 = 0x20;
+    pub const BREAK : u8 = 0x10;
+    pub const DECIMAL : u8 = 0x08;
+    pub const INTERRUPT : u8 = 0x04;
+    pub const ZERO : u8 = 0x02;
+    pub const CARRY : u8 = 0x01;
+
+    // 65C816 native mode flags in place of bit 5 and B
+    pub const MEMORY : u8 = 0x20;       // M, 8 bit A and memory
+    pub const INDEX : u8 = 0x10;        // X, 8 bit index registers
+
+    const LATCHES : u8 = !(StatusFlags::UNUSED | StatusFlags::BREAK);
+
+    // Status from a byte, e.g. pulled by PLP/RTI. B and bit 5 are ignored.
+    pub fn from_bits(p : u8) -> StatusFlags {
+        StatusFlags{ bits : p & StatusFlags::LATCHES, latches : StatusFlags::LATCHES }
+    }
+
+    // Status of a 65C816, all eight bits including M and X are kept
+    pub fn from_bits_65816(p : u8) -> StatusFlags {
+        StatusFlags{ bits : p, latches : 0xFF }
+    }
+
+    // P as seen by software, on the 6502 bit 5 reads as 1 and B as 0
+    pub fn bits(&self) -> u8 {
+        self.bits | (StatusFlags::UNUSED & !self.latches)
+    }
+
+    // P as pushed to the stack, on the 6502 with B set for PHP/BRK and clear
+    // for IRQ/NMI
+    pub fn pushed(&self, brk : bool) -> u8 {
+        if brk { self.bits() | (StatusFlags::BREAK & !self.latches) } else { self.bits() }
+    }
+
+    pub fn get(&self, flag : u8) -> bool {
+        self.bits & flag & self.latches != 0
+    }
+
+    pub fn set(&mut self, flag : u8, value : bool){
+        let flag = flag & self.latches;
+        if value { self.bits |= flag } else { self.bits &= !flag }
+    }
+
+    pub fn negative(&self) -> bool { self.get(StatusFlags::NEGATIVE) }
+    pub fn overflow(&self) -> bool { self.get(StatusFlags::OVERFLOW) }
+    pub fn decimal(&self) -> bool { self.get(StatusFlags::DECIMAL) }
+    pub fn interrupt_disable(&self) -> bool { self.get(StatusFlags::INTERRUPT) }
+    pub fn zero(&self) -> bool { self.get(StatusFlags::ZERO) }
+    pub fn carry(&self) -> bool { self.get(StatusFlags::CARRY) }
+
+    pub fn set_negative(&mut self, flag : bool){ self.set(StatusFlags::NEGATIVE, flag); }
+    pub fn set_overflow(&mut self, flag : bool){ self.set(StatusFlags::OVERFLOW, flag); }
+    pub fn set_decimal(&mut self, flag : bool){ self.set(StatusFlags::DECIMAL, flag); }
+    pub fn set_interrupt_disable(&mut self, flag : bool){ self.set(StatusFlags::INTERRUPT, flag); }
+    pub fn set_zero(&mut self, flag : bool){ self.set(StatusFlags::ZERO, flag); }
+    pub fn set_carry(&mut self, flag : bool){ self.set(StatusFlags::CARRY, flag); }
+}
+
+impl Default for StatusFlags {
+    fn default() -> Self {
+        StatusFlags::from_bits(0)
+    }
+}
+
+// Prints set flags in upper case, e.g. "Nv-bdIzC"
+impl fmt::Debug for StatusFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = ['C', 'Z', 'I', 'D', 'B', '-', 'V', 'N'];
+        let s : String = (0..8).rev().map(|bit| {
+            let name = names[bit];
+            if self.bits() & (1 << bit) != 0 { name } else { name.to_ascii_lowercase() }
+        }).collect();
+        f.write_str(&s)
+    }
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn test_bit5_and_break_semantics(){
+    let p = StatusFlags::from_bits(0xFF);
+    assert_eq!(p.bits(), 0xEF);
+    assert_eq!(p.pushed(true), 0xFF);
+    assert_eq!(p.pushed(false), 0xEF);
+    assert_eq!(StatusFlags::default().bits(), 0x20);
+    assert_eq!(StatusFlags::from_bits(0x30), StatusFlags::default());
+}
+
+#[test]
+fn test_flag_access(){
+    let mut p = StatusFlags::default();
+    p.set_carry(true);
+    p.set_negative(true);
+    p.set(StatusFlags::BREAK, true);
+    assert!(p.carry() && p.negative() && !p.zero());
+    assert!(!p.get(StatusFlags::BREAK));
+    assert_eq!(p.bits(), 0xA1);
+    assert_eq!(format!("{:?}", p), "Nv-bdizC");
+}
+
+#[test]
+fn test_65816_latches(){
+    let mut p = StatusFlags::from_bits_65816(0x30);
+    assert!(p.get(StatusFlags::MEMORY) && p.get(StatusFlags::INDEX));
+    p.set(StatusFlags::MEMORY, false);
+    assert_eq!(p.bits(), 0x10);
+    assert_eq!(p.pushed(true), 0x10);
+    assert_eq!(StatusFlags::from_bits_65816(0x00).bits(), 0x00);
+}
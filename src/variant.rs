@@ -0,0 +1,50 @@
+// The members of the 6502 family the CPU core can emulate. The variant
+// selects the opcode table and the quirks that differ between the chips.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CpuVariant {
+    // Original MOS 6502, including its undocumented opcodes and bugs
+    #[default]
+    Nmos6502,
+
+    // WDC/Rockwell 65C02. Undocumented opcodes are NOPs, JMP ($xxFF) is
+    // fixed, decimal mode sets N and Z from the result and the D flag is
+    // cleared on reset and interrupts.
+    Cmos65C02,
+
+    // NES CPU, an NMOS 6502 with the decimal mode disconnected
+    Ricoh2A03,
+
+    // C64 CPU, an NMOS 6502 with an I/O port at $00/$01
+    Mos6510,
+
+    // WDC 65C816, executed by CPU816. Its table decodes the 8 bit register
+    // lengths of emulation mode, the CPU core runs it as a 65C02.
+    Wdc65C816,
+}
+
+impl CpuVariant {
+    pub fn is_cmos(&self) -> bool {
+        matches!(self, CpuVariant::Cmos65C02 | CpuVariant::Wdc65C816)
+    }
+
+    // Whether the D flag switches ADC/SBC to BCD arithmetic
+    pub fn has_decimal_mode(&self) -> bool {
+        !matches!(self, CpuVariant::Ricoh2A03)
+    }
+
+    // Whether JMP ($xxFF) fetches the high byte of the target from $xx00
+    pub fn has_jmp_indirect_bug(&self) -> bool {
+        !self.is_cmos()
+    }
+
+    // Whether $0000/$0001 are the on-chip I/O port instead of memory
+    pub fn has_io_port(&self) -> bool {
+        matches!(self, CpuVariant::Mos6510)
+    }
+
+    // Whether the undocumented NMOS opcodes are decoded, otherwise they
+    // execute as NOPs
+    pub fn has_illegal_opcodes(&self) -> bool {
+        !self.is_cmos()
+    }
+}
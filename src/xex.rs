@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::Image;
+
+#[cfg(test)]
+#[path="./xex_test.rs"]
+mod xex_test;
+
+// Vectors the Atari DOS jumps through after and while loading a file
+pub const RUNAD : u16 = 0x02E0;
+pub const INITAD : u16 = 0x02E2;
+
+// Parses an Atari executable. The file is a list of segments, each a start
+// and an inclusive end address followed by the data, optionally preceded by
+// a $FFFF marker (required on the first one). A segment that sets INITAD
+// gets the address as its init routine, which Image::load calls before the
+// next segment is loaded. The last RUNAD written is the entry, falling back
+// to the start of the first segment like the DOS does.
+pub fn parse(bytes : &[u8]) -> Result<Image> {
+    if bytes.len() < 2 || bytes[0..2] != [0xFF, 0xFF] {
+        return Err(Error::InvalidFormat("XEX file does not start with $FFFF"));
+    }
+
+    let mut image = Image::new();
+    let mut run = None;
+    let mut pos = 2;
+    while pos < bytes.len() {
+        let mut start = word(bytes, pos)?;
+        if start == 0xFFFF {
+            pos += 2;
+            start = word(bytes, pos)?;
+        }
+        let end = word(bytes, pos + 2)?;
+        if end < start {
+            return Err(Error::InvalidFormat("XEX segment ends before its start"));
+        }
+        let len = (end - start) as usize + 1;
+        let data = bytes.get(pos + 4..pos + 4 + len)
+            .ok_or(Error::InvalidFormat("XEX segment is truncated"))?;
+        image.add_segment(start, data)?;
+        pos += 4 + len;
+
+        if let Some(address) = vector(start, data, RUNAD) {
+            run = Some(address);
+        }
+        if let Some(segment) = image.segments.last_mut() {
+            segment.init = vector(start, data, INITAD);
+        }
+    }
+
+    image.entry = run.or(image.segments.first().map(|segment| segment.address));
+    Ok(image)
+}
+
+pub fn load<P : AsRef<Path>>(path : P) -> Result<Image> {
+    parse(&fs::read(path)?)
+}
+
+// Writes the segments of the image as an Atari executable, with the entry
+// in a final RUNAD segment
+pub fn to_bytes(image : &Image) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFF];
+    let run = image.entry.map(|entry| (RUNAD, entry.to_le_bytes().to_vec()));
+    let segments = image.segments.iter()
+        .map(|segment| (segment.address, segment.data.clone()))
+        .chain(run);
+    for (address, data) in segments.filter(|(_, data)| !data.is_empty()) {
+        bytes.extend_from_slice(&address.to_le_bytes());
+        bytes.extend_from_slice(&(address + (data.len() - 1) as u16).to_le_bytes());
+        bytes.extend_from_slice(&data);
+    }
+    bytes
+}
+
+pub fn save<P : AsRef<Path>>(path : P, image : &Image) -> Result<()> {
+    fs::write(path, to_bytes(image))?;
+    Ok(())
+}
+
+fn word(bytes : &[u8], pos : usize) -> Result<u16> {
+    bytes.get(pos..pos + 2)
+        .map(|word| u16::from_le_bytes([word[0], word[1]]))
+        .ok_or(Error::InvalidFormat("XEX segment header is truncated"))
+}
+
+// Value a segment writes to the two byte vector, if it covers it entirely
+fn vector(start : u16, data : &[u8], vector : u16) -> Option<u16> {
+    let offset = vector.checked_sub(start)? as usize;
+    data.get(offset..offset + 2).map(|word| u16::from_le_bytes([word[0], word[1]]))
+}
@@ -0,0 +1,53 @@
+use super::*;
+use crate::cpu::CPU;
+use crate::loader::Segment;
+
+#[test]
+fn test_segments_and_vectors(){
+    let bytes = [
+        // $2000-$2001, then an INITAD segment without the optional marker
+        0xFF, 0xFF, 0x00, 0x20, 0x01, 0x20, 0x60, 0x60,
+        0xE2, 0x02, 0xE3, 0x02, 0x01, 0x20,
+        // $3000 and RUNAD with a repeated marker
+        0xFF, 0xFF, 0x00, 0x30, 0x00, 0x30, 0xEA,
+        0xE0, 0x02, 0xE1, 0x02, 0x00, 0x30,
+    ];
+    let image = parse(&bytes).unwrap();
+    assert_eq!(image.segments[0], Segment{ address : 0x2000, data : vec![0x60, 0x60], init : None });
+    assert_eq!(image.segments.len(), 4);
+    assert_eq!(image.segments[1].init, Some(0x2001));
+    assert_eq!(image.entry, Some(0x3000));
+
+    // without RUNAD the program starts at the first segment
+    let image = parse(&bytes[..14]).unwrap();
+    assert_eq!(image.entry, Some(0x2000));
+    let written = parse(&to_bytes(&image)).unwrap();
+    assert_eq!(written.segments[..2], image.segments[..]);
+    assert_eq!((written.segments[1].init, written.entry), (Some(0x2001), Some(0x2000)));
+}
+
+#[test]
+fn test_init_runs_before_next_segment(){
+    let bytes = [
+        // $0600: INC $0700; RTS, called through INITAD
+        0xFF, 0xFF, 0x00, 0x06, 0x03, 0x06, 0xEE, 0x00, 0x07, 0x60,
+        0xE2, 0x02, 0xE3, 0x02, 0x00, 0x06,
+        // the next stage overwrites the init routine with a JAM
+        0x00, 0x06, 0x00, 0x06, 0x02,
+        0xE0, 0x02, 0xE1, 0x02, 0x00, 0x06,
+    ];
+    let image = parse(&bytes).unwrap();
+    let mut cpu : CPU = CPU::new();
+    image.load(&mut cpu).unwrap();
+    assert_eq!(cpu.read_mem(0x0700).unwrap(), 1);
+    assert_eq!(cpu.read_mem(0x0600).unwrap(), 0x02);
+    image.start(&mut cpu).unwrap();
+    assert_eq!(cpu.pc(), 0x0600);
+}
+
+#[test]
+fn test_invalid_files(){
+    assert!(matches!(parse(&[0x00, 0x20, 0x00, 0x20, 0x60]), Err(Error::InvalidFormat(_))));
+    assert!(matches!(parse(&[0xFF, 0xFF, 0x00, 0x20, 0x05, 0x20, 0x60]), Err(Error::InvalidFormat(_))));
+    assert!(matches!(parse(&[0xFF, 0xFF, 0x01, 0x20, 0x00, 0x20]), Err(Error::InvalidFormat(_))));
+}